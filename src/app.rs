@@ -1,343 +1,863 @@
 use eframe::egui;
-use rapier2d::prelude::*;
-use nalgebra::{Vector2, Rotation2}; // Added Rotation2
-use rand::Rng; // Import random number generator
+use nalgebra::Vector2;
 
-use crate::creatures::snake::Snake; // Keep for initialization
-use crate::creatures::plankton::Plankton; // Import Plankton
-use crate::creature::{Creature, CreatureInfo, WorldContext}; // Added CreatureInfo and WorldContext explicitly
+use crate::arena::Contender;
+use crate::collider_tag::{ColliderTag, EntityKind};
+use crate::creature::{CreatureId, WorldContext};
+use crate::creatures::CreatureData;
+use crate::disease::Infection;
+use crate::fields::FieldGrid;
+use crate::glass::Glass;
+use crate::formation::Formation;
+use crate::impacts::ImpactKind;
+use crate::joint_profile::JointProfile;
+use crate::load_governor::LoadLevel;
+use crate::objectives::Objective;
+use crate::observer::{Observers, MAX_OBSERVATIONS};
+use crate::physics_presets::PhysicsPreset;
+use crate::player::PlayerInput;
+use crate::sim_thread::{SimCommand, SimulationRunner};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gait::{self, GaitSweep, GaitTrial};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scenario::Scenario;
+use crate::showcase::CameraPath;
+use crate::simulation::{standard_plankton, standard_snake, SegmentReadout, Simulation, WorldSnapshot, PIXELS_PER_METER, TIMESTEP, WALL_THICKNESS};
+use crate::signals::SignalKind;
+use crate::stats::PopulationHistory;
+use crate::summary;
 
-// Constants for the simulation world
-const PIXELS_PER_METER: f32 = 50.0;
-const WORLD_WIDTH_METERS: f32 = 20.0; // e.g., 1000 pixels / 50 px/m = 20m
-const WORLD_HEIGHT_METERS: f32 = 16.0; // e.g., 800 pixels / 50 px/m = 16m
-const WALL_THICKNESS: f32 = 0.5; // Half a meter thick walls
+/// How fast the arrow keys pan the view, in meters per second at zoom 1.
+const PAN_SPEED: f32 = 8.0;
+/// How much each +/- press zooms.
+const ZOOM_STEP: f32 = 1.25;
 
-// Unused for now, but keep for reference
-// const TIMESTEP: f32 = 1.0 / 60.0; // Run physics at 60Hz
+/// Margin left around whatever the view is fitted to, as a fraction of its size.
+const FIT_PADDING: f32 = 0.1;
+/// Smallest area, in meters, the view is fitted to, so fitting one small creature doesn't
+/// zoom all the way in.
+const FIT_MIN_SIZE: f32 = 2.0;
+
+/// How long a notification stays on screen, in seconds.
+const NOTIFICATION_SECONDS: f64 = 6.0;
+/// Cell size of the light overlay, in meters.
+const OVERLAY_CELL_SIZE: f32 = 0.5;
+
+/// Formations the spawn tool offers, with their starting settings.
+const SPAWN_FORMATIONS: [Formation; 3] = [
+    Formation::Line { spacing: 2.0, angle: std::f32::consts::FRAC_PI_2 },
+    Formation::Circle { radius: 2.0 },
+    Formation::School { spacing: 0.5, jitter: 0.2 },
+];
+
+/// Environmental field shaded over the tank, for debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldOverlay {
+    None,
+    Light,
+    Temperature,
+}
+
+impl FieldOverlay {
+    const ALL: [FieldOverlay; 3] = [FieldOverlay::None, FieldOverlay::Light, FieldOverlay::Temperature];
+
+    fn label(&self) -> &'static str {
+        match self {
+            FieldOverlay::None => "None",
+            FieldOverlay::Light => "Light",
+            FieldOverlay::Temperature => "Temperature",
+        }
+    }
+}
 
 pub struct SoftiesApp {
-    // Rapier physics world components
-    rigid_body_set: RigidBodySet,
-    collider_set: ColliderSet,
-    integration_parameters: IntegrationParameters,
-    physics_pipeline: PhysicsPipeline,
-    island_manager: IslandManager,
-    broad_phase: BroadPhaseMultiSap,
-    narrow_phase: NarrowPhase,
-    impulse_joint_set: ImpulseJointSet,
-    multibody_joint_set: MultibodyJointSet,
-    ccd_solver: CCDSolver,
-    query_pipeline: QueryPipeline, // Added query pipeline
-    physics_hooks: (), // No hooks for now
-    event_handler: (), // No events for now
-
-    // Creatures
-    creatures: Vec<Box<dyn Creature>>, // Changed from single snake
+    // The simulation runs elsewhere (its own thread on native); we only talk to it
+    runner: SimulationRunner,
+    // Front buffer: the latest snapshot we've received, drawn every frame
+    snapshot: WorldSnapshot,
 
     // View state (optional, for panning/zooming later)
     view_center: Vector2<f32>,
     zoom: f32,
+    // Scripted camera moves, followed until they end or the user moves the view
+    camera_path: Option<CameraPath>,
+    // Size of the tank view last frame, in points, for fitting things into it
+    canvas_size: egui::Vec2,
+    // Fit the tank into the view once its size is known
+    fit_tank_pending: bool,
 
     // UI State
     hovered_creature_id: Option<usize>,
+    // Creature picked from the list or with the keyboard
+    selected: Option<CreatureId>,
+    // Creature we're subscribed to for observations: the selected one, once the command is sent
+    watching: Option<CreatureId>,
+    show_infections: bool,
+    show_sounds: bool,
+    show_impacts: bool,
+    field_overlay: FieldOverlay,
+    // Light overlay for a tank of this size. Light never changes, so it's only built once.
+    light_overlay: Option<(Vector2<f32>, FieldGrid<f32>)>,
+    // Spawn tool settings
+    spawn_species: Contender,
+    spawn_count: usize,
+    spawn_formation: Formation,
+    // Last input sent for the player's creature, so we only send changes
+    player_input: PlayerInput,
+    // Completed objectives we've already shown a notification for
+    announced: Vec<Objective>,
+    // Tick of the newest anomaly we've shown a notification for
+    announced_anomaly: u64,
+    // Messages shown over the tank, with the time (in egui seconds) they disappear
+    notifications: Vec<(String, f64)>,
+    // Notebook entry being written, and the creature it will be about
+    note_draft: String,
+    note_creature: Option<CreatureId>,
+    // Shows only the classroom controls and plots
+    classroom: bool,
+    // A gait tuning run in the background: the creature, and where its results will arrive
+    #[cfg(not(target_arch = "wasm32"))]
+    gait_tuning: Option<(CreatureId, std::sync::mpsc::Receiver<Vec<GaitTrial>>)>,
 }
 
 impl Default for SoftiesApp {
     fn default() -> Self {
-        let mut rigid_body_set = RigidBodySet::new();
-        let mut collider_set = ColliderSet::new();
-        let mut impulse_joint_set = ImpulseJointSet::new();
-        let multibody_joint_set = MultibodyJointSet::new();
-        let query_pipeline = QueryPipeline::new(); // Initialize query pipeline
-
-        // --- Create Walls ---
-        let hw = WORLD_WIDTH_METERS / 2.0;
-        let hh = WORLD_HEIGHT_METERS / 2.0;
-        let wt = WALL_THICKNESS / 2.0;
-
-        // Floor
-        let floor_rb = RigidBodyBuilder::fixed().translation(vector![0.0, -hh - wt]).build();
-        let floor_handle = rigid_body_set.insert(floor_rb);
-        let floor_collider = ColliderBuilder::cuboid(hw + wt, wt).user_data(u128::MAX); // Assign high user_data to walls
-        collider_set.insert_with_parent(floor_collider, floor_handle, &mut rigid_body_set);
-
-        // Ceiling
-        let ceiling_rb = RigidBodyBuilder::fixed().translation(vector![0.0, hh + wt]).build();
-        let ceiling_handle = rigid_body_set.insert(ceiling_rb);
-        let ceiling_collider = ColliderBuilder::cuboid(hw + wt, wt).user_data(u128::MAX);
-        collider_set.insert_with_parent(ceiling_collider, ceiling_handle, &mut rigid_body_set);
-
-        // Left Wall
-        let left_wall_rb = RigidBodyBuilder::fixed().translation(vector![-hw - wt, 0.0]).build();
-        let left_wall_handle = rigid_body_set.insert(left_wall_rb);
-        let left_wall_collider = ColliderBuilder::cuboid(wt, hh + wt).user_data(u128::MAX);
-        collider_set.insert_with_parent(left_wall_collider, left_wall_handle, &mut rigid_body_set);
-
-        // Right Wall
-        let right_wall_rb = RigidBodyBuilder::fixed().translation(vector![hw + wt, 0.0]).build();
-        let right_wall_handle = rigid_body_set.insert(right_wall_rb);
-        let right_wall_collider = ColliderBuilder::cuboid(wt, hh + wt).user_data(u128::MAX);
-        collider_set.insert_with_parent(right_wall_collider, right_wall_handle, &mut rigid_body_set);
-
-
-        // --- Create Creatures ---
-        let mut creatures: Vec<Box<dyn Creature>> = Vec::new();
-        let mut creature_id_counter: u128 = 0;
-        let mut rng = rand::thread_rng(); // Initialize RNG
-
-        // --- Create Multiple Snakes ---
-        let num_snakes = 3;
-        let segment_radius = 5.0 / PIXELS_PER_METER;
-        let segment_spacing = 15.0 / PIXELS_PER_METER;
-        let margin = 2.0; // Keep snakes away from walls
-
-        for i in 0..num_snakes {
-            let mut snake = Snake::new(
-                segment_radius,
-                10, // Number of segments
-                segment_spacing,
-            );
-
-            // Adjust energy parameters for longer active periods
-            snake.attributes_mut().max_energy = 150.0; // Increased from 100.0
-            snake.attributes_mut().energy_recovery_rate = 8.0; // Increased from 5.0
-            snake.attributes_mut().metabolic_rate = 0.5; // Reduced from 1.0
-            snake.attributes_mut().energy = 150.0; // Start with full energy
-
-            // Calculate different starting positions for each snake
-            let initial_x = match i {
-                0 => -hw / 2.0, // Left side
-                1 => 0.0,       // Center
-                2 => hw / 2.0,  // Right side
-                _ => rng.gen_range((-hw + margin)..(hw - margin)), // Random for any additional snakes
-            };
-            let initial_y = match i {
-                0 => hh / 3.0,  // Upper third
-                1 => 0.0,       // Middle
-                2 => -hh / 3.0, // Lower third
-                _ => rng.gen_range((-hh + margin)..(hh - margin)), // Random for any additional snakes
-            };
-
-            snake.spawn_rapier(
-                &mut rigid_body_set,
-                &mut collider_set,
-                &mut impulse_joint_set,
-                Vector2::new(initial_x, initial_y),
-                creature_id_counter,
-            );
-            creatures.push(Box::new(snake));
-            creature_id_counter += 1;
-        }
-
-        // --- Create Plankton ---
-        let num_plankton = 20;
-        let plankton_radius = 4.0 / PIXELS_PER_METER; // Made smaller
-        for _ in 0..num_plankton {
-            let mut plankton = Plankton::new(plankton_radius);
-            // Random position
-            let margin = 1.0;
-            let initial_x = rng.gen_range((-hw + margin)..(hw - margin));
-            let initial_y = rng.gen_range((-hh + margin)..(hh - margin));
-            
-            plankton.spawn_rapier(
-                &mut rigid_body_set,
-                &mut collider_set,
-                &mut impulse_joint_set, // Pass joint set
-                Vector2::new(initial_x, initial_y),
-                creature_id_counter,
-            );
-            creatures.push(Box::new(plankton));
-            creature_id_counter += 1;
-        }
+        Self::new(Simulation::default())
+    }
+}
 
+impl SoftiesApp {
+    /// Starts the app on `simulation`, e.g. one built from a scenario file.
+    pub fn new(mut simulation: Simulation) -> Self {
+        let snapshot = simulation.snapshot();
+        let tick = snapshot.tick;
 
         Self {
-            rigid_body_set,
-            collider_set,
-            integration_parameters: IntegrationParameters::default(),
-            physics_pipeline: PhysicsPipeline::new(),
-            island_manager: IslandManager::new(),
-            broad_phase: BroadPhaseMultiSap::new(),
-            narrow_phase: NarrowPhase::new(),
-            impulse_joint_set,
-            multibody_joint_set,
-            ccd_solver: CCDSolver::new(),
-            query_pipeline, // Store query pipeline
-            physics_hooks: (),
-            event_handler: (),
-            creatures, // Store the vec containing snake and plankton
+            runner: SimulationRunner::start(simulation),
+            snapshot,
             view_center: Vector2::zeros(),
             zoom: 1.0,
+            camera_path: None,
+            canvas_size: egui::Vec2::ZERO,
+            fit_tank_pending: true,
             hovered_creature_id: None, // Initialize hover state
+            selected: None,
+            watching: None,
+            show_infections: false,
+            show_sounds: true,
+            show_impacts: true,
+            field_overlay: FieldOverlay::None,
+            light_overlay: None,
+            spawn_species: Contender::Plankton,
+            spawn_count: 10,
+            spawn_formation: SPAWN_FORMATIONS[2],
+            player_input: PlayerInput::default(),
+            announced: Vec::new(),
+            // Anything already in a loaded world is old news
+            announced_anomaly: tick,
+            notifications: Vec::new(),
+            note_draft: String::new(),
+            note_creature: None,
+            classroom: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            gait_tuning: None,
         }
     }
+
+    /// Has the camera follow `path`, timed by the simulation's clock.
+    pub fn with_camera_path(mut self, path: CameraPath) -> Self {
+        self.camera_path = Some(path);
+        self
+    }
 }
 
 impl SoftiesApp {
-    // Add the new tick_simulation method here, before eframe::App impl
-    pub fn tick_simulation(&mut self, dt: f32, _ctx: &egui::Context) {
-        // --- Creature Updates --- 
-        for creature in &mut self.creatures {
-            let is_this_creature_resting = creature.current_state() == crate::creature::CreatureState::Resting;
-            creature.attributes_mut().update_passive_stats(dt, is_this_creature_resting);
+    /// Keyboard control of the tank, so it can be used without a mouse. Ignored while a text
+    /// field has focus.
+    fn handle_keys(&mut self, ctx: &egui::Context, dt: f32) {
+        if ctx.wants_keyboard_input() {
+            return;
         }
+        let view = (self.view_center, self.zoom);
 
-        // --- Prepare CreatureInfo vector --- 
-        let mut all_creatures_info: Vec<CreatureInfo> = Vec::with_capacity(self.creatures.len());
-        for (_index, creature) in self.creatures.iter().enumerate() {
-            let creature_id = creature.id(); 
-            let type_name = creature.type_name();
-            let radius = creature.drawing_radius();
-            let primary_body_handle = creature.get_rigid_body_handles().first().cloned().unwrap_or_else(RigidBodyHandle::invalid);
-            
-            let (position, velocity) = if primary_body_handle != RigidBodyHandle::invalid() {
-                if let Some(body) = self.rigid_body_set.get(primary_body_handle) {
-                    (*body.translation(), *body.linvel())
-                } else {
-                    (Vector2::zeros(), Vector2::zeros())
-                }
-            } else {
-                (Vector2::zeros(), Vector2::zeros())
+        // N / Shift+N cycle the selection through the creatures and bring it into view
+        if ctx.input(|i| i.key_pressed(egui::Key::N)) && !self.snapshot.creatures.is_empty() {
+            let count = self.snapshot.creatures.len();
+            let current = self.selected.and_then(|id| self.snapshot.creatures.iter().position(|c| c.id() == id));
+            let next = match (current, ctx.input(|i| i.modifiers.shift)) {
+                (None, false) => 0,
+                (None, true) => count - 1,
+                (Some(index), false) => (index + 1) % count,
+                (Some(index), true) => (index + count - 1) % count,
             };
+            let creature = &self.snapshot.creatures[next];
+            self.selected = Some(creature.id());
+            if let Some(body) = creature.get_rigid_body_handles().first().and_then(|&h| self.snapshot.rigid_body_set.get(h)) {
+                self.view_center = *body.translation();
+            }
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::Enter)) && self.selected.is_some() {
+            self.runner.send(SimCommand::Possess(self.selected));
+        }
+
+        if self.snapshot.player.is_some() {
+            let input = ctx.input(|i| {
+                let axis = |positive: bool, negative: bool| positive as i32 as f32 - negative as i32 as f32;
+                PlayerInput {
+                    thrust: axis(
+                        i.key_down(egui::Key::W) || i.key_down(egui::Key::ArrowUp),
+                        i.key_down(egui::Key::S) || i.key_down(egui::Key::ArrowDown),
+                    ),
+                    turn: axis(
+                        i.key_down(egui::Key::A) || i.key_down(egui::Key::ArrowLeft),
+                        i.key_down(egui::Key::D) || i.key_down(egui::Key::ArrowRight),
+                    ),
+                    bite: i.key_down(egui::Key::Space),
+                }
+            });
+            if input != self.player_input {
+                self.runner.send(SimCommand::PlayerInput(input));
+                self.player_input = input;
+            }
+            if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.runner.send(SimCommand::Possess(None));
+            }
+        } else {
+            // The arrows belong to the player's creature while there is one
+            let pan = ctx.input(|i| {
+                let axis = |positive: egui::Key, negative: egui::Key| i.key_down(positive) as i32 as f32 - i.key_down(negative) as i32 as f32;
+                Vector2::new(axis(egui::Key::ArrowRight, egui::Key::ArrowLeft), axis(egui::Key::ArrowUp, egui::Key::ArrowDown))
+            });
+            self.view_center += pan * PAN_SPEED * dt / self.zoom;
+        }
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals) {
+                self.zoom *= ZOOM_STEP;
+            }
+            if i.key_pressed(egui::Key::Minus) {
+                self.zoom /= ZOOM_STEP;
+            }
+            if i.key_pressed(egui::Key::Home) {
+                self.view_center = Vector2::zeros();
+                self.zoom = 1.0;
+            }
+        });
+        if ctx.input(|i| i.key_pressed(egui::Key::T)) {
+            self.fit_tank();
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::F)) {
+            self.fit_selection();
+        }
+        // Any move of the user's own takes the camera off its path
+        if (self.view_center, self.zoom) != view {
+            self.camera_path = None;
+        }
+    }
+
+    /// Rings the segment under the pointer and shows its physics values beside it.
+    fn segment_tooltip(&self, ctx: &egui::Context, painter: &egui::Painter, world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2, readout: SegmentReadout) {
+        let part = readout.part;
+        let Some(creature) = self.snapshot.creatures.iter().find(|c| c.id() == part.creature) else { return };
+        let radius = creature
+            .get_rigid_body_handles()
+            .get(part.segment as usize)
+            .and_then(|&handle| self.snapshot.rigid_body_set.get(handle)?.colliders().first().copied())
+            .and_then(|collider| self.snapshot.collider_set.get(collider))
+            .map_or(0.1, |collider| collider.shape().compute_local_bounding_sphere().radius);
+        painter.circle_stroke(world_to_screen(readout.position), radius * PIXELS_PER_METER * self.zoom + 2.0, egui::Stroke::new(1.5, egui::Color32::WHITE));
 
-            all_creatures_info.push(CreatureInfo {
-                id: creature_id,
-                creature_type_name: type_name,
-                primary_body_handle,
-                position,
-                velocity,
-                radius,
+        let parasite_load = creature.attributes().parasite_load;
+        let personality = creature.attributes().personality;
+        egui::show_tooltip_at_pointer(ctx, egui::Id::new("body_part_tooltip"), |ui| {
+            ui.label(format!("{} {}\nSegment {} ({:?})", creature.type_name(), part.creature, part.segment, part.role));
+            ui.label(format!(
+                "Boldness {:.2}, activity {:.2}, sociality {:.2}",
+                personality.boldness, personality.activity, personality.sociality
+            ));
+            if parasite_load > 0.0 {
+                ui.label(format!("Parasite load {:.0}", parasite_load));
+            }
+            ui.label(format!("Position: ({:.2}, {:.2}) m", readout.position.x, readout.position.y));
+            ui.label(format!("Velocity: ({:.2}, {:.2}) m/s", readout.velocity.x, readout.velocity.y));
+            ui.label(format!("Force last tick: ({:.2}, {:.2}) N", readout.force.x, readout.force.y));
+            ui.label(match readout.joint_angle {
+                Some(angle) => format!("Bend from segment {}: {:.1}°", part.segment - 1, angle.to_degrees()),
+                None => "First segment: no joint ahead".to_string(),
             });
+        });
+    }
+
+    /// Centers and zooms the view so the box from `low` to `high` fills it, with some padding.
+    fn fit_view(&mut self, (low, high): (Vector2<f32>, Vector2<f32>)) {
+        if self.canvas_size.x <= 0.0 || self.canvas_size.y <= 0.0 {
+            return;
         }
+        let size = (high - low).map(|extent| extent.max(FIT_MIN_SIZE)) * (1.0 + FIT_PADDING);
+        self.view_center = (low + high) / 2.0;
+        self.zoom = (self.canvas_size.x / size.x).min(self.canvas_size.y / size.y) / PIXELS_PER_METER;
+    }
 
-        // Decide state and apply behavior
-        for creature in &mut self.creatures {
-            let world_context = WorldContext { 
-                world_height: WORLD_HEIGHT_METERS,
-                pixels_per_meter: PIXELS_PER_METER, 
-            };
-            
-            let own_id = creature.id();
-
-            creature.update_state_and_behavior(
-                dt, 
-                own_id, 
-                &mut self.rigid_body_set, 
-                &mut self.impulse_joint_set,
-                &self.collider_set, 
-                &self.query_pipeline,
-                &all_creatures_info, 
-                &world_context,
-            );
+    /// Fits the whole tank, walls included, into the view.
+    fn fit_tank(&mut self) {
+        if let Some(bounds) = self.snapshot.wall_bounds() {
+            self.fit_view(bounds);
         }
+    }
 
-        // --- Apply Custom Physics Forces --- 
-        let world_context_for_forces = crate::creature::WorldContext {
-            world_height: WORLD_HEIGHT_METERS,
-            pixels_per_meter: PIXELS_PER_METER,
-        };
-        for creature in &self.creatures { 
-            creature.apply_custom_forces(&mut self.rigid_body_set, &world_context_for_forces);
+    /// Fits the selected creature, and the one being controlled, into the view.
+    fn fit_selection(&mut self) {
+        let ids: Vec<CreatureId> = self.selected.into_iter().chain(self.snapshot.player).collect();
+        if let Some(bounds) = self.snapshot.creature_bounds(&ids) {
+            self.fit_view(bounds);
+        }
+    }
+
+    /// Moves the view along the camera path, if there is one, and drops the path at its end.
+    fn follow_camera_path(&mut self) {
+        let Some(path) = &self.camera_path else { return };
+        let seconds = self.snapshot.tick as f32 * TIMESTEP;
+        if let Some((center, zoom)) = path.at(seconds) {
+            self.view_center = center;
+            self.zoom = zoom;
+        }
+        if seconds >= path.end() {
+            self.camera_path = None;
+        }
+    }
+
+    /// Sliders for how stiff a jointed creature is at its head and its tail.
+    fn body_editor(&mut self, ui: &mut egui::Ui, id: CreatureId) {
+        let Some(mut profile) = self.snapshot.creatures.iter().find(|c| c.id() == id).and_then(|c| c.joint_profile()) else { return };
+        let before = profile;
+        ui.collapsing("Body", |ui| {
+            for (label, setting) in [("Head", &mut profile.head), ("Tail", &mut profile.tail)] {
+                ui.label(label);
+                ui.add(egui::Slider::new(&mut setting.stiffness, 0.0..=1.0).text("Stiffness"));
+                ui.add(egui::Slider::new(&mut setting.damping, 0.0..=0.5).text("Damping"));
+                ui.add(egui::Slider::new(&mut setting.limit, 0.0..=0.3).text("Bend limit (rad)"));
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Tapered").on_hover_text("Firm behind the head, floppy tail").clicked() {
+                    profile = JointProfile::tapered();
+                }
+                if ui.button("Even").on_hover_text("The head's setting all along the body").clicked() {
+                    profile = JointProfile::uniform(profile.head);
+                }
+            });
+            #[cfg(not(target_arch = "wasm32"))]
+            self.gait_tuner(ui, id);
+        });
+        if profile != before {
+            self.runner.send(SimCommand::SetJointProfile { creature: id, profile });
         }
+    }
 
-        // --- Physics Step --- 
-        self.physics_pipeline.step(
-            &Vector2::new(0.0, -1.0), 
-            &self.integration_parameters,
-            &mut self.island_manager,
-            &mut self.broad_phase,
-            &mut self.narrow_phase,
-            &mut self.rigid_body_set,
-            &mut self.collider_set,
-            &mut self.impulse_joint_set,
-            &mut self.multibody_joint_set,
-            &mut self.ccd_solver,
-            None, 
-            &self.physics_hooks,
-            &self.event_handler,
-        );
-
-        // --- Failsafe: Check for Escaped Creatures ---
-        let world_half_width = WORLD_WIDTH_METERS / 2.0;
-        let world_half_height = WORLD_HEIGHT_METERS / 2.0;
-        let bounds_padding = 1.0;
-
-        for (id, creature) in self.creatures.iter().enumerate() { 
-            let mut is_out_of_bounds = false;
-            for &body_handle in creature.get_rigid_body_handles() {
-                if let Some(body) = self.rigid_body_set.get(body_handle) {
-                    let pos = body.translation();
-                    if pos.x.abs() > world_half_width + bounds_padding || 
-                       pos.y.abs() > world_half_height + bounds_padding {
-                        is_out_of_bounds = true;
-                        break; 
+    /// A slider for each of the creature's tunable parameters.
+    fn tuning_panel(&mut self, ui: &mut egui::Ui, id: CreatureId) {
+        let Some(creature) = self.snapshot.creatures.iter().find(|c| c.id() == id) else { return };
+        let tunables = creature.tunables();
+        if tunables.is_empty() {
+            return;
+        }
+        let mut changed = Vec::new();
+        ui.collapsing("Tuning", |ui| {
+            for tunable in tunables {
+                let mut value = tunable.value;
+                if ui.add(egui::Slider::new(&mut value, tunable.min..=tunable.max).text(tunable.name)).changed() {
+                    changed.push((tunable.name, value));
+                }
+            }
+        });
+        for (name, value) in changed {
+            self.runner.send(SimCommand::SetTunable { creature: id, name: name.to_string(), value });
+        }
+    }
+
+    /// Picks a species, a count and a formation, and spawns the group in the middle of the view.
+    fn spawn_panel(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Spawn group", |ui| {
+            egui::ComboBox::from_label("Species")
+                .selected_text(self.spawn_species.label())
+                .show_ui(ui, |ui| {
+                    for option in Contender::ALL {
+                        ui.selectable_value(&mut self.spawn_species, option, option.label());
+                    }
+                });
+            ui.add(egui::Slider::new(&mut self.spawn_count, 1..=40).text("Count"));
+            egui::ComboBox::from_label("Formation")
+                .selected_text(self.spawn_formation.label())
+                .show_ui(ui, |ui| {
+                    for option in SPAWN_FORMATIONS {
+                        if ui.selectable_label(self.spawn_formation.label() == option.label(), option.label()).clicked() {
+                            self.spawn_formation = option;
+                        }
                     }
+                });
+            match &mut self.spawn_formation {
+                Formation::Line { spacing, angle } => {
+                    ui.add(egui::Slider::new(spacing, 0.2..=5.0).text("Spacing (m)"));
+                    ui.add(egui::Slider::new(angle, -std::f32::consts::PI..=std::f32::consts::PI).text("Angle (rad)"));
+                }
+                Formation::Circle { radius } => {
+                    ui.add(egui::Slider::new(radius, 0.5..=6.0).text("Radius (m)"));
                 }
+                Formation::School { spacing, jitter } => {
+                    ui.add(egui::Slider::new(spacing, 0.2..=3.0).text("Spacing (m)"));
+                    ui.add(egui::Slider::new(jitter, 0.0..=0.5).text("Jitter"));
+                }
+            }
+            if ui.button("Spawn at view center").on_hover_text("Spots too close to something are skipped").clicked() {
+                let creature = match self.spawn_species {
+                    Contender::Snake => CreatureData::Snake(standard_snake()),
+                    Contender::Plankton => CreatureData::Plankton(standard_plankton()),
+                };
+                self.runner.send(SimCommand::SpawnGroup { creature: Box::new(creature), count: self.spawn_count, center: self.view_center, formation: self.spawn_formation });
             }
+        });
+    }
 
-            if is_out_of_bounds {
-                eprintln!(
-                    "WARN: Creature ID {} (Type: {}) escaped bounds and was reset!",
-                    id, 
-                    creature.type_name()
-                );
-                for &body_handle in creature.get_rigid_body_handles() {
-                    if let Some(body) = self.rigid_body_set.get_mut(body_handle) {
-                        body.set_translation(Vector2::zeros(), true);
-                        body.set_linvel(Vector2::zeros(), true);
-                        body.set_angvel(0.0, true);
+    /// Empties the tank, all of it or one species at a time, or starts it over.
+    fn tank_panel(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Tank", |ui| {
+            let species: std::collections::BTreeSet<&'static str> = self.snapshot.creatures.iter().map(|creature| creature.type_name()).collect();
+            ui.horizontal_wrapped(|ui| {
+                if ui.button("Clear all").clicked() {
+                    self.runner.send(SimCommand::ClearAll);
+                }
+                for type_name in species {
+                    if ui.button(format!("Clear {}", type_name)).clicked() {
+                        self.runner.send(SimCommand::ClearSpecies(type_name.to_string()));
                     }
                 }
+            });
+            if ui.button("Reset to scenario").on_hover_text("Rebuild the tank as it started, keeping the settings").clicked() {
+                self.runner.send(SimCommand::ResetToScenario);
+                // Ids start over, so whatever was picked would be something else
+                self.selected = None;
+                self.watching = None;
+            }
+        });
+    }
+
+    /// A button that checks the world's health, and what the last check found, to copy into
+    /// a bug report.
+    fn self_test_panel(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Self-test", |ui| {
+            if ui.button("Run self-test").on_hover_text("Check energy, joints, bodies and sensing on the live world").clicked() {
+                self.runner.send(SimCommand::RunSelfTest);
+            }
+            let Some(report) = &self.snapshot.panels.self_test else { return };
+            let color = if report.passed() { egui::Color32::LIGHT_GREEN } else { egui::Color32::LIGHT_RED };
+            ui.colored_label(color, report.summary());
+            for check in &report.checks {
+                ui.small(format!("{} {}: {}", if check.passed { "✔" } else { "✘" }, check.name, check.detail));
+            }
+            if ui.button("Copy report").clicked() {
+                ui.output_mut(|o| o.copied_text = report.to_text());
             }
+        });
+    }
+
+    /// Shows a creature's gait, and a button that tunes it in the background.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn gait_tuner(&mut self, ui: &mut egui::Ui, id: CreatureId) {
+        let Some(creature) = self.snapshot.creatures.iter().find(|c| c.id() == id) else { return };
+        let Some(gait) = creature.gait() else { return };
+        ui.label(format!("Gait: amplitude {:.1}, frequency {:.1}, wave {:.1}", gait.amplitude, gait.frequency, gait.wave_length));
+        if self.gait_tuning.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Tuning...");
+            });
+        } else if ui.button("Tune gait").on_hover_text("Try many gaits on a copy and keep the one that swims furthest per unit of energy").clicked() {
+            let data = creature.to_data();
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = sender.send(gait::tune(&data, &GaitSweep::default()));
+            });
+            self.gait_tuning = Some((id, receiver));
         }
+    }
 
-        // --- UI Panel and Drawing --- 
-        // These parts will remain in the eframe::App::update method
-        // as they interact directly with egui panels and painters.
+    /// Applies the best gait once a background tuning run finishes, and says how it did.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn finish_gait_tuning(&mut self, now: f64) {
+        let Some((id, receiver)) = &self.gait_tuning else { return };
+        let trials = match receiver.try_recv() {
+            Ok(trials) => trials,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Vec::new(),
+        };
+        let id = *id;
+        self.gait_tuning = None;
+        let Some(best) = trials.first() else {
+            self.notifications.push(("Gait tuning failed".to_string(), now + NOTIFICATION_SECONDS));
+            return;
+        };
+        self.runner.send(SimCommand::SetGait { creature: id, gait: best.gait });
+        let current = self.snapshot.creatures.iter().find(|c| c.id() == id).and_then(|c| c.gait());
+        let before = trials.iter().find(|trial| Some(trial.gait) == current);
+        let message = match before {
+            Some(before) => format!("Tuned creature {}: {:.2} m per energy, was {:.2}", id, best.efficiency, before.efficiency),
+            None => format!("Tuned creature {}: {:.2} m per energy", id, best.efficiency),
+        };
+        self.notifications.push((message, now + NOTIFICATION_SECONDS));
+    }
 
-        // Request redraw for animation (can also be in tick_simulation if preferred)
-        // For now, let's keep it here, but it will be called by the main update loop.
-        // ctx.request_repaint(); 
-        // Actually, this should probably be in the main update function, 
-        // as tick_simulation is just about the logic.
+    /// Writes the selected creature as a demo scenario in the working directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_demo(&mut self, now: f64) {
+        let Some(demo) = self.selected.and_then(|id| Scenario::demo_of(&self.snapshot, id)) else { return };
+        let path = demo.file_name();
+        let result = demo.encode().map_err(|err| err.to_string()).and_then(|bytes| std::fs::write(&path, bytes).map_err(|err| err.to_string()));
+        let message = match result {
+            Ok(()) => format!("Exported demo to {} (open it with --open)", path),
+            Err(err) => format!("Couldn't export demo: {}", err),
+        };
+        self.notifications.push((message, now + NOTIFICATION_SECONDS));
+    }
+
+    /// The few controls a predator-prey lesson needs, and what they do to the populations.
+    fn classroom_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Experiment");
+        let snakes = self.snapshot.creatures.iter().filter(|creature| creature.type_name() == "Snake").count();
+        let mut predators = snakes;
+        if ui.add(egui::Slider::new(&mut predators, 0..=10).text("Predators")).on_hover_text("Number of snakes in the tank").changed() {
+            self.runner.send(SimCommand::SetSnakeCount(predators));
+        }
+        let mut food = self.snapshot.food_abundance;
+        if ui.add(egui::Slider::new(&mut food, 0.0..=2.0).text("Food")).on_hover_text("How many plankton the tank can feed").changed() {
+            self.runner.send(SimCommand::SetFoodAbundance(food));
+        }
+        let mut temperature = self.snapshot.temperature_offset;
+        let slider = egui::Slider::new(&mut temperature, -10.0..=10.0).text("Temperature").suffix(" °C");
+        if ui.add(slider).on_hover_text("Warmer water hatches eggs sooner").changed() {
+            self.runner.send(SimCommand::SetTemperatureOffset(temperature));
+        }
+        let mut turbulence = self.snapshot.flow.turbulence;
+        let slider = egui::Slider::new(&mut turbulence, 0.0..=0.5).text("Turbulence").suffix(" m/s");
+        if ui.add(slider).on_hover_text("How strongly the water swirls, carrying plankton with it").changed() {
+            self.runner.send(SimCommand::SetTurbulence(turbulence));
+        }
+        ui.separator();
+
+        ui.heading("Populations");
+        population_plot(ui, &self.snapshot.panels.population);
+    }
+}
+
+/// Line colors for the population plot, by type.
+fn series_color(type_name: &str) -> egui::Color32 {
+    match type_name {
+        "Snake" => egui::Color32::from_rgb(230, 110, 90),
+        "Plankton" => egui::Color32::from_rgb(120, 210, 120),
+        _ => egui::Color32::LIGHT_GRAY,
     }
 }
 
+/// A line per creature type, count against time, with the latest counts as a legend.
+fn population_plot(ui: &mut egui::Ui, history: &PopulationHistory) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 140.0), egui::Sense::hover());
+    response.widget_info(|| {
+        let counts: Vec<String> = history.type_names().iter().map(|name| format!("{} {}", name, history.latest(name))).collect();
+        egui::WidgetInfo::labeled(egui::WidgetType::Other, format!("Population plot: {}", counts.join(", ")))
+    });
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    if history.is_empty() {
+        painter.text(rect.center(), egui::Align2::CENTER_CENTER, "Collecting...", egui::FontId::proportional(12.0), egui::Color32::GRAY);
+        return;
+    }
+
+    let all: Vec<(&String, Vec<(f32, u32)>)> = history.type_names().iter().map(|name| (name, history.series(name))).collect();
+    let (start, end) = all[0].1.first().zip(all[0].1.last()).map_or((0.0, 1.0), |(first, last)| (first.0, last.0.max(first.0 + 1.0)));
+    let top = all.iter().flat_map(|(_, series)| series.iter().map(|&(_, count)| count)).max().unwrap_or(0).max(1) as f32;
+    let to_screen = |seconds: f32, count: u32| {
+        egui::pos2(
+            rect.left() + (seconds - start) / (end - start) * rect.width(),
+            rect.bottom() - count as f32 / top * (rect.height() - 4.0),
+        )
+    };
+    for (name, series) in &all {
+        let points: Vec<egui::Pos2> = series.iter().map(|&(seconds, count)| to_screen(seconds, count)).collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, series_color(name))));
+    }
+    painter.text(rect.left_top() + egui::vec2(4.0, 2.0), egui::Align2::LEFT_TOP, format!("{}", top), egui::FontId::proportional(10.0), egui::Color32::GRAY);
+
+    ui.horizontal_wrapped(|ui| {
+        for (name, _) in &all {
+            ui.colored_label(series_color(name), format!("{} {}", name, history.latest(name)));
+        }
+        let span = end - start;
+        ui.weak(if span < 120.0 { format!("last {:.0} s", span) } else { format!("last {:.0} min", span / 60.0) });
+    });
+}
+
+/// What the observer stream has seen of a creature lately: its last decision and an energy trace.
+fn watch_panel(ui: &mut egui::Ui, observers: &Observers, creature: CreatureId) {
+    let Some(latest) = observers.latest(creature) else { return };
+    match latest.last_decision {
+        Some(decision) => ui.weak(format!(
+            "Last decision: {:?} → {:?}, {:.0} s ago",
+            decision.from,
+            decision.to,
+            (latest.tick - decision.tick) as f32 * TIMESTEP
+        )),
+        None => ui.weak("No decisions seen yet"),
+    };
+
+    let energy: Vec<f32> = observers.since(creature, 0).map(|observation| observation.energy).collect();
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 30.0), egui::Sense::hover());
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, format!("Energy trace, latest {:.1}", latest.energy)));
+    let top = energy.iter().copied().fold(1.0, f32::max);
+    let step = rect.width() / (MAX_OBSERVATIONS - 1) as f32;
+    let points: Vec<egui::Pos2> = energy
+        .iter()
+        .enumerate()
+        .map(|(index, value)| egui::pos2(rect.left() + index as f32 * step, rect.bottom() - value / top * rect.height()))
+        .collect();
+    ui.painter_at(rect).add(egui::Shape::line(points, egui::Stroke::new(1.0, egui::Color32::LIGHT_GREEN)));
+}
+
 impl eframe::App for SoftiesApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.show(ctx);
+    }
+}
+
+impl SoftiesApp {
+    /// Draws one frame of the whole UI; split from `update` so it runs without a window.
+    fn show(&mut self, ctx: &egui::Context) {
         // Set dark theme explicitly
         ctx.set_visuals(egui::Visuals::dark());
 
         // Get delta time
         let dt = ctx.input(|i| i.stable_dt);
 
-        // Run the core simulation logic
-        self.tick_simulation(dt, ctx);
+        // Pick up the newest simulation state, if there is one. Otherwise keep drawing the last.
+        if let Some(snapshot) = self.runner.poll(dt) {
+            self.snapshot = snapshot;
+        }
+
+        // Follow the selected creature through the observer stream
+        if self.watching != self.selected {
+            if let Some(old) = self.watching {
+                self.runner.send(SimCommand::Unsubscribe(old));
+            }
+            if let Some(new) = self.selected {
+                self.runner.send(SimCommand::Subscribe(new));
+            }
+            self.watching = self.selected;
+        }
+
+        // Announce objectives completed since the last frame
+        let now = ctx.input(|i| i.time);
+        for active in self.snapshot.panels.objectives.iter().filter(|active| active.is_complete()) {
+            if !self.announced.contains(&active.objective) {
+                self.announced.push(active.objective.clone());
+                self.notifications.push((format!("Goal complete: {}", active.objective.description()), now + NOTIFICATION_SECONDS));
+            }
+        }
+        for anomaly in self.snapshot.panels.anomalies.iter().filter(|anomaly| anomaly.tick > self.announced_anomaly) {
+            let message = match &anomaly.dump {
+                Some(path) => format!("Physics anomaly at tick {}, saved to {}", anomaly.tick, path.display()),
+                None => format!("Physics anomaly at tick {}", anomaly.tick),
+            };
+            // Invariant violations can run to many lines; the first is enough here
+            let detail = anomaly.detail.lines().next().unwrap_or_default();
+            self.notifications.push((format!("{}: {}", message, detail), now + NOTIFICATION_SECONDS));
+        }
+        if let Some(anomaly) = self.snapshot.panels.anomalies.last() {
+            self.announced_anomaly = self.announced_anomaly.max(anomaly.tick);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.finish_gait_tuning(now);
+        self.notifications.retain(|(_, until)| *until > now);
+
+        self.handle_keys(ctx, dt);
+        self.follow_camera_path();
 
         // --- UI Panel --- 
         egui::SidePanel::left("creature_list_panel")
             .resizable(true)
             .default_width(150.0)
             .show(ctx, |ui| {
+                ui.checkbox(&mut self.classroom, "Classroom mode");
+                ui.label(summary::tank_summary(&self.snapshot));
+                if let Some(description) = self.selected.and_then(|id| summary::creature_summary(&self.snapshot, id)) {
+                    ui.label(description);
+                    if let Some(id) = self.selected {
+                        watch_panel(ui, &self.snapshot.panels.observers, id);
+                        self.body_editor(ui, id);
+                        self.tuning_panel(ui, id);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Export as demo").on_hover_text("Save this creature alone in a small tank").clicked() {
+                        self.export_demo(now);
+                    }
+                }
+                if self.classroom {
+                    self.classroom_panel(ui);
+                    return;
+                }
+                ui.separator();
+
+                ui.collapsing("Settings", |ui| {
+                    let mut preset = self.snapshot.physics_preset;
+                    egui::ComboBox::from_label("Physics")
+                        .selected_text(preset.label())
+                        .show_ui(ui, |ui| {
+                            for option in PhysicsPreset::ALL {
+                                ui.selectable_value(&mut preset, option, option.label());
+                            }
+                        });
+                    if preset != self.snapshot.physics_preset {
+                        self.runner.send(SimCommand::SetPhysicsPreset(preset));
+                    }
+
+                    let mut virulence = self.snapshot.virulence;
+                    if ui.add(egui::Slider::new(&mut virulence, 0.0..=1.0).text("Virulence")).changed() {
+                        self.runner.send(SimCommand::SetVirulence(virulence));
+                    }
+                    ui.checkbox(&mut self.show_infections, "Show infections");
+                    ui.checkbox(&mut self.show_sounds, "Show calls and noises");
+                    ui.checkbox(&mut self.show_impacts, "Show impact sparks");
+                    egui::ComboBox::from_label("Field overlay")
+                        .selected_text(self.field_overlay.label())
+                        .show_ui(ui, |ui| {
+                            for option in FieldOverlay::ALL {
+                                ui.selectable_value(&mut self.field_overlay, option, option.label());
+                            }
+                        });
+
+                    let mut directed = self.snapshot.panels.director.is_some();
+                    let toggle = ui.checkbox(&mut directed, "Auto-balance ecosystem");
+                    if toggle.on_hover_text("Nudges plankton productivity and predator metabolism to keep both going").changed() {
+                        self.runner.send(SimCommand::SetDirector(directed));
+                    }
+                    if let Some(director) = self.snapshot.panels.director.as_ref().filter(|director| !director.log().is_empty()) {
+                        ui.collapsing("Interventions", |ui| {
+                            for intervention in director.log().iter().rev() {
+                                let seconds = (intervention.tick as f32 * crate::simulation::TIMESTEP) as u64;
+                                ui.small(format!("{}:{:02} {}", seconds / 60, seconds % 60, intervention.message));
+                            }
+                        });
+                    }
+                });
+                self.spawn_panel(ui);
+                self.tank_panel(ui);
+                self.self_test_panel(ui);
+                ui.collapsing("Goals", |ui| {
+                    for objective in Objective::suggestions() {
+                        let active = self.snapshot.panels.objectives.iter().find(|active| active.objective == objective);
+                        let mut enabled = active.is_some();
+                        if ui.checkbox(&mut enabled, objective.description()).changed() {
+                            self.runner.send(if enabled {
+                                SimCommand::AddObjective(objective.clone())
+                            } else {
+                                SimCommand::RemoveObjective(objective.clone())
+                            });
+                            self.announced.retain(|announced| *announced != objective);
+                        }
+                        if let Some(active) = active {
+                            let text = if active.is_complete() { "Done!".to_string() } else { format!("{:.0}%", active.progress * 100.0) };
+                            ui.add(egui::ProgressBar::new(active.progress).text(text));
+                        }
+                    }
+                });
+                let mut note_hovered: Option<CreatureId> = None;
+                ui.collapsing("Notebook", |ui| {
+                    let label = ui.label("New note");
+                    let response = ui
+                        .add(egui::TextEdit::multiline(&mut self.note_draft).hint_text("Observation").desired_rows(2))
+                        .labelled_by(label.id);
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter) && i.modifiers.command);
+                    egui::ComboBox::from_label("About")
+                        .selected_text(self.note_creature.map_or("Nothing".to_string(), |id| format!("Creature {}", id)))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.note_creature, None, "Nothing");
+                            for creature in &self.snapshot.creatures {
+                                let label = format!("{} {}", creature.type_name(), creature.id());
+                                ui.selectable_value(&mut self.note_creature, Some(creature.id()), label);
+                            }
+                        });
+                    if ui.button("Add note").clicked() || submitted {
+                        let text = std::mem::take(&mut self.note_draft);
+                        self.runner.send(SimCommand::AddNote { text, creature: self.note_creature });
+                    }
+
+                    for note in self.snapshot.panels.notebook.notes() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.weak(note.timestamp());
+                            if let Some(creature) = note.creature {
+                                // Hovering the link highlights the creature in the tank
+                                let link = ui.link(format!("#{}", creature));
+                                link.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Link, format!("Creature {}", creature)));
+                                if link.hovered() {
+                                    note_hovered = Some(creature);
+                                }
+                            }
+                            ui.label(&note.text);
+                            let delete = ui.small_button("x").on_hover_text("Delete note");
+                            delete.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, "Delete note"));
+                            if delete.clicked() {
+                                self.runner.send(SimCommand::RemoveNote(note.id));
+                            }
+                        });
+                    }
+                    if !self.snapshot.panels.notebook.notes().is_empty() && ui.button("Copy as Markdown").clicked() {
+                        ui.output_mut(|o| o.copied_text = self.snapshot.panels.notebook.to_markdown());
+                    }
+                });
+                ui.separator();
+
+                if let Some(player) = self.snapshot.player {
+                    ui.label(format!("Controlling creature {}", player));
+                    ui.small("W/S or arrows: swim\nA/D: turn\nSpace: bite\nEsc: let go");
+                    if ui.button("Let go").clicked() {
+                        self.runner.send(SimCommand::Possess(None));
+                    }
+                } else {
+                    ui.small("Click a creature to select it, Ctrl+click to control it");
+                }
+                ui.small("N / Shift+N: select next / previous\nEnter: control selected\nArrows: pan, +/-: zoom, Home: reset view\nT: fit tank, F: fit selection");
+                ui.horizontal(|ui| {
+                    if ui.button("Fit tank").clicked() {
+                        self.camera_path = None;
+                        self.fit_tank();
+                    }
+                    let has_selection = self.selected.is_some() || self.snapshot.player.is_some();
+                    if ui.add_enabled(has_selection, egui::Button::new("Fit selection")).clicked() {
+                        self.camera_path = None;
+                        self.fit_selection();
+                    }
+                });
+                ui.separator();
+
                 ui.heading("Creatures");
                 ui.separator();
 
                 let mut currently_hovered: Option<usize> = None;
-                for (id, creature) in self.creatures.iter().enumerate() {
+                for (id, creature) in self.snapshot.creatures.iter().enumerate() {
                     let label_text = format!(
                         "ID: {}\nType: {}\nState: {:?}", 
-                        id, 
+                        creature.id(), 
                         creature.type_name(),
                         creature.current_state()
                     );
                     // Use selectable label for hover detection
-                    let response = ui.selectable_label(false, label_text);
-                    if response.hovered() {
+                    let response = ui.selectable_label(self.selected == Some(creature.id()), label_text);
+                    if response.hovered() || response.has_focus() {
                         currently_hovered = Some(id);
                     }
+                    if response.clicked() {
+                        self.selected = Some(creature.id());
+                    }
                     ui.separator();
                 }
+                if let Some(creature) = note_hovered {
+                    currently_hovered = self.snapshot.creatures.iter().position(|c| c.id() == creature);
+                }
                 // Update the app state *after* checking all labels
                 self.hovered_creature_id = currently_hovered;
             });
@@ -346,6 +866,15 @@ impl eframe::App for SoftiesApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             let painter = ui.painter();
             let available_rect = ui.available_rect_before_wrap();
+            self.canvas_size = available_rect.size();
+            // Small windows may not show the whole tank at zoom 1
+            if self.fit_tank_pending && self.snapshot.wall_bounds().is_some() {
+                self.fit_tank_pending = false;
+                self.fit_tank();
+            }
+            // Screen readers can't see the drawing, so the canvas reads out the tank summary
+            let canvas = ui.interact(available_rect, ui.id().with("tank"), egui::Sense::hover());
+            canvas.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, summary::tank_summary(&self.snapshot)));
 
             // Simple world-to-screen transformation
             let world_to_screen = |world_pos: Vector2<f32>| -> egui::Pos2 {
@@ -363,51 +892,178 @@ impl eframe::App for SoftiesApp {
                 let screen_center = available_rect.center();
                 egui::pos2(screen_center.x + pixel_pt.x, screen_center.y - pixel_pt.y) // Invert Y here
             };
+            // ...and back again, for the pointer
+            let screen_to_world = |screen_pos: egui::Pos2| -> Vector2<f32> {
+                let offset = screen_pos - available_rect.center();
+                let pixels_per_world_unit = PIXELS_PER_METER * self.zoom;
+                self.view_center + Vector2::new(offset.x, -offset.y) / pixels_per_world_unit
+            };
 
-            // --- Draw Walls ---
-            for (_collider_handle, collider) in self.collider_set.iter() { // Renamed handle to _collider_handle as it's not used directly here for fetching body
-                if collider.user_data == u128::MAX { // Corrected: user_data is a field
-                    if let Some(rigid_body_handle) = collider.parent() { // Get the parent RigidBodyHandle
-                        if let Some(body) = self.rigid_body_set.get(rigid_body_handle) { // Use the RigidBodyHandle
-                            let position = body.translation();
-                            let rotation_angle = body.rotation().angle();
-
-                            if let Some(cuboid) = collider.shape().as_cuboid() {
-                                let half_extents = cuboid.half_extents;
-                                // Helper to create rotated points
-                                let create_rotated_point = |x_offset, y_offset| -> Vector2<f32> {
-                                    Rotation2::new(rotation_angle) * Vector2::new(x_offset, y_offset)
-                                };
-
-                                let screen_points = [
-                                    world_to_screen(*position + create_rotated_point(-half_extents.x, -half_extents.y)),
-                                    world_to_screen(*position + create_rotated_point(half_extents.x, -half_extents.y)),
-                                    world_to_screen(*position + create_rotated_point(half_extents.x, half_extents.y)),
-                                    world_to_screen(*position + create_rotated_point(-half_extents.x, half_extents.y)),
-                                ];
-
-                                painter.add(egui::Shape::closed_line(
-                                    screen_points.to_vec(),
-                                    egui::Stroke::new(2.0, egui::Color32::GRAY)
-                                ));
-                            }
-                        }
+            // The body part under the pointer, if any
+            let pointer_part = ctx
+                .pointer_hover_pos()
+                .filter(|pos| available_rect.contains(*pos))
+                .and_then(|pos| self.snapshot.body_part_at(screen_to_world(pos)));
+
+            // --- Selection and Player Control ---
+            // A click only selects. Taking control is its own action: Enter, or Ctrl+click.
+            if ctx.input(|i| i.pointer.primary_clicked()) {
+                if let Some(part) = pointer_part {
+                    self.selected = Some(part.creature);
+                    if ctx.input(|i| i.modifiers.command) {
+                        self.runner.send(SimCommand::Possess(Some(part.creature)));
                     }
                 }
             }
 
+            let world_context = WorldContext {
+                world_width: self.snapshot.world_size.x,
+                world_height: self.snapshot.world_size.y,
+                pixels_per_meter: PIXELS_PER_METER,
+                water_temperature_offset: self.snapshot.temperature_offset,
+                flow: self.snapshot.flow.clone(),
+            };
+
+            // --- Draw Terrain ---
+            // Scenery drawn from a silhouette. The tank's own walls are drawn as glass below.
+            for (_collider_handle, collider) in self.snapshot.collider_set.iter() {
+                if ColliderTag::unpack(collider.user_data).is_some_and(|tag| tag.kind == EntityKind::Wall) {
+                    let (Some(polyline), Some(body)) = (collider.shape().as_polyline(), collider.parent().and_then(|handle| self.snapshot.rigid_body_set.get(handle))) else { continue };
+                    let position = body.translation();
+                    for segment in polyline.segments() {
+                        painter.line_segment(
+                            [world_to_screen(*position + segment.a.coords), world_to_screen(*position + segment.b.coords)],
+                            egui::Stroke::new(2.0, egui::Color32::GRAY),
+                        );
+                    }
+                }
+            }
+
+            // --- Draw Glass ---
+            // Reflections first, so the panes wash them out
+            let glass = Glass::new(self.snapshot.world_size, WALL_THICKNESS);
+            glass.draw_reflections(painter, &world_to_screen, &self.snapshot.creatures, &self.snapshot.rigid_body_set, self.zoom, &world_context);
+            glass.draw_panes(painter, &world_to_screen);
+
+            // --- Field Overlay ---
+            match self.field_overlay {
+                FieldOverlay::None => {}
+                FieldOverlay::Light => {
+                    let world_size = self.snapshot.world_size;
+                    if self.light_overlay.as_ref().is_none_or(|(size, _)| *size != world_size) {
+                        let light = world_context.light_field();
+                        self.light_overlay = Some((world_size, FieldGrid::from_fn(world_size, OVERLAY_CELL_SIZE, |point| light.intensity_at(point.y))));
+                    }
+                    if let Some((_, grid)) = &self.light_overlay {
+                        grid.draw_overlay(painter, &world_to_screen, egui::Color32::YELLOW);
+                    }
+                }
+                FieldOverlay::Temperature => self.snapshot.water_temperature.draw_overlay(painter, &world_to_screen, egui::Color32::RED),
+            }
+
+            // Draw eggs under the creatures
+            for egg in &self.snapshot.eggs {
+                egg.draw(painter, &self.snapshot.rigid_body_set, &world_to_screen, self.zoom, &world_context);
+            }
+
             // Draw the creatures
-            for (id, creature) in self.creatures.iter().enumerate() {
-                let is_hovered = self.hovered_creature_id == Some(id);
+            for (id, creature) in self.snapshot.creatures.iter().enumerate() {
+                let is_hovered = self.hovered_creature_id == Some(id)
+                    || pointer_part.is_some_and(|part| part.creature == creature.id())
+                    || self.snapshot.player == Some(creature.id())
+                    || self.selected == Some(creature.id());
                 
                 // Call the creature's draw method
                 creature.draw(
                     painter,
-                    &self.rigid_body_set,
+                    &self.snapshot.rigid_body_set,
                     &world_to_screen, // Pass the closure
                     self.zoom,
                     is_hovered,
-                    PIXELS_PER_METER, // Pass the constant
+                    &world_context,
+                );
+            }
+
+            glass.draw_meniscus(painter, &world_to_screen);
+
+            // --- Sound Rings ---
+            if self.show_sounds {
+                for signal in self.snapshot.sounds.signals() {
+                    let (r, g, b) = match signal.kind {
+                        SignalKind::Call => (255, 230, 140),
+                        SignalKind::Noise => (220, 220, 220),
+                    };
+                    let color = egui::Color32::from_rgba_unmultiplied(r, g, b, (signal.strength() * 110.0) as u8);
+                    let radius = signal.radius() * PIXELS_PER_METER * self.zoom;
+                    painter.circle_stroke(world_to_screen(signal.origin), radius, egui::Stroke::new(1.0, color));
+                }
+            }
+
+            // --- Impact Sparks ---
+            // A flash at the contact point and a burst of streaks flying out, both bigger for harder hits
+            if self.show_impacts {
+                for spark in self.snapshot.impacts.sparks() {
+                    let (r, g, b) = match spark.kind {
+                        ImpactKind::Creatures => (255, 200, 90),
+                        ImpactKind::Wall => (200, 220, 255),
+                    };
+                    let fade = spark.fade();
+                    let color = egui::Color32::from_rgba_unmultiplied(r, g, b, (fade * 230.0) as u8);
+                    let center = world_to_screen(spark.position);
+                    let size = spark.size() * self.zoom;
+                    painter.circle_filled(center, 3.0 * size * fade, color);
+                    let reach = 4.0 + 14.0 * size * (1.0 - fade);
+                    for streak in 0..spark.streaks {
+                        let direction = egui::Vec2::angled(streak as f32 * std::f32::consts::TAU / spark.streaks as f32 + spark.force);
+                        painter.line_segment([center + direction * reach * 0.5, center + direction * reach], egui::Stroke::new(1.0, color));
+                    }
+                }
+            }
+
+            // --- Infection Overlay ---
+            if self.show_infections {
+                for creature in &self.snapshot.creatures {
+                    let color = match creature.attributes().infection {
+                        Infection::Healthy => continue,
+                        Infection::Infected { .. } => egui::Color32::from_rgb(230, 40, 40),
+                        Infection::Immune { .. } => egui::Color32::from_rgba_unmultiplied(120, 200, 255, 120),
+                    };
+                    let ring_radius = creature.drawing_radius() * 1.8 * PIXELS_PER_METER * self.zoom;
+                    for &handle in creature.get_rigid_body_handles() {
+                        if let Some(body) = self.snapshot.rigid_body_set.get(handle) {
+                            painter.circle_stroke(world_to_screen(*body.translation()), ring_radius, egui::Stroke::new(1.5, color));
+                        }
+                    }
+                }
+            }
+
+            if let Some(readout) = pointer_part.and_then(|part| self.snapshot.segment_readout(part)) {
+                self.segment_tooltip(ctx, painter, &world_to_screen, readout);
+            }
+
+            for (row, (message, _)) in self.notifications.iter().enumerate() {
+                painter.text(
+                    available_rect.center_top() + egui::vec2(0.0, 8.0 + row as f32 * 20.0),
+                    egui::Align2::CENTER_TOP,
+                    message,
+                    egui::FontId::proportional(16.0),
+                    egui::Color32::from_rgb(140, 230, 140),
+                );
+            }
+
+            // --- Overload Indicator ---
+            let load_message = match self.snapshot.load_level {
+                LoadLevel::Normal => None,
+                LoadLevel::Strained => Some("Simulation strained: reduced behavior detail"),
+                LoadLevel::Overloaded => Some("Simulation overloaded: minimal behavior detail"),
+            };
+            if let Some(message) = load_message {
+                painter.text(
+                    available_rect.right_top() + egui::vec2(-8.0, 8.0),
+                    egui::Align2::RIGHT_TOP,
+                    message,
+                    egui::FontId::proportional(14.0),
+                    egui::Color32::from_rgb(255, 170, 0),
                 );
             }
         });
@@ -419,46 +1075,38 @@ impl eframe::App for SoftiesApp {
 
 #[cfg(test)]
 mod tests {
-    use super::*; // Imports SoftiesApp, PIXELS_PER_METER, WORLD_HEIGHT_METERS etc.
-    use crate::creature::CreatureState;
-    use egui;   // For egui::Context and other egui types used in DummyFrame
+    use super::*;
+    use egui::accesskit::{Action, ActionRequest, Role};
 
     #[test]
-    fn plankton_eventually_rests() {
-        let mut app = SoftiesApp::default();
-        let mock_ctx = egui::Context::default();
-
-        // Set initial energy of plankton to be low, so they become tired faster.
-        // Tired threshold is typically 20% of max_energy.
-        // Plankton max_energy is 20.0, so tired at <= 4.0.
-        // Start them at 22% (4.4 energy) so they are not immediately tired.
-        for creature_box in app.creatures.iter_mut() {
-            if creature_box.type_name() == "Plankton" {
-                let max_energy = creature_box.attributes().max_energy;
-                creature_box.attributes_mut().energy = max_energy * 0.22;
-            }
-        }
-
-        let mut resting_observed = false;
-        let iterations = 2000; // Increased from 1000
-        let fixed_dt = 1.0 / 60.0; // Simulate at 60 FPS for the test
+    fn every_control_has_a_screen_reader_name() {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let mut app = SoftiesApp::new(Simulation::default());
+        let sections = ["Settings", "Interventions", "Goals", "Notebook", "Tank", "Spawn group", "Self-test", "Body", "Tuning"];
+        let controls = [Role::Button, Role::CheckBox, Role::Slider, Role::ComboBox, Role::TextInput, Role::MultilineTextInput, Role::Link];
 
-        for i in 0..iterations {
-            app.tick_simulation(fixed_dt, &mock_ctx); // Call the new method
+        // Open the collapsed sections the way a screen reader would, a level per frame.
+        let mut opened = Vec::new();
+        let mut input = egui::RawInput::default();
+        for _ in 0..4 {
+            let update = ctx.run(input, |ctx| app.show(ctx)).platform_output.accesskit_update.expect("AccessKit was enabled");
+            let unnamed: Vec<Role> = update
+                .nodes
+                .iter()
+                .filter(|(_, node)| controls.contains(&node.role()) && node.name().is_none_or(str::is_empty) && node.labelled_by().is_empty())
+                .map(|(_, node)| node.role())
+                .collect();
+            assert!(unnamed.is_empty(), "unnamed controls: {:?}", unnamed);
 
-            for creature in &app.creatures {
-                if creature.type_name() == "Plankton" {
-                    if creature.current_state() == CreatureState::Resting {
-                        println!("Plankton entered resting state at iteration {}", i);
-                        resting_observed = true;
-                        break;
-                    }
+            input = egui::RawInput::default();
+            for (id, node) in &update.nodes {
+                if let Some(name) = node.name().filter(|name| sections.contains(name) && !opened.contains(&name.to_string())) {
+                    opened.push(name.to_string());
+                    input.events.push(egui::Event::AccessKitActionRequest(ActionRequest { action: Action::Default, target: *id, data: None }));
                 }
             }
-            if resting_observed {
-                break;
-            }
         }
-        assert!(resting_observed, "Plankton did not enter Resting state after {} iterations", iterations);
+        assert!(opened.contains(&"Notebook".to_string()), "opened {:?}", opened);
     }
-}
\ No newline at end of file
+}
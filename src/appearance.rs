@@ -0,0 +1,113 @@
+use eframe::egui::{self, Color32};
+
+use crate::creature_attributes::CreatureAttributes;
+
+/// Seconds a hatchling takes to grow up. Creatures placed in the tank start this old.
+pub const ADULT_AGE: f32 = 90.0;
+/// Seconds by which a creature's markings are as dark as they get.
+pub const OLD_AGE: f32 = 900.0;
+// Contact force, in newtons, that leaves a mark, and how many times that makes the worst wound
+const WOUND_FORCE: f32 = 25.0;
+const WORST_WOUND_FORCE: f32 = 5.0 * WOUND_FORCE;
+// How much smaller, and how much paler, a creature draws when just hatched
+const HATCHLING_SCALE: f32 = 0.8;
+const HATCHLING_PALENESS: f32 = 0.45;
+// Darkness of the markings when grown, and when old
+const ADULT_PATTERN: f32 = 0.12;
+const OLD_PATTERN: f32 = 0.55;
+const SCAR_COLOR: Color32 = Color32::from_rgb(90, 20, 25);
+
+/// How a creature's history shows on it: the young are small and pale, markings darken with
+/// age, and injured segments carry marks until they heal. Each species' `draw` applies it
+/// to its own colors and shapes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Look {
+    /// Drawn size, as a fraction of the body's.
+    pub scale: f32,
+    /// How far the body color is washed toward white.
+    pub paleness: f32,
+    /// How much darker markings are than the body.
+    pub pattern: f32,
+}
+
+impl Look {
+    pub fn of(attributes: &CreatureAttributes) -> Self {
+        let growth = (attributes.age / ADULT_AGE).clamp(0.0, 1.0);
+        let aging = ((attributes.age - ADULT_AGE) / (OLD_AGE - ADULT_AGE)).clamp(0.0, 1.0);
+        Self {
+            scale: HATCHLING_SCALE + (1.0 - HATCHLING_SCALE) * growth,
+            paleness: HATCHLING_PALENESS * (1.0 - growth),
+            pattern: if growth < 1.0 { 0.0 } else { ADULT_PATTERN + (OLD_PATTERN - ADULT_PATTERN) * aging },
+        }
+    }
+
+    /// `color` as this creature's body shows it.
+    pub fn body(&self, color: Color32) -> Color32 {
+        blend(color, Color32::WHITE, self.paleness)
+    }
+
+    /// `color` as this creature's markings show it: the body color, darkened with age.
+    pub fn marking(&self, color: Color32) -> Color32 {
+        blend(self.body(color), Color32::BLACK, self.pattern)
+    }
+}
+
+/// How badly a hit with `force` newtons wounds the part it lands on, from 0 to 1.
+pub fn wound_severity(force: f32) -> f32 {
+    ((force - WOUND_FORCE) / (WORST_WOUND_FORCE - WOUND_FORCE)).clamp(0.0, 1.0)
+}
+
+/// Moves each channel of `color` a fraction `t` of the way to `target`. Alpha is kept, and
+/// the target is scaled by it, as colors are premultiplied.
+fn blend(color: Color32, target: Color32, t: f32) -> Color32 {
+    let alpha = color.a() as f32 / 255.0;
+    let mix = |from: u8, to: u8| (from as f32 + (to as f32 * alpha - from as f32) * t).round() as u8;
+    Color32::from_rgba_premultiplied(mix(color.r(), target.r()), mix(color.g(), target.g()), mix(color.b(), target.b()), color.a())
+}
+
+/// Marks a wound of `severity` (0 to 1) on a segment drawn at `center` with `radius` points:
+/// a couple of dark slashes, longer and bolder the worse it is.
+pub fn draw_wound(painter: &egui::Painter, center: egui::Pos2, radius: f32, severity: f32) {
+    if severity <= 0.05 {
+        return;
+    }
+    let color = SCAR_COLOR.gamma_multiply(0.4 + 0.6 * severity);
+    let stroke = egui::Stroke::new((radius * 0.25 * severity).max(1.0), color);
+    let reach = radius * (0.4 + 0.5 * severity);
+    for (offset, angle) in [(-0.25, 0.9_f32), (0.25, 0.7)] {
+        let along = egui::Vec2::angled(angle) * reach;
+        let shift = egui::vec2(offset * radius, 0.0);
+        painter.line_segment([center + shift - along, center + shift + along], stroke);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creature_attributes::DietType;
+
+    #[test]
+    fn young_are_pale_and_old_are_marked() {
+        let mut attributes = CreatureAttributes::new(10.0, 1.0, 10.0, 0.1, DietType::Herbivore, 1.0, vec![], vec![]);
+        let grown = Look::of(&attributes);
+        assert_eq!((grown.scale, grown.paleness), (1.0, 0.0));
+
+        attributes.age = 0.0;
+        let hatchling = Look::of(&attributes);
+        let color = Color32::from_rgb(100, 200, 100);
+        assert!(hatchling.scale < 1.0);
+        assert!(hatchling.body(color).r() > color.r() && hatchling.marking(color) == hatchling.body(color));
+
+        attributes.age = OLD_AGE * 2.0;
+        let old = Look::of(&attributes);
+        assert!(old.marking(color).g() < grown.marking(color).g());
+        assert_eq!(old.body(color), color);
+
+        // Wounds add up per segment, to at worst 1, and heal over time
+        attributes.wound(3, 0.4);
+        attributes.wound(3, 0.9);
+        assert_eq!((attributes.wound_at(3), attributes.wound_at(1), attributes.wound_at(9)), (1.0, 0.0, 0.0));
+        attributes.update_passive_stats(10.0, false, 1.0);
+        assert!(attributes.wound_at(3) < 1.0 && attributes.age > OLD_AGE * 2.0);
+    }
+}
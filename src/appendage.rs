@@ -0,0 +1,104 @@
+use eframe::egui;
+use nalgebra::{Rotation2, Vector2};
+use serde::{Deserialize, Serialize};
+
+// Most a single link may bend relative to the one before it, in radians
+const MAX_LINK_BEND: f32 = 0.6;
+
+/// A thin decorative chain (antenna, whisker, fin ray) sticking out of one of a creature's
+/// segments. Purely visual: it has no bodies, and is posed fresh each frame from the parent
+/// segment's position, heading and velocity, so it costs nothing in the physics step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Appendage {
+    /// Index of the segment it grows from.
+    pub parent_segment: usize,
+    /// Direction it leaves the segment, in radians from the segment's heading.
+    pub base_angle: f32,
+    /// Distance from the segment's center to the root, in meters. Usually the segment's radius.
+    pub root_offset: f32,
+    pub link_count: usize,
+    /// Length of each link, in meters.
+    pub link_length: f32,
+    /// Constant bend added at every link, in radians. Gives antennae their arc.
+    pub curl: f32,
+    /// How far links swing back against the parent's motion, in radians per m/s of sideways speed.
+    pub drag: f32,
+    /// Line width at the root, in meters. Tapers to a third of this at the tip.
+    pub width: f32,
+}
+
+impl Appendage {
+    /// World positions of the root and every joint along the chain, root first.
+    /// `heading` is the direction the parent segment faces; it needn't be normalized.
+    pub fn points(&self, parent_position: Vector2<f32>, heading: Vector2<f32>, parent_velocity: Vector2<f32>) -> Vec<Vector2<f32>> {
+        let heading = heading.try_normalize(1e-6).unwrap_or_else(Vector2::x);
+        let mut direction = Rotation2::new(self.base_angle) * heading;
+        let mut point = parent_position + direction * self.root_offset;
+
+        let mut points = Vec::with_capacity(self.link_count + 1);
+        points.push(point);
+        for _ in 0..self.link_count {
+            // Sideways motion relative to this link bends it the opposite way, like drag in water
+            let sideways_speed = direction.perp(&parent_velocity);
+            let bend = (self.curl - self.drag * sideways_speed).clamp(-MAX_LINK_BEND, MAX_LINK_BEND);
+            direction = Rotation2::new(bend) * direction;
+            point += direction * self.link_length;
+            points.push(point);
+        }
+        points
+    }
+
+    /// Draws the chain as a tapering line.
+    pub fn draw(
+        &self,
+        painter: &egui::Painter,
+        points: &[Vector2<f32>],
+        world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
+        pixels_per_world_unit: f32,
+        color: egui::Color32,
+    ) {
+        let links = points.len().saturating_sub(1);
+        for (i, link) in points.windows(2).enumerate() {
+            let taper = 1.0 - (i as f32 / links as f32) * (2.0 / 3.0);
+            let width = (self.width * taper * pixels_per_world_unit).max(0.5);
+            painter.line_segment([world_to_screen(link[0]), world_to_screen(link[1])], egui::Stroke::new(width, color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn whisker() -> Appendage {
+        Appendage {
+            parent_segment: 0,
+            base_angle: 0.0,
+            root_offset: 0.1,
+            link_count: 4,
+            link_length: 0.05,
+            curl: 0.0,
+            drag: 2.0,
+            width: 0.01,
+        }
+    }
+
+    #[test]
+    fn straight_when_still() {
+        let points = whisker().points(Vector2::zeros(), Vector2::new(2.0, 0.0), Vector2::zeros());
+        assert_eq!(points.len(), 5);
+        assert!((points[0] - Vector2::new(0.1, 0.0)).norm() < 1e-6);
+        assert!((points[4] - Vector2::new(0.3, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn trails_behind_sideways_motion() {
+        // Parent moving up: the whisker should sweep down, away from the motion
+        let points = whisker().points(Vector2::zeros(), Vector2::x(), Vector2::new(0.0, 1.0));
+        assert!(points.last().unwrap().y < 0.0);
+        // Links stay the same length however much they bend
+        for link in points.windows(2) {
+            assert!(((link[1] - link[0]).norm() - 0.05).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use nalgebra::Vector2;
+
+use crate::creature::CreatureId;
+use crate::simulation::{Simulation, TIMESTEP, WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+/// Creatures on each side unless the config says otherwise.
+pub const DEFAULT_TEAM_SIZE: usize = 4;
+/// Simulated seconds a match lasts unless one side is wiped out first.
+pub const DEFAULT_DURATION_SECONDS: f32 = 180.0;
+/// Points for each rival a team eliminates. Survival and food are worth a point per second and per unit.
+const KILL_POINTS: f32 = 50.0;
+/// A rival that touched a creature this recently (in seconds) gets the credit when it drops.
+const KILL_CREDIT_SECONDS: f32 = 3.0;
+
+/// A species that can enter the arena. Both sides may be the same species, to compare a
+/// behavior change against a copy of the old one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contender {
+    Snake,
+    Plankton,
+}
+
+impl Contender {
+    pub const ALL: [Contender; 2] = [Contender::Snake, Contender::Plankton];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Contender::Snake => "snake",
+            Contender::Plankton => "plankton",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|contender| contender.label().eq_ignore_ascii_case(name))
+    }
+
+    fn spawn(self, sim: &mut Simulation, position: Vector2<f32>) -> CreatureId {
+        match self {
+            Contender::Snake => sim.spawn_snake(position),
+            Contender::Plankton => sim.spawn_plankton(position),
+        }
+    }
+}
+
+/// How a match is set up.
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaConfig {
+    pub contenders: [Contender; 2],
+    pub team_size: usize,
+    pub duration_seconds: f32,
+}
+
+impl ArenaConfig {
+    pub fn new(first: Contender, second: Contender) -> Self {
+        Self {
+            contenders: [first, second],
+            team_size: DEFAULT_TEAM_SIZE,
+            duration_seconds: DEFAULT_DURATION_SECONDS,
+        }
+    }
+}
+
+/// One side's tally.
+#[derive(Debug, Clone)]
+pub struct TeamScore {
+    pub contender: Contender,
+    pub survivors: usize,
+    /// Seconds alive, summed over every member.
+    pub survival_seconds: f32,
+    /// Rivals eliminated while or shortly after touching a member.
+    pub kills: u32,
+    /// Satiety gained by eating, summed over every member.
+    pub energy_gathered: f32,
+}
+
+impl TeamScore {
+    pub fn points(&self) -> f32 {
+        self.survival_seconds + self.kills as f32 * KILL_POINTS + self.energy_gathered
+    }
+}
+
+/// Who won a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaOutcome {
+    /// Index of the winning team.
+    Winner(usize),
+    Draw,
+}
+
+/// Final scores of a match.
+#[derive(Debug, Clone)]
+pub struct ArenaReport {
+    pub teams: [TeamScore; 2],
+    pub seconds: f32,
+}
+
+impl ArenaReport {
+    pub fn outcome(&self) -> ArenaOutcome {
+        let [first, second] = [self.teams[0].points(), self.teams[1].points()];
+        if (first - second).abs() < 0.5 {
+            ArenaOutcome::Draw
+        } else if first > second {
+            ArenaOutcome::Winner(0)
+        } else {
+            ArenaOutcome::Winner(1)
+        }
+    }
+}
+
+impl fmt::Display for ArenaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Arena: {} vs {}, {:.0}s", self.teams[0].contender.label(), self.teams[1].contender.label(), self.seconds)?;
+        for (index, team) in self.teams.iter().enumerate() {
+            writeln!(
+                f,
+                "  team {} ({}): {:.0} points - {} survivors, {:.0}s survived, {} kills, {:.1} energy gathered",
+                index + 1,
+                team.contender.label(),
+                team.points(),
+                team.survivors,
+                team.survival_seconds,
+                team.kills,
+                team.energy_gathered
+            )?;
+        }
+        match self.outcome() {
+            ArenaOutcome::Winner(index) => write!(f, "  winner: team {} ({})", index + 1, self.teams[index].contender.label()),
+            ArenaOutcome::Draw => write!(f, "  draw"),
+        }
+    }
+}
+
+/// A creature entered in the match.
+struct Entrant {
+    id: CreatureId,
+    team: usize,
+    last_satiety: f32,
+    // When a rival last touched it, in match seconds
+    last_rival_contact: Option<f32>,
+}
+
+/// Two teams in a standard tank, one starting on each side, scored as they go.
+///
+/// The arena adds one rule the open tank doesn't have: a creature with no energy and no
+/// satiety left is eliminated. Hatchlings don't join either team.
+pub struct Arena {
+    pub sim: Simulation,
+    config: ArenaConfig,
+    entrants: Vec<Entrant>,
+    teams: [TeamScore; 2],
+    elapsed: f32,
+}
+
+impl Arena {
+    pub fn new(config: ArenaConfig) -> Self {
+        let mut sim = Simulation::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS);
+        let mut entrants = Vec::new();
+        let half = sim.world_size() / 2.0;
+        for (team, contender) in config.contenders.into_iter().enumerate() {
+            // Each team gets its own half. Snakes extend to the right, so leave room for the body.
+            let x_range = if team == 0 { (-half.x + 1.0, -2.5) } else { (1.0, half.x - 2.5) };
+            for member in 0..config.team_size {
+                let t = (member as f32 + 0.5) / config.team_size as f32;
+                let x = x_range.0 + (x_range.1 - x_range.0) * ((member % 2) as f32 * 0.5 + 0.25);
+                let y = -half.y + 1.0 + (half.y * 2.0 - 2.0) * t;
+                let id = contender.spawn(&mut sim, Vector2::new(x, y));
+                let last_satiety = sim.creatures.last().map_or(0.0, |creature| creature.attributes().satiety);
+                entrants.push(Entrant { id, team, last_satiety, last_rival_contact: None });
+            }
+        }
+
+        let team = |contender| TeamScore {
+            contender,
+            survivors: config.team_size,
+            survival_seconds: 0.0,
+            kills: 0,
+            energy_gathered: 0.0,
+        };
+        Self {
+            sim,
+            config,
+            entrants,
+            teams: [team(config.contenders[0]), team(config.contenders[1])],
+            elapsed: 0.0,
+        }
+    }
+
+    /// Whether time is up or a team has been wiped out.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.config.duration_seconds || self.teams.iter().any(|team| team.survivors == 0)
+    }
+
+    /// Runs one tick of the match.
+    pub fn step(&mut self) {
+        self.sim.tick(TIMESTEP);
+        self.elapsed += TIMESTEP;
+        self.score(TIMESTEP);
+    }
+
+    /// Runs the match to the end.
+    pub fn run(mut self) -> ArenaReport {
+        while !self.is_finished() {
+            self.step();
+        }
+        self.report()
+    }
+
+    pub fn report(&self) -> ArenaReport {
+        ArenaReport { teams: self.teams.clone(), seconds: self.elapsed }
+    }
+
+    /// Tallies the last `dt` seconds and eliminates anyone who's run out.
+    fn score(&mut self, dt: f32) {
+        let mut eliminated = Vec::new();
+        for index in 0..self.entrants.len() {
+            let entrant = &self.entrants[index];
+            let Some(creature) = self.sim.creatures.iter().find(|creature| creature.id() == entrant.id) else { continue };
+            let attributes = creature.attributes();
+            let team = entrant.team;
+
+            let touched_by_rival = self.sim.creatures_touching(entrant.id).iter().any(|other| {
+                self.entrants.iter().any(|rival| rival.id == *other && rival.team != team)
+            });
+
+            let gained = (attributes.satiety - entrant.last_satiety).max(0.0);
+            let exhausted = attributes.energy <= 0.0 && attributes.satiety <= 0.0;
+            let satiety = attributes.satiety;
+            self.teams[team].energy_gathered += gained;
+            self.teams[team].survival_seconds += dt;
+
+            let entrant = &mut self.entrants[index];
+            entrant.last_satiety = satiety;
+            if touched_by_rival {
+                entrant.last_rival_contact = Some(self.elapsed);
+            }
+            if exhausted {
+                eliminated.push(index);
+            }
+        }
+
+        for &index in eliminated.iter().rev() {
+            let entrant = self.entrants.remove(index);
+            self.sim.remove_creature(entrant.id);
+            self.teams[entrant.team].survivors -= 1;
+            if entrant.last_rival_contact.is_some_and(|at| self.elapsed - at <= KILL_CREDIT_SECONDS) {
+                self.teams[1 - entrant.team].kills += 1;
+            }
+        }
+    }
+}
+
+/// Plays one match with the standard settings.
+pub fn run(first: Contender, second: Contender) -> ArenaReport {
+    Arena::new(ArenaConfig::new(first, second)).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn teams_start_on_opposite_sides() {
+        let arena = Arena::new(ArenaConfig::new(Contender::Snake, Contender::Plankton));
+        assert_eq!(arena.sim.creatures.len(), DEFAULT_TEAM_SIZE * 2);
+        for entrant in &arena.entrants {
+            let creature = arena.sim.creatures.iter().find(|c| c.id() == entrant.id).unwrap();
+            let x = arena.sim.rigid_body_set[creature.get_rigid_body_handles()[0]].translation().x;
+            assert_eq!(x < 0.0, entrant.team == 0, "{} at {}", creature.type_name(), x);
+        }
+        assert_eq!(Contender::parse("Snake"), Some(Contender::Snake));
+        assert_eq!(Contender::parse("eel"), None);
+    }
+
+    #[test]
+    fn exhausted_creatures_are_eliminated_and_credited() {
+        let mut arena = Arena::new(ArenaConfig { team_size: 1, ..ArenaConfig::new(Contender::Snake, Contender::Plankton) });
+        // Put the plankton against the snake's head
+        let [snake, plankton] = [arena.entrants[0].id, arena.entrants[1].id];
+        arena.sim.remove_creature(plankton);
+        let head = arena.sim.creatures[0].get_rigid_body_handles()[0];
+        let head = *arena.sim.rigid_body_set[head].translation();
+        let plankton = arena.sim.spawn_plankton(head + Vector2::new(0.0, 0.17));
+        arena.entrants[1].id = plankton;
+        arena.step();
+        assert_eq!(arena.sim.creatures_touching(plankton), vec![snake]);
+
+        let victim = arena.sim.creatures.iter_mut().find(|c| c.id() == plankton).unwrap();
+        victim.attributes_mut().energy = 0.0;
+        victim.attributes_mut().satiety = 0.0;
+        arena.score(TIMESTEP);
+
+        let report = arena.report();
+        assert_eq!(report.teams[0].kills, 1);
+        assert_eq!(report.teams[1].survivors, 0);
+        assert!(arena.is_finished());
+        assert_eq!(report.outcome(), ArenaOutcome::Winner(0));
+        assert!(report.to_string().contains("winner: team 1 (snake)"));
+    }
+
+    #[test]
+    fn a_short_match_runs_to_time() {
+        let mut config = ArenaConfig::new(Contender::Plankton, Contender::Plankton);
+        config.duration_seconds = 1.0;
+        let report = Arena::new(config).run();
+        assert!(report.seconds >= 1.0 && report.seconds < 1.1);
+        for team in &report.teams {
+            assert_eq!(team.survivors, DEFAULT_TEAM_SIZE);
+            assert!((team.survival_seconds - report.seconds * DEFAULT_TEAM_SIZE as f32).abs() < 0.1);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// How strongly a plankton flocks with its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoidWeights {
+    /// How far it sees other plankton, in body radii.
+    pub perception: f32,
+    /// How close others may come before it edges away, in body radii.
+    pub separation_distance: f32,
+    pub cohesion: f32,
+    pub separation: f32,
+    pub alignment: f32,
+}
+
+impl Default for BoidWeights {
+    fn default() -> Self {
+        Self { perception: 10.0, separation_distance: 1.5, cohesion: 0.15, separation: 0.25, alignment: 0.1 }
+    }
+}
+
+/// How a snake goes after food.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SnakeBehavior {
+    /// 0 keeps a hungry snake wandering as if fed. Above that it hunts, swimming harder the
+    /// higher this is: 1 is the full seeking-food wave.
+    pub aggression: f32,
+}
+
+impl Default for SnakeBehavior {
+    fn default() -> Self {
+        Self { aggression: 0.0 }
+    }
+}
+
+/// Behavior constants for one species, to give a group of creatures its own character
+/// without a new creature type.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Behavior {
+    Plankton(BoidWeights),
+    Snake(SnakeBehavior),
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::creature::{Creature, CreatureId};
+
+/// Where in a creature's body a segment sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SegmentRole {
+    Head,
+    Body,
+    Tail,
+}
+
+impl SegmentRole {
+    /// Role of segment `index` in a chain of `count` segments, head first.
+    /// A creature made of a single segment is all head.
+    pub fn in_chain(index: usize, count: usize) -> Self {
+        if index == 0 {
+            SegmentRole::Head
+        } else if index + 1 == count {
+            SegmentRole::Tail
+        } else {
+            SegmentRole::Body
+        }
+    }
+}
+
+/// One segment of one creature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BodyPart {
+    pub creature: CreatureId,
+    /// Index into the creature's `get_rigid_body_handles`.
+    pub segment: u32,
+    pub role: SegmentRole,
+}
+
+/// Maps every creature collider to the body part it belongs to, so a contact or point query
+/// can tell a head from a tail without going back through the creature.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BodyPartRegistry {
+    parts: HashMap<ColliderHandle, BodyPart>,
+}
+
+impl BodyPartRegistry {
+    /// Records the colliders on each of `creature`'s bodies. Call once it has spawned.
+    pub fn register(&mut self, creature: &dyn Creature, rigid_body_set: &RigidBodySet) {
+        for (segment, &body) in creature.get_rigid_body_handles().iter().enumerate() {
+            let part = BodyPart {
+                creature: creature.id(),
+                segment: segment as u32,
+                role: creature.segment_role(segment),
+            };
+            for &collider in rigid_body_set[body].colliders() {
+                self.parts.insert(collider, part);
+            }
+        }
+    }
+
+    /// Forgets every collider belonging to `creature`.
+    pub fn unregister(&mut self, creature: CreatureId) {
+        self.parts.retain(|_, part| part.creature != creature);
+    }
+
+    pub fn get(&self, collider: ColliderHandle) -> Option<BodyPart> {
+        self.parts.get(&collider).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ColliderHandle, BodyPart)> + '_ {
+        self.parts.iter().map(|(&collider, &part)| (collider, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::Simulation;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn roles_follow_the_chain() {
+        assert_eq!(SegmentRole::in_chain(0, 1), SegmentRole::Head);
+        assert_eq!(SegmentRole::in_chain(0, 10), SegmentRole::Head);
+        assert_eq!(SegmentRole::in_chain(4, 10), SegmentRole::Body);
+        assert_eq!(SegmentRole::in_chain(9, 10), SegmentRole::Tail);
+    }
+
+    #[test]
+    fn every_snake_collider_is_registered() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let id = sim.spawn_snake(Vector2::zeros());
+        let snake = &sim.creatures[0];
+        let segments = snake.get_rigid_body_handles().len();
+
+        for (segment, &body) in snake.get_rigid_body_handles().iter().enumerate() {
+            let collider = sim.rigid_body_set[body].colliders()[0];
+            let part = sim.body_part(collider).unwrap();
+            assert_eq!(part.creature, id);
+            assert_eq!(part.segment as usize, segment);
+            assert_eq!(part.role, SegmentRole::in_chain(segment, segments));
+        }
+
+        // Point lookups need the query pipeline, which is built by the physics step
+        sim.tick(1.0 / 60.0);
+        let tail_body = *sim.creatures[0].get_rigid_body_handles().last().unwrap();
+        let tail = *sim.rigid_body_set[tail_body].translation();
+        assert_eq!(sim.body_part_at(tail).map(|part| part.role), Some(SegmentRole::Tail));
+        assert_eq!(sim.snapshot().body_part_at(tail).map(|part| part.role), Some(SegmentRole::Tail));
+        assert_eq!(sim.body_part_at(Vector2::new(-9.0, -7.0)), None);
+
+        sim.remove_creature(id);
+        assert_eq!(sim.body_parts.iter().count(), 0);
+    }
+}
@@ -0,0 +1,92 @@
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::body_parts::BodyPartRegistry;
+use crate::creature::{Creature, CreatureId};
+
+/// Satiety a cannibal gets from a meal, as a fraction of its maximum.
+pub const MEAL_SATIETY: f32 = 0.6;
+
+/// An optional rule for one species: when starving, its members eat smaller kin their head
+/// runs into. A crowded, hungry population thins itself out instead of all starving together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cannibalism {
+    /// `Creature::type_name` of the species it applies to.
+    pub species: String,
+    /// Satiety, as a fraction of the maximum, below which they turn on their own.
+    pub starving_below: f32,
+}
+
+/// Who eats whom this step: pairs of (eater, meal) where a starving member of a species with
+/// a rule has its head touching kin it would eat (`CreatureAttributes::would_cannibalize`).
+/// Nobody eats twice or is eaten twice, and nobody eaten gets to eat.
+pub fn meals(
+    rules: &[Cannibalism],
+    creatures: &[Box<dyn Creature>],
+    rigid_body_set: &RigidBodySet,
+    narrow_phase: &NarrowPhase,
+    body_parts: &BodyPartRegistry,
+) -> Vec<(CreatureId, CreatureId)> {
+    let mut meals: Vec<(CreatureId, CreatureId)> = Vec::new();
+    let taken = |meals: &[(CreatureId, CreatureId)], id: CreatureId| meals.iter().any(|&(eater, meal)| eater == id || meal == id);
+    for rule in rules {
+        for eater in creatures.iter().filter(|creature| creature.type_name() == rule.species) {
+            if taken(&meals, eater.id()) {
+                continue;
+            }
+            let Some(head) = eater.get_rigid_body_handles().first().and_then(|&head| rigid_body_set.get(head)) else { continue };
+            let meal = head.colliders().iter().find_map(|&collider| {
+                narrow_phase
+                    .contact_pairs_with(collider)
+                    .filter(|pair| pair.has_any_active_contact)
+                    .map(|pair| if pair.collider1 == collider { pair.collider2 } else { pair.collider1 })
+                    .filter_map(|other| body_parts.get(other))
+                    .filter(|part| part.creature != eater.id() && !taken(&meals, part.creature))
+                    .filter_map(|part| creatures.iter().find(|creature| creature.id() == part.creature))
+                    .find(|kin| kin.type_name() == rule.species && eater.attributes().would_cannibalize(kin.attributes(), rule.starving_below))
+            });
+            if let Some(meal) = meal {
+                meals.push((eater.id(), meal.id()));
+            }
+        }
+    }
+    meals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector2;
+
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn only_the_starving_eat_their_own() {
+        let rule = Cannibalism { species: "Snake".to_string(), starving_below: 0.2 };
+        // Two snakes, the second's head against the first's
+        let pair = |cannibals: bool| {
+            let mut sim = Simulation::new(20.0, 16.0);
+            let hungry = sim.spawn_snake(Vector2::zeros());
+            let head = *sim.rigid_body_set[sim.creatures[0].get_rigid_body_handles()[0]].translation();
+            let fed = sim.spawn_snake(head + Vector2::new(0.0, 0.19));
+            sim.creatures[0].attributes_mut().satiety = 5.0;
+            if cannibals {
+                sim.set_cannibalism(rule.clone());
+            }
+            for _ in 0..5 {
+                sim.tick(TIMESTEP);
+            }
+            (sim, hungry, fed)
+        };
+
+        // The starving one eats the fed one, not the other way round
+        let (sim, hungry, fed) = pair(true);
+        assert!(sim.creatures.iter().all(|creature| creature.id() != fed));
+        let eater = sim.creatures.iter().find(|creature| creature.id() == hungry).unwrap();
+        assert!(eater.attributes().satiety > eater.attributes().max_satiety * MEAL_SATIETY - 5.0);
+
+        // No rule, no meal
+        let (sim, _, fed) = pair(false);
+        assert!(sim.creatures.iter().any(|creature| creature.id() == fed));
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::creature::CreatureId;
+
+/// What sort of thing a collider belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EntityKind {
+    Wall,
+    Food,
+    Creature,
+    Fixture,
+}
+
+impl EntityKind {
+    // Codes start at 1 so an untouched user_data of 0 doesn't decode as anything
+    fn code(self) -> u8 {
+        match self {
+            EntityKind::Wall => 1,
+            EntityKind::Food => 2,
+            EntityKind::Creature => 3,
+            EntityKind::Fixture => 4,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(EntityKind::Wall),
+            2 => Some(EntityKind::Food),
+            3 => Some(EntityKind::Creature),
+            4 => Some(EntityKind::Fixture),
+            _ => None,
+        }
+    }
+}
+
+// Bit layout of the packed u128, from the low end: 64 bits of id, 32 bits of segment
+// index, 8 bits of kind. The rest is zero.
+const SEGMENT_SHIFT: u32 = 64;
+const KIND_SHIFT: u32 = 96;
+
+/// Identifies what a collider is part of. Stored packed in Rapier's `user_data`, so
+/// anything holding a collider (sensing, contact events, drawing) can tell what it touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ColliderTag {
+    pub kind: EntityKind,
+    /// Owning creature for `Creature` colliders; free for other kinds to number their instances.
+    pub id: u64,
+    /// Which body part of the owner this collider is, counting from the head.
+    pub segment: u32,
+}
+
+impl ColliderTag {
+    pub fn wall() -> Self {
+        Self { kind: EntityKind::Wall, id: 0, segment: 0 }
+    }
+
+    pub fn creature(id: CreatureId, segment: u32) -> Self {
+        Self { kind: EntityKind::Creature, id: id.0, segment }
+    }
+
+    pub fn pack(self) -> u128 {
+        self.id as u128 | (self.segment as u128) << SEGMENT_SHIFT | (self.kind.code() as u128) << KIND_SHIFT
+    }
+
+    /// Decodes a collider's `user_data`. `None` if it wasn't made by `pack`.
+    pub fn unpack(user_data: u128) -> Option<Self> {
+        if user_data >> (KIND_SHIFT + 8) != 0 {
+            return None;
+        }
+        Some(Self {
+            kind: EntityKind::from_code((user_data >> KIND_SHIFT) as u8)?,
+            id: user_data as u64,
+            segment: (user_data >> SEGMENT_SHIFT) as u32,
+        })
+    }
+
+    /// The creature this collider belongs to, if it belongs to one.
+    pub fn creature_id(&self) -> Option<CreatureId> {
+        (self.kind == EntityKind::Creature).then_some(CreatureId(self.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_survive_packing() {
+        let tags = [
+            ColliderTag::wall(),
+            ColliderTag::creature(CreatureId(0), 0),
+            ColliderTag::creature(CreatureId(u64::MAX), u32::MAX),
+            ColliderTag { kind: EntityKind::Food, id: 7, segment: 0 },
+            ColliderTag { kind: EntityKind::Fixture, id: 3, segment: 2 },
+        ];
+        for tag in tags {
+            assert_eq!(ColliderTag::unpack(tag.pack()), Some(tag));
+        }
+    }
+
+    #[test]
+    fn only_creature_tags_have_a_creature() {
+        assert_eq!(ColliderTag::creature(CreatureId(5), 2).creature_id(), Some(CreatureId(5)));
+        assert_eq!(ColliderTag::wall().creature_id(), None);
+        assert_eq!(ColliderTag::unpack(0), None);
+        assert_eq!(ColliderTag::unpack(u128::MAX), None);
+    }
+}
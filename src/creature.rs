@@ -1,12 +1,24 @@
-use rapier2d::prelude::{RigidBodyHandle, ImpulseJointHandle, RigidBodySet, ImpulseJointSet, ColliderSet, QueryPipeline};
+use rapier2d::prelude::{RigidBodyHandle, ImpulseJointHandle, RigidBodySet, ImpulseJointSet};
 use nalgebra::Vector2; // Added for vector math in helper
 use eframe::egui; // Added for Painter in draw method
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
+use crate::body_parts::SegmentRole;
 use crate::creature_attributes::CreatureAttributes;
+use crate::creatures::CreatureData;
+use crate::egg::Species;
+use crate::flow::FlowField;
+use crate::behavior::Behavior;
+use crate::tunable::Tunable;
+use crate::gait::Gait;
+use crate::joint_profile::JointProfile;
+use crate::light::LightField;
+use crate::wall_following::WallContact;
 
 /// Represents the general behavioral state of a creature.
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CreatureState {
     Idle,      // Doing nothing specific, minimal movement.
     Wandering, // Exploring randomly.
@@ -19,15 +31,41 @@ pub enum CreatureState {
 /// Context about the simulation world passed to creature updates.
 #[allow(dead_code)]
 pub struct WorldContext {
+    pub world_width: f32,
     pub world_height: f32,
     pub pixels_per_meter: f32,
+    /// °C added to the water temperature everywhere in the tank.
+    pub water_temperature_offset: f32,
+    /// How the water is moving.
+    pub flow: FlowField,
+}
+
+impl WorldContext {
+    /// Half the tank's width and height. The tank is centered on the origin.
+    pub fn half_extents(&self) -> Vector2<f32> {
+        Vector2::new(self.world_width / 2.0, self.world_height / 2.0)
+    }
+
+    pub fn light_field(&self) -> LightField {
+        LightField::for_tank(self.world_height).with_temperature_offset(self.water_temperature_offset)
+    }
+}
+
+/// Identifies a creature within a simulation. Never reused, even after the creature is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct CreatureId(pub u64);
+
+impl fmt::Display for CreatureId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 /// Basic information about a creature, used for awareness by other creatures.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct CreatureInfo {
-    pub id: u128,
+    pub id: CreatureId,
     pub creature_type_name: &'static str,
     pub primary_body_handle: RigidBodyHandle, // Or Option<RigidBodyHandle> if a creature might not have one temporarily
     pub position: Vector2<f32>,
@@ -36,14 +74,78 @@ pub struct CreatureInfo {
     // pub attributes: CreatureAttributes, // Consider if the full attributes are needed or just specific parts like size/tags
 }
 
+/// What a creature can find out about its surroundings while deciding what to do.
+/// The simulation answers from Rapier's query pipeline; tests can script the answers
+/// with `MockWorld` instead of building a physics pipeline.
+pub trait Senses {
+    /// Info about every creature, as of the start of this tick.
+    fn all_creatures(&self) -> &[CreatureInfo];
+
+    /// Creatures with a collider overlapping the circle, not counting walls or `own_id`.
+    /// Takes the rigid bodies from the caller, who holds them mutably during behavior updates.
+    fn creatures_near(&self, bodies: &RigidBodySet, center: Vector2<f32>, radius: f32, own_id: CreatureId) -> Vec<&CreatureInfo>;
+
+    fn creature(&self, id: CreatureId) -> Option<&CreatureInfo> {
+        self.all_creatures().iter().find(|info| info.id == id)
+    }
+
+    /// The nearest wall or piece of scenery to `point`, if it's within `max_distance`.
+    /// Senses that don't model walls never find one.
+    fn nearest_wall(&self, _bodies: &RigidBodySet, _point: Vector2<f32>, _max_distance: f32) -> Option<WallContact> {
+        None
+    }
+}
+
 #[allow(dead_code)]
-pub trait Creature {
+pub trait Creature: Send {
     // Return unique ID for this creature instance
-    fn id(&self) -> u128;
+    fn id(&self) -> CreatureId;
 
     // Return slices of Rapier handles
     fn get_rigid_body_handles(&self) -> &[RigidBodyHandle];
     fn get_joint_handles(&self) -> &[ImpulseJointHandle];
+    // Drops any of these joints from the creature's handles because the simulation is about
+    // to remove them, e.g. when the creature at the other end goes away
+    fn forget_joints(&mut self, joints: &[ImpulseJointHandle]);
+    // Role of the body at this index in get_rigid_body_handles. Treats the bodies as a chain
+    // from head to tail unless overridden.
+    fn segment_role(&self, segment: usize) -> SegmentRole {
+        SegmentRole::in_chain(segment, self.get_rigid_body_handles().len())
+    }
+
+    // Lays an egg if one is ready, paying its cost. The simulation places it at the creature's
+    // last segment. Creatures that don't lay eggs keep the default.
+    fn lay_egg(&mut self) -> Option<Species> {
+        None
+    }
+
+    // How stiff the joints are along the body, for creatures built as a jointed chain
+    fn joint_profile(&self) -> Option<JointProfile> {
+        None
+    }
+    // Replaces the joint profile, updating the creature's live joints to match. Creatures
+    // without a chain ignore it.
+    fn set_joint_profile(&mut self, _profile: JointProfile, _impulse_joint_set: &mut ImpulseJointSet) {}
+    // The wave a swimming creature sends down its body, for creatures that swim that way
+    fn gait(&self) -> Option<Gait> {
+        None
+    }
+    fn set_gait(&mut self, _gait: Gait) {}
+    // The species' tunable behavior constants, for creatures that have any
+    fn behavior(&self) -> Option<Behavior> {
+        None
+    }
+    // Replaces them. Ignored if they're for another species.
+    fn set_behavior(&mut self, _behavior: Behavior) {}
+    // Named numbers to show as sliders in the inspector, e.g. drag coefficients. Species
+    // register theirs with `tunable::param!`
+    fn tunables(&self) -> Vec<Tunable> {
+        Vec::new()
+    }
+    // Sets one by name, clamped to its range. Returns false if there's no such tunable.
+    fn set_tunable(&mut self, _name: &str, _value: f32) -> bool {
+        false
+    }
 
     // Access creature attributes
     fn attributes(&self) -> &CreatureAttributes;
@@ -55,19 +157,28 @@ pub trait Creature {
     // Type Info
     fn type_name(&self) -> &'static str; // Added for UI
 
+    // Copies the creature so the renderer can draw a snapshot of it
+    fn clone_box(&self) -> Box<dyn Creature>;
+    // Copies the creature as plain data, for serialization
+    fn to_data(&self) -> CreatureData;
+    // Encodes everything about the creature, internal timers and targets included, with
+    // `creatures::encode_state`. Its physics handles only mean something in the world it was
+    // saved from.
+    fn save_state(&self) -> Result<Vec<u8>, bincode::Error>;
+    // Replaces the creature with one saved by the same species' save_state
+    fn load_state(&mut self, state: &[u8]) -> Result<(), bincode::Error>;
+
     // State and Behavior
     fn current_state(&self) -> CreatureState;
     // Decides the next state and executes behavior for the current frame.
-    // Needs physics access for actions and potentially sensing.
+    // Needs physics access for actions and sensing.
     fn update_state_and_behavior(
         &mut self,
         dt: f32,
-        own_id: u128, // ID of the creature instance being updated
+        own_id: CreatureId, // ID of the creature instance being updated
         rigid_body_set: &mut RigidBodySet, // Still mutable for direct actions by self
         impulse_joint_set: &mut ImpulseJointSet, // Still mutable for direct actions by self
-        collider_set: &ColliderSet, // Immutable for querying others
-        query_pipeline: &QueryPipeline, // For spatial queries
-        all_creatures_info: &Vec<CreatureInfo>, // Info about all other creatures
+        senses: &dyn Senses, // For finding other creatures
         world_context: &WorldContext,
     );
 
@@ -86,6 +197,6 @@ pub trait Creature {
         world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
         zoom: f32,
         is_hovered: bool,
-        pixels_per_meter: f32, // Added parameter
+        world_context: &WorldContext, // For scale and depth tinting
     );
 }
@@ -1,5 +1,16 @@
 use serde::{Deserialize, Serialize};
 
+use crate::disease::Infection;
+use crate::personality::Personality;
+
+pub const MAX_PARASITE_LOAD: f32 = 100.0;
+/// Wound severity healed per second.
+const WOUND_HEAL_RATE: f32 = 0.01;
+/// Prey may be up to this many times a predator's size.
+const MAX_PREY_SIZE_RATIO: f32 = 1.5;
+/// A wound this bad anywhere leaves a creature stressed.
+const STRESSFUL_WOUND: f32 = 0.3;
+
 /// Defines the dietary preference of a creature.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DietType {
@@ -22,6 +33,19 @@ pub struct CreatureAttributes {
     pub diet_type: DietType,
     pub size: f32, // General size indicator
 
+    /// Parasites carried, from 0 to `MAX_PARASITE_LOAD`. Cleaners remove them (see `symbiosis`).
+    pub parasite_load: f32,
+    pub infection: Infection,
+    /// 0 for creatures placed in the tank, one more than the parent's for hatchlings.
+    pub generation: u32,
+    /// Seconds lived. Hatchlings start at 0; creatures placed in the tank start grown
+    /// (`appearance::ADULT_AGE`).
+    pub age: f32,
+    /// Injury per segment, from 0 (unhurt) to 1, by segment index. Heals slowly.
+    pub wounds: Vec<f32>,
+    /// This individual's own take on its species' behavior, rolled when it's spawned.
+    pub personality: Personality,
+
     // Tags defining what this creature *can* eat
     pub prey_tags: Vec<String>,
     // Tags defining what this creature is. Used for things like determining which things can eat this creature.
@@ -32,6 +56,7 @@ pub struct CreatureAttributes {
 impl CreatureAttributes {
     /// Creates a new set of attributes with default values.
     /// Consider using a builder pattern if this gets complex.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         max_energy: f32,
         energy_recovery_rate: f32,
@@ -51,18 +76,30 @@ impl CreatureAttributes {
             metabolic_rate,
             diet_type,
             size,
+            parasite_load: 0.0,
+            infection: Infection::Healthy,
+            generation: 0,
+            age: crate::appearance::ADULT_AGE,
+            wounds: Vec::new(),
+            personality: Personality::default(),
             prey_tags,
             self_tags,
         }
     }
 
-    // Placeholder methods for future logic
-    pub fn update_passive_stats(&mut self, dt: f32, is_resting: bool) {
+    /// Burns satiety and energy for `dt` seconds at `metabolic_rate` times `metabolism`
+    /// (1.0 normally), and recovers energy if resting.
+    pub fn update_passive_stats(&mut self, dt: f32, is_resting: bool, metabolism: f32) {
+        let metabolic_rate = self.metabolic_rate * metabolism;
+        self.age += dt;
+        for wound in &mut self.wounds {
+            *wound = (*wound - WOUND_HEAL_RATE * dt).max(0.0);
+        }
         // Decrease satiety over time
-        self.satiety = (self.satiety - self.metabolic_rate * dt).max(0.0);
+        self.satiety = (self.satiety - metabolic_rate * dt).max(0.0);
 
         // Passive metabolic energy drain (always occurs)
-        self.energy = (self.energy - self.metabolic_rate * dt * 0.5).max(0.0); // Example: energy drains at half the metabolic rate of satiety
+        self.energy = (self.energy - metabolic_rate * dt * 0.5).max(0.0); // Example: energy drains at half the metabolic rate of satiety
 
         // Recover energy if resting
         if is_resting {
@@ -78,12 +115,48 @@ impl CreatureAttributes {
         self.satiety = (self.satiety + amount).min(self.max_satiety);
     }
 
+    /// Hurts `segment` by `severity`, on top of any wound it already has.
+    pub fn wound(&mut self, segment: usize, severity: f32) {
+        if self.wounds.len() <= segment {
+            self.wounds.resize(segment + 1, 0.0);
+        }
+        self.wounds[segment] = (self.wounds[segment] + severity).min(1.0);
+    }
+
+    pub fn wound_at(&self, segment: usize) -> f32 {
+        self.wounds.get(segment).copied().unwrap_or(0.0)
+    }
+
+    pub fn gain_parasites(&mut self, amount: f32) {
+        self.parasite_load = (self.parasite_load + amount).min(MAX_PARASITE_LOAD);
+    }
+
+    /// Removes up to `amount` parasites. Returns how many were actually removed.
+    pub fn remove_parasites(&mut self, amount: f32) -> f32 {
+        let removed = amount.min(self.parasite_load);
+        self.parasite_load -= removed;
+        removed
+    }
+
+    /// Bolder creatures count as hungry sooner.
     pub fn is_hungry(&self) -> bool {
-        self.satiety < self.max_satiety * 0.5 // Example threshold
+        self.satiety < self.max_satiety * 0.5 * self.personality.boldness // Example threshold
     }
 
+    /// More active creatures tire later.
     pub fn is_tired(&self) -> bool {
-        self.energy < self.max_energy * 0.2 // Example threshold
+        self.energy < self.max_energy * 0.2 / self.personality.activity // Example threshold
+    }
+
+    /// Whether a resting creature has its energy back to `fraction` of the maximum, or
+    /// enough less for an active one.
+    pub fn is_rested(&self, fraction: f32) -> bool {
+        self.energy >= self.max_energy * (fraction / self.personality.activity).min(1.0)
+    }
+
+    /// Badly hurt somewhere. Stressed creatures keep to the walls (see `wall_following`).
+    pub fn is_stressed(&self) -> bool {
+        self.wounds.iter().any(|&wound| wound >= STRESSFUL_WOUND)
     }
 
     /// Checks if this creature *can* eat another creature based on tags.
@@ -91,14 +164,27 @@ impl CreatureAttributes {
         match self.diet_type {
             DietType::Herbivore => false, // Can't eat creatures
             DietType::Carnivore | DietType::Omnivore => {
-                // Must be smaller or similar size (adjust multiplier as needed)
-                if other.size > self.size * 1.5 { return false; }
+                if !self.can_swallow(other) { return false; }
                 // Check if any of the other's tags match our prey tags
                 self.prey_tags.iter().any(|prey_tag| other.self_tags.contains(prey_tag))
             }
         }
     }
 
+    /// Whether `other` is small enough for this creature to eat: smaller or of similar size.
+    pub fn can_swallow(&self, other: &CreatureAttributes) -> bool {
+        other.size <= self.size * MAX_PREY_SIZE_RATIO
+    }
+
+    /// Whether this creature, starving, would eat `kin`, one of its own species. Only
+    /// carnivores and omnivores do, only with satiety below `starving_below` (a fraction
+    /// of the maximum, raised for bold ones), and only kin they can swallow that are no
+    /// bigger than themselves.
+    pub fn would_cannibalize(&self, kin: &CreatureAttributes, starving_below: f32) -> bool {
+        let starving = self.satiety < self.max_satiety * starving_below * self.personality.boldness;
+        self.diet_type != DietType::Herbivore && starving && self.can_swallow(kin) && kin.size <= self.size
+    }
+
     /// Checks if this creature *can* be eaten by another creature based on tags.
     pub fn can_be_eaten_by(&self, potential_predator: &CreatureAttributes) -> bool {
         potential_predator.can_eat(self)
@@ -1,2 +1,175 @@
 pub mod plankton;
 pub mod snake;
+
+use serde::{Deserialize, Serialize};
+
+use crate::creature::Creature;
+use plankton::Plankton;
+use snake::Snake;
+
+/// Every concrete creature type as plain data, for when creatures have to be serialized
+/// (e.g. snapshots sent from the web worker to the page).
+#[derive(Clone, Serialize, Deserialize)]
+pub enum CreatureData {
+    Snake(Snake),
+    Plankton(Plankton),
+}
+
+impl CreatureData {
+    pub fn into_creature(self) -> Box<dyn Creature> {
+        match self {
+            CreatureData::Snake(snake) => Box::new(snake),
+            CreatureData::Plankton(plankton) => Box::new(plankton),
+        }
+    }
+}
+
+// Just the species: the creatures themselves are too big to print usefully
+impl std::fmt::Debug for CreatureData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreatureData::Snake(_) => f.write_str("Snake"),
+            CreatureData::Plankton(_) => f.write_str("Plankton"),
+        }
+    }
+}
+
+/// A creature as its species name and `Creature::save_state`, so it can be stored and
+/// restored without the caller knowing its concrete type.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedCreature {
+    pub species: String,
+    pub state: Vec<u8>,
+}
+
+impl SavedCreature {
+    pub fn save(creature: &dyn Creature) -> Result<Self, bincode::Error> {
+        Ok(Self { species: creature.type_name().to_string(), state: creature.save_state()? })
+    }
+
+    /// Rebuilds the creature, or explains why it can't.
+    pub fn restore(&self) -> Result<Box<dyn Creature>, String> {
+        let mut creature = blank(&self.species).ok_or_else(|| format!("unknown species '{}'", self.species))?;
+        creature.load_state(&self.state).map_err(|err| format!("bad {} state: {}", self.species, err))?;
+        Ok(creature)
+    }
+}
+
+/// Encodes a creature's state for `Creature::save_state`, tagged with its species' layout version.
+///
+/// States are bincode, which isn't self-describing: bytes saved before a field was added,
+/// removed or reordered would decode as garbage, or not at all. Hence the rule: a species bumps
+/// its `STATE_VERSION` whenever its saved fields change, and its `load_state` goes on reading
+/// each older version it still supports by decoding the old layout and filling in the new
+/// fields. Any other version is refused with `unknown_version`.
+pub fn encode_state<T: Serialize>(version: u32, state: &T) -> Result<Vec<u8>, bincode::Error> {
+    bincode::serialize(&(version, state))
+}
+
+/// Splits a state from `encode_state` into its version and the encoded fields.
+pub fn decode_version(state: &[u8]) -> Result<(u32, &[u8]), bincode::Error> {
+    let version: u32 = bincode::deserialize(state)?;
+    let tag = bincode::serialized_size(&version)? as usize;
+    Ok((version, &state[tag..]))
+}
+
+/// The error for a state whose version `load_state` doesn't read.
+pub fn unknown_version(species: &str, version: u32, current: u32) -> bincode::Error {
+    Box::new(bincode::ErrorKind::Custom(format!(
+        "{} state is version {}, but this build reads version {}",
+        species, version, current
+    )))
+}
+
+/// A placeholder of the named species for `load_state` to fill in. Every species goes here.
+fn blank(species: &str) -> Option<Box<dyn Creature>> {
+    match species {
+        "Snake" => Some(Box::new(Snake::new(0.1, 1, 0.3))),
+        "Plankton" => Some(Box::new(Plankton::new(0.1))),
+        _ => None,
+    }
+}
+
+/// Serializes a `Vec<Box<dyn Creature>>` field through `SavedCreature`.
+/// Use with `#[serde(with = "crate::creatures::boxed")]`.
+pub mod boxed {
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::SavedCreature;
+    use crate::creature::Creature;
+
+    pub fn serialize<S: Serializer>(creatures: &[Box<dyn Creature>], serializer: S) -> Result<S::Ok, S::Error> {
+        let saved = creatures.iter().map(|creature| SavedCreature::save(creature.as_ref())).collect::<Result<Vec<_>, _>>().map_err(S::Error::custom)?;
+        serializer.collect_seq(saved)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Box<dyn Creature>>, D::Error> {
+        let saved = Vec::<SavedCreature>::deserialize(deserializer)?;
+        saved.iter().map(|creature| creature.restore().map_err(D::Error::custom)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn states_from_older_layouts_are_upgraded_or_refused() {
+        // A species that added `stamina` in version 2 and, following the rule, still reads 1
+        #[derive(Serialize, Deserialize)]
+        struct Before {
+            energy: f32,
+        }
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct After {
+            energy: f32,
+            stamina: f32,
+        }
+        let load = |state: &[u8]| -> Result<After, bincode::Error> {
+            match decode_version(state)? {
+                (2, fields) => bincode::deserialize(fields),
+                (1, fields) => {
+                    let before: Before = bincode::deserialize(fields)?;
+                    Ok(After { energy: before.energy, stamina: 1.0 })
+                }
+                (version, _) => Err(unknown_version("Test", version, 2)),
+            }
+        };
+        let saved = encode_state(1, &Before { energy: 3.0 }).unwrap();
+        assert_eq!(load(&saved).unwrap(), After { energy: 3.0, stamina: 1.0 });
+
+        // Real species refuse a version they don't know rather than misread it
+        for species in ["Snake", "Plankton"] {
+            let state = blank(species).unwrap().save_state().unwrap();
+            let (version, fields) = decode_version(&state).unwrap();
+            let newer = [bincode::serialize(&(version + 1)).unwrap(), fields.to_vec()].concat();
+            let err = SavedCreature { species: species.to_string(), state: newer }.restore().err().unwrap();
+            assert!(err.contains("version"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn every_species_round_trips_its_internal_state() {
+        let mut sim = Simulation::default();
+        for _ in 0..30 {
+            sim.tick(TIMESTEP);
+        }
+        let mut restored = Simulation::decode(&sim.encode().unwrap()).unwrap();
+        assert_eq!(restored.creatures.len(), sim.creatures.len());
+        for (before, after) in sim.creatures.iter().zip(&restored.creatures) {
+            assert_eq!(before.type_name(), after.type_name());
+            // Timers, targets and the like come back exactly
+            assert_eq!(before.save_state().unwrap(), after.save_state().unwrap());
+        }
+        restored.tick(TIMESTEP);
+
+        let unknown = SavedCreature { species: "Kraken".to_string(), state: Vec::new() };
+        assert!(unknown.restore().err().unwrap().contains("Kraken"));
+        let mut mismatched = SavedCreature::save(sim.creatures[0].as_ref()).unwrap();
+        mismatched.species = if sim.creatures[0].type_name() == "Snake" { "Plankton" } else { "Snake" }.to_string();
+        assert!(mismatched.restore().is_err());
+    }
+}
@@ -2,9 +2,22 @@ use rapier2d::prelude::*;
 use nalgebra::{Vector2, Point2};
 use eframe::egui; // Keep for draw method later
 use rand::Rng;
-
-use crate::creature::{Creature, CreatureState, WorldContext, CreatureInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::appendage::Appendage;
+use crate::appearance::{self, Look};
+use crate::behavior::{Behavior, BoidWeights};
+use crate::collider_tag::ColliderTag;
+use crate::random;
+use crate::impacts::IMPACT_FORCE_THRESHOLD;
+use crate::egg::Species;
+use crate::hydro::{BuoyancyController, DepthGoal};
+use crate::phototaxis::{LightResponse, Phototaxis};
+use crate::creature::{Creature, CreatureId, CreatureState, WorldContext, Senses};
 use crate::creature_attributes::{CreatureAttributes, DietType};
+use crate::creatures::{self, CreatureData};
+use crate::tunable::{self, param, Param, Tunable};
+use crate::wall_following::{WallFollowing, WallSide};
 
 /// Simplified info for boid calculation
 #[derive(Debug, Clone, Copy)]
@@ -68,8 +81,10 @@ pub fn calculate_boid_steering_impulse(
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*; // Import items from the parent module (plankton.rs)
+    use crate::mock_world::MockWorld;
     use nalgebra::Vector2;
 
     const DEFAULT_PERCEPTION_RADIUS: f32 = 10.0;
@@ -220,16 +235,106 @@ mod tests {
         // Total expected: (0.15, 0.0)
         assert_vec_approx_eq(impulse, Vector2::new(0.15, 0.0), 1e-6);
     }
+
+    fn spawn_in(world: &mut MockWorld) -> Plankton {
+        let mut plankton = Plankton::new(0.08);
+        plankton.spawn_rapier(
+            &mut world.rigid_body_set,
+            &mut world.collider_set,
+            &mut world.impulse_joint_set,
+            Vector2::zeros(),
+            MockWorld::SUBJECT_ID,
+        );
+        plankton
+    }
+
+    #[test]
+    fn tired_plankton_rests_until_recovered() {
+        let mut world = MockWorld::new();
+        let mut plankton = spawn_in(&mut world);
+
+        plankton.attributes.energy = plankton.attributes.max_energy * 0.1;
+        world.update(&mut plankton, 1.0 / 60.0);
+        assert_eq!(plankton.current_state(), CreatureState::Resting);
+
+        plankton.attributes.energy = plankton.attributes.max_energy;
+        world.update(&mut plankton, 1.0 / 60.0);
+        assert_eq!(plankton.current_state(), CreatureState::Wandering);
+    }
+
+    #[test]
+    fn wandering_plankton_steers_toward_sensed_plankton() {
+        let mut world = MockWorld::new();
+        let mut plankton = spawn_in(&mut world);
+        plankton.attributes.energy = plankton.attributes.max_energy;
+        world.add_neighbor("Plankton", Vector2::new(0.5, 0.0), Vector2::zeros());
+
+        world.update(&mut plankton, 1.0 / 60.0);
+
+        // Cohesion (0.15) outweighs the random wander impulse (at most 0.05 per axis)
+        assert_eq!(plankton.current_state(), CreatureState::Wandering);
+        assert!(world.velocity(&plankton).x > 0.0);
+    }
+
+    #[test]
+    fn lays_eggs_only_when_due_and_rested() {
+        let mut plankton = Plankton::new(0.1);
+        plankton.egg_timer = 0.0;
+        assert_eq!(plankton.lay_egg(), None);
+
+        plankton.egg_timer = EGG_INTERVAL_SECONDS;
+        assert_eq!(plankton.lay_egg(), Some(Species::Plankton));
+        assert!(plankton.attributes.energy < plankton.attributes.max_energy * EGG_MIN_ENERGY_FRACTION);
+
+        // Too tired to lay again, even once it's due
+        plankton.egg_timer = EGG_INTERVAL_SECONDS;
+        assert_eq!(plankton.lay_egg(), None);
+    }
 }
 
+// Seconds between eggs, at best
+const EGG_INTERVAL_SECONDS: f32 = 40.0;
+// Only well-rested plankton lay, and laying costs this fraction of their max energy
+const EGG_MIN_ENERGY_FRACTION: f32 = 0.8;
+const EGG_ENERGY_COST_FRACTION: f32 = 0.3;
+
+// Layout version of the saved state. Bump it whenever the saved fields change, see
+// `creatures::encode_state`.
+const STATE_VERSION: u32 = 1;
+
+const TUNABLES: &[Param<Plankton>] = &[
+    param!("Perception", 1.0, 30.0, boids.perception),
+    param!("Cohesion", 0.0, 1.0, boids.cohesion),
+    param!("Separation", 0.0, 1.0, boids.separation),
+    param!("Alignment", 0.0, 1.0, boids.alignment),
+    param!("Bladder rate", 0.0, 2.0, bladder.rate),
+    param!("Dimmest light", 0.0, 1.0, phototaxis.min_intensity),
+    param!("Brightest light", 0.0, 1.0, phototaxis.max_intensity),
+    param!("Light pull", 0.0, 0.2, phototaxis.strength),
+];
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Plankton {
-    id: u128,
+    id: CreatureId,
     segment_handles: Vec<RigidBodyHandle>, // Changed from single handle
     joint_handle: Option<ImpulseJointHandle>, // Added joint handle
     attributes: CreatureAttributes,
     current_state: CreatureState,
     pub primary_radius: f32, // Renamed from radius
     pub secondary_radius: f32, // Added second radius
+    // Decorative, trailing from the secondary segment
+    cilia: Vec<Appendage>,
+    // Seconds since the last egg
+    egg_timer: f32,
+    // How strongly it flocks
+    boids: BoidWeights,
+    // Floats it up to the light and lets it settle to rest
+    bladder: BuoyancyController,
+    // The light it feeds in, and how hard it swims for it
+    phototaxis: Phototaxis,
+    // How it hugs the glass when stressed, and which side it's keeping the wall on
+    wall_following: WallFollowing,
+    wall_side: Option<WallSide>,
 }
 
 #[allow(dead_code)]
@@ -247,17 +352,38 @@ impl Plankton {
             DietType::Herbivore, // Placeholder
             size,
             vec![],
-            vec!["plankton".to_string(), "small_food".to_string()],
+            vec!["plankton".to_string(), "small_food".to_string(), crate::symbiosis::CLEANER_TAG.to_string()],
         );
 
         Self {
-            id: 0,
+            id: CreatureId::default(),
             segment_handles: Vec::with_capacity(2),
             joint_handle: None,
             attributes,
             current_state: CreatureState::Wandering,
             primary_radius,
             secondary_radius,
+            cilia: [-0.5, 0.0, 0.5]
+                .into_iter()
+                .map(|angle| Appendage {
+                    parent_segment: 1,
+                    base_angle: angle,
+                    root_offset: secondary_radius * 0.8,
+                    link_count: 3,
+                    link_length: secondary_radius * 0.7,
+                    curl: 0.0,
+                    drag: 0.5,
+                    width: secondary_radius * 0.2,
+                })
+                .collect(),
+            // Stagger so a tank of new plankton doesn't lay all at once
+            egg_timer: random::rng().gen_range(0.0..EGG_INTERVAL_SECONDS),
+            boids: BoidWeights::default(),
+            bladder: BuoyancyController::default(),
+            // About the top 40% of a standard tank
+            phototaxis: Phototaxis { response: LightResponse::Positive, strength: 0.02, min_intensity: 0.45, max_intensity: 0.9 },
+            wall_following: WallFollowing { distance: primary_radius * 3.0, range: primary_radius * 10.0 },
+            wall_side: None,
         }
     }
 
@@ -268,7 +394,7 @@ impl Plankton {
         collider_set: &mut ColliderSet,
         impulse_joint_set: &mut ImpulseJointSet, // Added joint set param
         initial_position: Vector2<f32>,
-        creature_id: u128,
+        creature_id: CreatureId,
     ) {
         self.id = creature_id;
         self.segment_handles.clear();
@@ -290,7 +416,9 @@ impl Plankton {
         let collider1 = ColliderBuilder::ball(self.primary_radius)
                          .restitution(0.1)
                          .density(10.0)
-                         .user_data(creature_id)
+                         .user_data(ColliderTag::creature(creature_id, 0).pack())
+                         .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                         .contact_force_event_threshold(IMPACT_FORCE_THRESHOLD)
                          .build();
         collider_set.insert_with_parent(collider1, handle1, rigid_body_set);
 
@@ -309,7 +437,9 @@ impl Plankton {
         let collider2 = ColliderBuilder::ball(self.secondary_radius)
                          .restitution(0.1)
                          .density(10.0)
-                         .user_data(creature_id)
+                         .user_data(ColliderTag::creature(creature_id, 1).pack())
+                         .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                         .contact_force_event_threshold(IMPACT_FORCE_THRESHOLD)
                          .build();
         collider_set.insert_with_parent(collider2, handle2, rigid_body_set);
 
@@ -332,73 +462,36 @@ impl Plankton {
         rigid_body_set: &mut RigidBodySet,
         world_context: &WorldContext, 
     ) {
-        // Constants for controlling net vertical acceleration (relative to world gravity magnitude of 1.0)
-        const BASE_BUOYANCY_FORCE: f32 = 0.002;  // Base force magnitude
-        const NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_LOW: f32 = 0.02;    
-        const NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_HIGH: f32 = -0.2;   
-        const NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_INZONE: f32 = 0.0;  
-        const NET_GRAVITY_ACCEL_SCALE_WANDERING: f32 = -0.05;         
-        const NET_GRAVITY_ACCEL_SCALE_RESTING: f32 = -0.1;            
-
-        // Add oscillation parameters
-        const OSCILLATION_AMPLITUDE: f32 = 0.05;  
-        const OSCILLATION_FREQUENCY: f32 = 0.3;   
-
         // Velocity control parameters
         const MAX_VERTICAL_SPEED: f32 = 0.5;  // Maximum vertical speed
         const VERTICAL_DAMPING: f32 = 0.1;    // Damping factor for vertical movement
         const HORIZONTAL_DAMPING: f32 = 0.05; // Damping factor for horizontal movement
 
-        let light_zone_target_min_y = world_context.world_height * 0.05;
-        let light_zone_target_max_y = world_context.world_height * 0.35;
-
         for handle in &self.segment_handles {
             if let Some(body) = rigid_body_set.get_mut(*handle) {
-                let current_y = body.translation().y;
-                let current_x = body.translation().x;
                 let current_velocity = *body.linvel();
-                
-                // Calculate oscillation based on x position to create a wave-like pattern
-                let oscillation = (current_x * OSCILLATION_FREQUENCY).sin() * OSCILLATION_AMPLITUDE;
-                
-                let target_net_accel_y_factor = match self.current_state {
-                    CreatureState::SeekingFood => {
-                        if current_y < light_zone_target_min_y {
-                            NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_LOW
-                        } else if current_y > light_zone_target_max_y {
-                            NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_HIGH
-                        } else {
-                            NET_GRAVITY_ACCEL_SCALE_SEEKING_FOOD_INZONE
-                        }
-                    }
-                    CreatureState::Wandering | CreatureState::Idle => {
-                        NET_GRAVITY_ACCEL_SCALE_WANDERING + oscillation
-                    }
-                    CreatureState::Resting => {
-                        NET_GRAVITY_ACCEL_SCALE_RESTING + oscillation * 0.5
-                    }
-                    CreatureState::Fleeing => {
-                        NET_GRAVITY_ACCEL_SCALE_WANDERING + oscillation
-                    }
-                };
+                // Plankton drift with the water: damping is drag against it, so cancel the
+                // part of the drag that the water's own motion accounts for
+                let flow = world_context.flow.velocity_at(*body.translation());
 
-                // Calculate base buoyancy force
-                let buoyancy_force_y = BASE_BUOYANCY_FORCE * (1.0 + target_net_accel_y_factor);
+                // The swim bladder does the rising and sinking
+                let buoyancy_force_y = self.bladder.lift(body.mass());
                 
                 // Apply velocity-dependent damping
                 let mut final_force_y = buoyancy_force_y;
                 
-                // Vertical velocity damping
-                if current_velocity.y.abs() > MAX_VERTICAL_SPEED {
+                // Vertical velocity damping, relative to the water
+                let relative_velocity = current_velocity - flow;
+                if relative_velocity.y.abs() > MAX_VERTICAL_SPEED {
                     // If exceeding max speed, apply strong damping
-                    final_force_y -= current_velocity.y * VERTICAL_DAMPING * 2.0;
+                    final_force_y -= relative_velocity.y * VERTICAL_DAMPING * 2.0;
                 } else {
                     // Normal damping
-                    final_force_y -= current_velocity.y * VERTICAL_DAMPING;
+                    final_force_y -= relative_velocity.y * VERTICAL_DAMPING;
                 }
                 
                 // Horizontal velocity damping
-                let damping_force_x = -current_velocity.x * HORIZONTAL_DAMPING;
+                let damping_force_x = -relative_velocity.x * HORIZONTAL_DAMPING;
                 
                 // // Debug logging for every 10th frame (roughly 6 times per second at 60fps)
                 // if self.id == 10 && self.id % 10 == 0 {  // Only log for plankton with ID 10
@@ -409,7 +502,7 @@ impl Plankton {
                 //         velocity = ?(current_velocity.x, current_velocity.y),
                 //         velocity_magnitude = current_velocity.norm(),
                 //         mass = body.mass(),
-                //         oscillation = oscillation,
+                //         flow = ?(flow.x, flow.y),
                 //         target_accel_factor = target_net_accel_y_factor,
                 //         buoyancy_force = buoyancy_force_y,
                 //         final_force = final_force_y,
@@ -431,21 +524,22 @@ impl Plankton {
                 // }
                 
                 // Add velocity damping if moving too fast
-                if current_velocity.norm() > 2.0 {
+                if relative_velocity.norm() > 2.0 {
                     body.set_linear_damping(20.0);
                 } else {
                     body.set_linear_damping(12.0);
                 }
+                let carried_by_flow = flow * body.mass() * body.linear_damping();
                 
                 // Apply the final forces
-                body.add_force(Vector2::new(damping_force_x, final_force_y), true);
+                body.add_force(Vector2::new(damping_force_x, final_force_y) + carried_by_flow, true);
             }
         }
     }
 }
 
 impl Creature for Plankton {
-    fn id(&self) -> u128 {
+    fn id(&self) -> CreatureId {
         self.id
     }
 
@@ -458,6 +552,12 @@ impl Creature for Plankton {
         self.joint_handle.as_slice()
     }
 
+    fn forget_joints(&mut self, joints: &[ImpulseJointHandle]) {
+        if self.joint_handle.is_some_and(|handle| joints.contains(&handle)) {
+            self.joint_handle = None;
+        }
+    }
+
     fn attributes(&self) -> &CreatureAttributes {
         &self.attributes
     }
@@ -474,6 +574,26 @@ impl Creature for Plankton {
         "Plankton"
     }
 
+    fn clone_box(&self) -> Box<dyn Creature> {
+        Box::new(self.clone())
+    }
+
+    fn to_data(&self) -> CreatureData {
+        CreatureData::Plankton(self.clone())
+    }
+
+    fn save_state(&self) -> Result<Vec<u8>, bincode::Error> {
+        creatures::encode_state(STATE_VERSION, self)
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), bincode::Error> {
+        match creatures::decode_version(state)? {
+            (STATE_VERSION, fields) => *self = bincode::deserialize(fields)?,
+            (version, _) => return Err(creatures::unknown_version("Plankton", version, STATE_VERSION)),
+        }
+        Ok(())
+    }
+
     fn current_state(&self) -> CreatureState {
         self.current_state
     }
@@ -481,68 +601,35 @@ impl Creature for Plankton {
     fn update_state_and_behavior(
         &mut self,
         dt: f32,
-        own_id: u128,
+        own_id: CreatureId,
         rigid_body_set: &mut RigidBodySet,
         _impulse_joint_set: &mut ImpulseJointSet,
-        collider_set: &ColliderSet,
-        query_pipeline: &QueryPipeline,
-        all_creatures_info: &Vec<CreatureInfo>,
+        senses: &dyn Senses,
         world_context: &WorldContext,
     ) {
-        // Boids parameters (can be tuned)
-        let perception_radius: f32 = self.primary_radius * 10.0;  // Reduced from 15.0
-        let separation_distance: f32 = self.primary_radius * 1.5;  // Reduced from 2.0
-        let cohesion_strength: f32 = 0.15;   // Reduced from 0.2
-        let separation_strength: f32 = 0.25;  // Reduced from 0.3
-        let alignment_strength: f32 = 0.1;    // Reduced from 0.15
-
-        let self_primary_handle = self.segment_handles.get(0).cloned().unwrap_or_else(RigidBodyHandle::invalid);
+        self.egg_timer += dt;
+
+        let perception_radius: f32 = self.primary_radius * self.boids.perception;
+        let separation_distance: f32 = self.primary_radius * self.boids.separation_distance;
+
+        let self_primary_handle = self.segment_handles.first().cloned().unwrap_or_else(RigidBodyHandle::invalid);
         let self_position = rigid_body_set.get(self_primary_handle).map_or(Vector2::zeros(), |b| *b.translation());
-        let _self_velocity = rigid_body_set.get(self_primary_handle).map_or(Vector2::zeros(), |b| *b.linvel());
+        let self_velocity = rigid_body_set.get(self_primary_handle).map_or(Vector2::zeros(), |b| *b.linvel());
 
-        // --- Sensing Phase using QueryPipeline --- 
+        // --- Sensing Phase --- 
         let mut boid_neighbors: Vec<BoidNeighborInfo> = Vec::new();
-        let perception_shape = Ball::new(perception_radius);
-        let perception_shape_pos = Isometry::new(self_position, 0.0);
-        
-        // Modified filter to include all creatures
-        let interaction_filter = InteractionGroups::new(Group::GROUP_1, Group::GROUP_1);
-        let query_filter = QueryFilter::new()
-            .groups(interaction_filter)
-            .exclude_rigid_body(self_primary_handle);
-
-        query_pipeline.intersections_with_shape(
-            rigid_body_set,
-            collider_set,
-            &perception_shape_pos,
-            &perception_shape,
-            query_filter,
-            |intersecting_collider_handle| {
-                let intersecting_collider = match collider_set.get(intersecting_collider_handle) {
-                    Some(c) => c,
-                    None => return true,
-                };
-
-                let creature_id_from_collider = intersecting_collider.user_data;
-                if creature_id_from_collider == u128::MAX { return true; } // Skip walls
-                if creature_id_from_collider == own_id { return true; } // Skip self
-
-                // Find this creature in all_creatures_info
-                if let Some(other_creature_info) = all_creatures_info.iter().find(|info| info.id == creature_id_from_collider) {
-                    if other_creature_info.creature_type_name == "Plankton" {
-                        // Only add if within perception radius
-                        let distance = (other_creature_info.position - self_position).norm();
-                        if distance <= perception_radius {
-                            boid_neighbors.push(BoidNeighborInfo {
-                                position: other_creature_info.position,
-                                velocity: other_creature_info.velocity,
-                            });
-                        }
-                    }
+        for other_creature_info in senses.creatures_near(rigid_body_set, self_position, perception_radius, own_id) {
+            if other_creature_info.creature_type_name == "Plankton" {
+                // Only add if within perception radius
+                let distance = (other_creature_info.position - self_position).norm();
+                if distance <= perception_radius {
+                    boid_neighbors.push(BoidNeighborInfo {
+                        position: other_creature_info.position,
+                        velocity: other_creature_info.velocity,
+                    });
                 }
-                true
-            },
-        );
+            }
+        }
 
         // Calculate Boid Impulse
         let boid_impulse = calculate_boid_steering_impulse(
@@ -550,9 +637,9 @@ impl Creature for Plankton {
             &boid_neighbors,
             perception_radius,
             separation_distance,
-            cohesion_strength,
-            separation_strength,
-            alignment_strength
+            self.boids.cohesion * self.attributes.personality.sociality,
+            self.boids.separation,
+            self.boids.alignment * self.attributes.personality.sociality
         );
 
         // // Debug logging for boids behavior
@@ -572,12 +659,11 @@ impl Creature for Plankton {
         let current_y = self_position.y;
 
         // Define energy thresholds for state changes
-        let energy_critically_low_threshold = self.attributes.max_energy * 0.21; // Changed from 0.25 
-        let energy_comfortable_threshold = self.attributes.max_energy * 0.65; 
+        let energy_critically_low_threshold = self.attributes.max_energy * 0.21 * self.attributes.personality.boldness; // Changed from 0.25 
 
-        // Define the "light zone" for SeekingFood behavior reference
-        let light_zone_ideal_min_y = world_context.world_height * 0.1; 
-        let light_zone_ideal_max_y = world_context.world_height * 0.45; // Slightly below absolute ceiling for safety
+        // The light it feeds in, for SeekingFood
+        let light = world_context.light_field();
+        let intensity = light.intensity_at(current_y);
 
         let mut next_state = self.current_state;
 
@@ -586,7 +672,7 @@ impl Creature for Plankton {
         } else {
             match self.current_state {
                 CreatureState::Resting => {
-                    if self.attributes.energy >= energy_comfortable_threshold {
+                    if self.attributes.is_rested(0.65) {
                         next_state = CreatureState::Wandering; 
                     }
                 }
@@ -596,10 +682,10 @@ impl Creature for Plankton {
                     }
                 }
                 CreatureState::SeekingFood => {
-                    if self.attributes.energy >= energy_comfortable_threshold {
+                    if self.attributes.is_rested(0.65) {
                          // Only switch to wandering if energy is high AND they are somewhat in a good spot
                          // This prevents them from immediately leaving the light zone if they just arrived.
-                        if current_y >= light_zone_ideal_min_y {
+                        if intensity >= self.phototaxis.min_intensity {
                             next_state = CreatureState::Wandering;
                         }
                     }
@@ -615,30 +701,58 @@ impl Creature for Plankton {
         }
         self.current_state = next_state;
 
+        // Rise to the light to feed, settle to rest, and otherwise go with the flow
+        self.bladder.goal = match self.current_state {
+            CreatureState::SeekingFood => DepthGoal::Hold(self.phototaxis.preferred_height(&light)),
+            CreatureState::Resting => DepthGoal::Sink,
+            CreatureState::Wandering | CreatureState::Idle | CreatureState::Fleeing => DepthGoal::Drift,
+        };
+        let bladder_cost = self.bladder.update(dt, current_y, self_velocity.y);
+        self.attributes.energy = (self.attributes.energy - bladder_cost).max(0.0);
+
+
+        let wall = if self.attributes.is_stressed() { senses.nearest_wall(rigid_body_set, self_position, f32::INFINITY) } else { None };
+        let wall_steering = match wall {
+            Some(wall) => {
+                let side = *self.wall_side.get_or_insert_with(|| WallSide::for_heading(&wall, self_velocity));
+                Some(self.wall_following.steering(&wall, side))
+            }
+            None => {
+                self.wall_side = None;
+                None
+            }
+        };
 
         // --- Execute Behavior based on State --- 
         match self.current_state {
             CreatureState::Wandering => {
                 if let Some(body) = rigid_body_set.get_mut(self_primary_handle) {
                     if self_primary_handle != RigidBodyHandle::invalid() { 
-                        let mut rng = rand::thread_rng();
+                        let mut rng = random::rng();
                         let impulse_strength = 0.05; // Increased from 0.02
-                        let random_impulse = Vector2::new(
-                            rng.gen_range(-impulse_strength..impulse_strength),
-                            rng.gen_range(-impulse_strength..impulse_strength)
-                        );
+                        // Stressed, it makes for the nearest wall and creeps along it instead of wandering
+                        let wander_impulse = match wall_steering {
+                            Some(direction) => direction * impulse_strength,
+                            None => Vector2::new(
+                                rng.gen_range(-impulse_strength..impulse_strength),
+                                rng.gen_range(-impulse_strength..impulse_strength)
+                            ),
+                        };
                         // Apply boid impulses along with random wandering
-                        body.apply_impulse(random_impulse + boid_impulse, true);
+                        body.apply_impulse(wander_impulse + boid_impulse, true);
                     }
                  }
             }
             CreatureState::SeekingFood => { 
                 // Energy recovery for plankton happens here if in light zone
                 let energy_cap_for_photosynthesis = self.attributes.max_energy * 0.9;
-                if current_y >= light_zone_ideal_min_y && current_y <= light_zone_ideal_max_y && self.attributes.energy < energy_cap_for_photosynthesis {
+                if self.phototaxis.in_band(intensity) && self.attributes.energy < energy_cap_for_photosynthesis {
                     self.attributes.energy = (self.attributes.energy + self.attributes.energy_recovery_rate * dt).min(self.attributes.max_energy);
                 }
-                // Buoyancy handles upward movement if needed (defined in apply_buoyancy_and_drag)
+                // The bladder does most of the rising; this nudges it toward the light
+                if let Some(body) = rigid_body_set.get_mut(self_primary_handle) {
+                    body.apply_impulse(self.phototaxis.steering(|point| light.intensity_at(point.y), self_position), true);
+                }
             }
             CreatureState::Resting => { /* Buoyancy handles sinking */ }
             CreatureState::Idle => { /* Do nothing */}
@@ -646,6 +760,33 @@ impl Creature for Plankton {
         }
     }
 
+    fn behavior(&self) -> Option<Behavior> {
+        Some(Behavior::Plankton(self.boids))
+    }
+
+    fn set_behavior(&mut self, behavior: Behavior) {
+        if let Behavior::Plankton(boids) = behavior {
+            self.boids = boids;
+        }
+    }
+
+    fn tunables(&self) -> Vec<Tunable> {
+        tunable::list(TUNABLES, self)
+    }
+
+    fn set_tunable(&mut self, name: &str, value: f32) -> bool {
+        tunable::set(TUNABLES, self, name, value)
+    }
+
+    fn lay_egg(&mut self) -> Option<Species> {
+        if self.egg_timer < EGG_INTERVAL_SECONDS || self.attributes.energy < self.attributes.max_energy * EGG_MIN_ENERGY_FRACTION {
+            return None;
+        }
+        self.egg_timer = 0.0;
+        self.attributes.consume_energy(self.attributes.max_energy * EGG_ENERGY_COST_FRACTION);
+        Some(Species::Plankton)
+    }
+
     fn apply_custom_forces(&self, rigid_body_set: &mut RigidBodySet, world_context: &WorldContext) {
         // Call the helper method, now passing world_context
         self.apply_buoyancy_and_drag(rigid_body_set, world_context);
@@ -658,7 +799,7 @@ impl Creature for Plankton {
         world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
         zoom: f32,
         is_hovered: bool,
-        pixels_per_meter: f32,
+        world_context: &WorldContext,
     ) {
         let base_color = match self.current_state() {
             CreatureState::Idle => egui::Color32::from_rgb(100, 120, 100), // Dull Greenish
@@ -668,14 +809,19 @@ impl Creature for Plankton {
             CreatureState::Fleeing => egui::Color32::TRANSPARENT, // Keep transparent or choose panic color
         };
 
+        let light = world_context.light_field();
+        // Young plankton are smaller and paler, old ones have a darker spot
+        let look = Look::of(&self.attributes);
+        let body_color = look.body(base_color);
+
         let handles = self.get_rigid_body_handles();
         if handles.len() != 2 { 
             // Fallback: Draw simple circles if we don't have exactly 2 segments
-            let screen_radius = self.primary_radius * pixels_per_meter * zoom;
+            let screen_radius = self.primary_radius * look.scale * world_context.pixels_per_meter * zoom;
             for handle in handles {
                 if let Some(body) = rigid_body_set.get(*handle) {
                     let screen_pos = world_to_screen(*body.translation());
-                    painter.circle_filled(screen_pos, screen_radius, base_color);
+                    painter.circle_filled(screen_pos, screen_radius, light.tint(body_color, body.translation().y));
                 }
             }
             return; 
@@ -686,8 +832,8 @@ impl Creature for Plankton {
         let pos2 = rigid_body_set.get(handles[1]).map(|b| *b.translation());
 
         if let (Some(p1), Some(p2)) = (pos1, pos2) {
-            let radius1 = self.primary_radius;
-            let radius2 = self.secondary_radius;
+            let radius1 = self.primary_radius * look.scale;
+            let radius2 = self.secondary_radius * look.scale;
 
             // Calculate direction and perpendicular vectors
             let direction = (p2 - p1).try_normalize(1e-6).unwrap_or_else(Vector2::zeros);
@@ -704,14 +850,14 @@ impl Creature for Plankton {
             // Convert to screen coordinates
             let skin_screen: Vec<egui::Pos2> = skin_world
                 .into_iter()
-                .map(|wp| world_to_screen(wp))
+                .map(world_to_screen)
                 .collect();
 
             if skin_screen.len() == 4 {
                 // Draw highlight outline
                 if is_hovered {
                     // Use average screen radius for highlight stroke thickness
-                    let avg_screen_radius = (radius1 + radius2) / 2.0 * pixels_per_meter * zoom;
+                    let avg_screen_radius = (radius1 + radius2) / 2.0 * world_context.pixels_per_meter * zoom;
                     painter.add(egui::Shape::convex_polygon(
                         skin_screen.clone(),
                         egui::Color32::TRANSPARENT,
@@ -721,22 +867,36 @@ impl Creature for Plankton {
                 // Draw the main skin polygon
                 painter.add(egui::Shape::convex_polygon(
                     skin_screen,
-                    base_color,
+                    light.tint(body_color, p1.y),
                     egui::Stroke::NONE,
                 ));
+                let pixels_per_world_unit = world_context.pixels_per_meter * zoom;
+                if look.pattern > 0.0 {
+                    painter.circle_filled(world_to_screen(p1), radius1 * 0.45 * pixels_per_world_unit, light.tint(look.marking(base_color), p1.y));
+                }
+                for (segment, (position, radius)) in [(p1, radius1), (p2, radius2)].into_iter().enumerate() {
+                    appearance::draw_wound(painter, world_to_screen(position), radius * pixels_per_world_unit, self.attributes.wound_at(segment));
+                }
+
+                // Cilia point away from the primary segment
+                let velocity = rigid_body_set.get(handles[1]).map_or_else(Vector2::zeros, |body| *body.linvel());
+                for cilium in &self.cilia {
+                    let points = cilium.points(p2, p2 - p1, velocity);
+                    cilium.draw(painter, &points, world_to_screen, pixels_per_world_unit, light.tint(body_color, p2.y));
+                }
             }
         } else {
             // Fallback if bodies not found (draw circles)
-            let screen_radius1 = self.primary_radius * pixels_per_meter * zoom;
-            let screen_radius2 = self.secondary_radius * pixels_per_meter * zoom;
+            let screen_radius1 = self.primary_radius * look.scale * world_context.pixels_per_meter * zoom;
+            let screen_radius2 = self.secondary_radius * look.scale * world_context.pixels_per_meter * zoom;
              if let Some(body) = rigid_body_set.get(handles[0]) {
                  let screen_pos = world_to_screen(*body.translation());
-                 painter.circle_filled(screen_pos, screen_radius1, base_color);
+                 painter.circle_filled(screen_pos, screen_radius1, light.tint(body_color, body.translation().y));
              }
               if let Some(body) = rigid_body_set.get(handles[1]) {
                  let screen_pos = world_to_screen(*body.translation());
-                 painter.circle_filled(screen_pos, screen_radius2, base_color);
+                 painter.circle_filled(screen_pos, screen_radius2, light.tint(body_color, body.translation().y));
              }
         }
     }
-} 
+}
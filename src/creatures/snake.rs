@@ -1,18 +1,59 @@
 use rapier2d::prelude::*;
 use nalgebra::{Point2, Vector2};
 use eframe::egui; // Add egui import
-use rand::{self, Rng}; // Add Rng trait import
-
-use crate::creature::{Creature, CreatureState, WorldContext, CreatureInfo}; // Add WorldContext and CreatureInfo import
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::appendage::Appendage;
+use crate::appearance::{self, Look};
+use crate::behavior::{Behavior, SnakeBehavior};
+use crate::collider_tag::ColliderTag;
+use crate::random;
+use crate::impacts::IMPACT_FORCE_THRESHOLD;
+use crate::creature::{Creature, CreatureId, CreatureState, WorldContext, Senses};
 use crate::creature_attributes::{CreatureAttributes, DietType}; // Use package name
-
+use crate::creatures::{self, CreatureData};
+use crate::gait::Gait;
+use crate::joint_profile::JointProfile;
+use crate::tunable::{self, param, Param, Tunable};
+use crate::wall_following::{WallFollowing, WallSide};
+
+// Heavier than plankton, so a flock piling onto the body can't crush the chain apart
+const SEGMENT_DENSITY: f32 = 10.0;
+// Swimming and drag forces were tuned for segments of density 3. Scaling them keeps the pace.
+const FORCE_SCALE: f32 = SEGMENT_DENSITY / 3.0;
+
+// Layout version of the saved state. Bump it whenever the saved fields change, see
+// `creatures::encode_state`.
+const STATE_VERSION: u32 = 1;
+
+const TUNABLES: &[Param<Snake>] = &[
+    param!("Wiggle amplitude", 0.0, 3.0, gait.amplitude),
+    param!("Wiggle frequency", 0.0, 3.0, gait.frequency),
+    param!("Wave length", 0.1, 3.0, gait.wave_length),
+    param!("Sideways drag", 0.0, 50.0, perp_drag),
+    param!("Forward drag", 0.0, 20.0, forward_drag),
+    param!("Aggression", 0.0, 2.0, behavior.aggression),
+    param!("Wall distance", 0.1, 2.0, wall_following.distance),
+];
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Snake {
-    id: u128, // Added creature ID field
+    id: CreatureId, // Added creature ID field
     segment_handles: Vec<RigidBodyHandle>,
     joint_handles: Vec<ImpulseJointHandle>,
     pub segment_radius: f32, // Made public for drawing access in app.rs
     segment_count: usize,
     segment_spacing: f32,
+    // Stiffness, damping and bend limit of each joint, from head to tail
+    joint_profile: JointProfile,
+    // The snake's own swimming wave, scaled by each state
+    gait: Gait,
+    // How it goes after food
+    behavior: SnakeBehavior,
+    // Water resistance across and along each segment, before FORCE_SCALE
+    perp_drag: f32,
+    forward_drag: f32,
     wiggle_timer: f32, // Timer to control the wiggle animation
     rest_timer: f32,   // Timer to track rest time
     attributes: CreatureAttributes, // Added attributes field
@@ -22,11 +63,18 @@ pub struct Snake {
     target_update_timer: f32,
     last_position: Vector2<f32>,
     stuck_timer: f32,
+    // How it swims along a wall that's in the way, and which side it's keeping the wall on
+    // while it does. `wall_steering` is the direction to swim this tick, when following one.
+    wall_following: WallFollowing,
+    wall_side: Option<WallSide>,
+    wall_steering: Option<Vector2<f32>>,
     // Add debug fields
     debug_info: DebugInfo,
+    // Decorative, drawn from the head
+    antennae: Vec<Appendage>,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 struct DebugInfo {
     max_velocity: f32,
     collision_count: u32,
@@ -48,21 +96,26 @@ impl Snake {
             1.0,                  // metabolic_rate
             DietType::Carnivore,  // diet_type (let's make it a carnivore for now)
             size,                 // size
-            vec!["small_fish".to_string(), "worm".to_string()], // prey_tags
+            vec!["small_fish".to_string(), "worm".to_string(), "egg".to_string()], // prey_tags
             vec!["snake".to_string(), "medium_predator".to_string()], // self_tags
         );
 
         // Initialize rest_timer with a random value between 0 and 5 seconds
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         let rest_timer = rng.gen_range(0.0..5.0);
 
         Self {
-            id: 0, // Default ID, will be overwritten in spawn_rapier
+            id: CreatureId::default(), // Overwritten in spawn_rapier
             segment_handles: Vec::with_capacity(segment_count),
             joint_handles: Vec::with_capacity(segment_count.saturating_sub(1)),
             segment_radius,
             segment_count,
             segment_spacing,
+            joint_profile: JointProfile::tapered(),
+            gait: Gait::default(),
+            behavior: SnakeBehavior::default(),
+            perp_drag: 15.0,
+            forward_drag: 5.0,
             wiggle_timer: 0.0, // Initialize timer
             rest_timer,        // Initialize with random value
             attributes,        // Initialize attributes
@@ -71,7 +124,23 @@ impl Snake {
             target_update_timer: 0.0,
             last_position: Vector2::zeros(),
             stuck_timer: 0.0,
+            wall_following: WallFollowing { distance: segment_radius * 6.0, range: segment_radius * 15.0 },
+            wall_side: None,
+            wall_steering: None,
             debug_info: DebugInfo::default(),
+            antennae: [-1.0, 1.0]
+                .into_iter()
+                .map(|side| Appendage {
+                    parent_segment: 0,
+                    base_angle: side * 0.45,
+                    root_offset: segment_radius * 0.8,
+                    link_count: 4,
+                    link_length: segment_radius * 0.6,
+                    curl: side * 0.12, // Arc outward
+                    drag: 0.3,
+                    width: segment_radius * 0.25,
+                })
+                .collect(),
         }
     }
 
@@ -82,14 +151,14 @@ impl Snake {
         collider_set: &mut ColliderSet,
         impulse_joint_set: &mut ImpulseJointSet,
         initial_position: Vector2<f32>,
-        creature_id: u128,
+        creature_id: CreatureId,
     ) {
         self.id = creature_id;
         self.segment_handles.clear();
         self.joint_handles.clear();
 
         let mut parent_handle: Option<RigidBodyHandle> = None;
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         
         let initial_angle: f32 = rng.gen_range(-0.02..0.02); // Moderate angle range
         
@@ -111,21 +180,24 @@ impl Snake {
             // Create Collider with moderate parameters
             let collider = ColliderBuilder::ball(self.segment_radius)
                 .restitution(0.0)  // No bounce
-                .density(3.0)      // Moderate density
+                .density(SEGMENT_DENSITY)
                 .friction(0.1)     // Moderate friction
-                .user_data(creature_id)
+                .user_data(ColliderTag::creature(creature_id, i as u32).pack())
+                .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(IMPACT_FORCE_THRESHOLD)
                 .build();
             collider_set.insert_with_parent(collider, segment_handle, rigid_body_set);
 
-            // Create joint with moderate parameters
+            // Create joint, as stiff as the profile says for this point along the body
             if let Some(prev_handle) = parent_handle {
+                let setting = self.joint_profile.at(i - 1, self.segment_count - 1);
                 let joint = RevoluteJointBuilder::new()
                     .local_anchor1(Point2::new(self.segment_spacing / 2.0, 0.0))
                     .local_anchor2(Point2::new(-self.segment_spacing / 2.0, 0.0))
-                    .motor_velocity(0.0, 0.0)
+                    .motor(0.0, 0.0, setting.stiffness, setting.damping)
                     .motor_max_force(0.3)  // Moderate force
                     .motor_model(MotorModel::ForceBased)
-                    .limits([-0.02, 0.02])   // Moderate limits
+                    .limits([-setting.limit, setting.limit])
                     .build();
                 let joint_handle = impulse_joint_set.insert(prev_handle, segment_handle, joint, true);
                 self.joint_handles.push(joint_handle);
@@ -137,16 +209,15 @@ impl Snake {
 
     // Add new method to update target position
     fn update_target_position(&mut self, _rigid_body_set: &RigidBodySet, world_context: &WorldContext) {
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         
         // Update target every 3-5 seconds or if we're stuck
         if self.target_position.is_none() || self.target_update_timer > rng.gen_range(3.0..5.0) || self.stuck_timer > 1.0 {
             // Generate new target within world bounds
-            // Use world_height and assume square world for now
-            let world_size = world_context.world_height;
+            let half = world_context.half_extents();
             let new_target = Vector2::new(
-                rng.gen_range(-world_size/2.0..world_size/2.0),
-                rng.gen_range(-world_size/2.0..world_size/2.0)
+                rng.gen_range(-half.x..half.x),
+                rng.gen_range(-half.y..half.y)
             );
             self.target_position = Some(new_target);
             self.target_update_timer = 0.0;
@@ -172,6 +243,33 @@ impl Snake {
         }
     }
 
+    // Follows the nearest wall this tick if the snake is heading into it or it's between the
+    // head and the target, which also walks the snake out of corners instead of pushing into
+    // them. Stressed snakes look for a wall from anywhere and hug it.
+    fn update_wall_following(&mut self, rigid_body_set: &RigidBodySet, senses: &dyn Senses) {
+        let head = self.segment_handles.first().and_then(|&handle| rigid_body_set.get(handle));
+        let stressed = self.attributes.is_stressed();
+        let range = if stressed { f32::INFINITY } else { self.wall_following.range };
+        let wall = head.and_then(|head| {
+            let position = *head.translation();
+            let heading = Vector2::new(head.rotation().angle().cos(), head.rotation().angle().sin());
+            let wall = senses.nearest_wall(rigid_body_set, position, range)?;
+            let blocked = self.wall_following.blocks(&wall, heading)
+                || self.target_position.is_some_and(|target| self.wall_following.blocks(&wall, target - position));
+            (stressed || blocked).then_some((wall, heading))
+        });
+        match wall {
+            Some((wall, heading)) => {
+                let side = *self.wall_side.get_or_insert_with(|| WallSide::for_heading(&wall, heading));
+                self.wall_steering = Some(self.wall_following.steering(&wall, side));
+            }
+            None => {
+                self.wall_side = None;
+                self.wall_steering = None;
+            }
+        }
+    }
+
     // Add method to check for self-collision and problematic states
     fn check_safety(&mut self, rigid_body_set: &RigidBodySet, dt: f32) -> bool {
         let mut is_safe = true;
@@ -261,28 +359,30 @@ impl Snake {
 
     // Add method to check if position is within bounds
     fn is_within_bounds(&self, pos: Vector2<f32>, world_context: &WorldContext) -> bool {
-        let half_size = world_context.world_height / 2.0;
+        let half = world_context.half_extents();
         let margin = self.segment_radius * 3.0; // Increased margin for better safety
         
-        pos.x.abs() < half_size - margin && pos.y.abs() < half_size - margin
+        pos.x.abs() < half.x - margin && pos.y.abs() < half.y - margin
     }
 
     // Add method to get a safe position within bounds
     fn get_safe_position(&self, world_context: &WorldContext) -> Vector2<f32> {
-        let half_size = world_context.world_height / 2.0;
+        let half = world_context.half_extents();
         let margin = self.segment_radius * 6.0; // Increased margin for better safety
-        let mut rng = rand::thread_rng();
+        // The body is laid out to the right of the returned position
+        let body_length = self.segment_spacing * self.segment_count.saturating_sub(1) as f32;
+        let mut rng = random::rng();
         
         Vector2::new(
-            rng.gen_range(-half_size + margin..half_size - margin),
-            rng.gen_range(-half_size + margin..half_size - margin)
+            rng.gen_range(-half.x + margin..half.x - margin - body_length),
+            rng.gen_range(-half.y + margin..half.y - margin)
         )
     }
 
     // Add method to reset snake to a safe position
     fn reset_to_safe_position(&mut self, rigid_body_set: &mut RigidBodySet, world_context: &WorldContext) {
         let base_pos = self.get_safe_position(world_context);
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         let initial_angle: f32 = rng.gen_range(-0.01..0.01); // Reduced angle range for more stability
 
         // Reset each segment to a proper formation with gentle curve
@@ -305,19 +405,20 @@ impl Snake {
         self.stuck_timer = 0.0;
         self.target_position = None;
         self.target_update_timer = 0.0;
+        self.wall_side = None;
         self.last_position = base_pos;
     }
 
     // Add method to calculate boundary avoidance force
     fn calculate_boundary_force(&self, pos: Vector2<f32>, world_context: &WorldContext) -> Option<Vector2<f32>> {
-        let half_size = world_context.world_height / 2.0;
+        let half = world_context.half_extents();
         let margin = self.segment_radius * 3.0; // Moderate margin
         
         // Calculate distance to each boundary
-        let dist_to_right = half_size - pos.x;
-        let dist_to_left = half_size + pos.x;
-        let dist_to_top = half_size - pos.y;
-        let dist_to_bottom = half_size + pos.y;
+        let dist_to_right = half.x - pos.x;
+        let dist_to_left = half.x + pos.x;
+        let dist_to_top = half.y - pos.y;
+        let dist_to_bottom = half.y + pos.y;
         
         // If we're too close to any boundary, calculate avoidance force
         if dist_to_right < margin || dist_to_left < margin || dist_to_top < margin || dist_to_bottom < margin {
@@ -348,19 +449,19 @@ impl Snake {
 
     // Add method to clamp position within bounds
     fn clamp_position(&self, pos: Vector2<f32>, world_context: &WorldContext) -> Vector2<f32> {
-        let half_size = world_context.world_height / 2.0;
+        let half = world_context.half_extents();
         let margin = self.segment_radius * 3.0; // Increased margin
         
         Vector2::new(
-            pos.x.clamp(-half_size + margin, half_size - margin),
-            pos.y.clamp(-half_size + margin, half_size - margin)
+            pos.x.clamp(-half.x + margin, half.x - margin),
+            pos.y.clamp(-half.y + margin, half.y - margin)
         )
     }
 
     // Add method to check and correct all segments
     fn check_and_correct_segments(&mut self, rigid_body_set: &mut RigidBodySet, world_context: &WorldContext) {
         let mut needs_reset = false;
-        let half_size = world_context.world_height / 2.0;
+        let half = world_context.half_extents();
         let margin = self.segment_radius * 3.0; // Moderate margin
         
         // Check all segments for boundary violations
@@ -369,29 +470,31 @@ impl Snake {
                 let pos = Vector2::new(body.translation().x, body.translation().y);
                 
                 // Check if out of bounds
-                if pos.x.abs() >= half_size - margin || pos.y.abs() >= half_size - margin {
+                if pos.x.abs() >= half.x - margin || pos.y.abs() >= half.y - margin {
                     // Calculate correction force
                     let mut correction = Vector2::zeros();
                     
                     // X-axis correction
-                    if pos.x.abs() >= half_size - margin {
+                    if pos.x.abs() >= half.x - margin {
                         correction.x = -pos.x.signum() * 20.0; // Moderate correction force
                     }
                     
                     // Y-axis correction
-                    if pos.y.abs() >= half_size - margin {
+                    if pos.y.abs() >= half.y - margin {
                         correction.y = -pos.y.signum() * 20.0; // Moderate correction force
                     }
                     
-                    // Apply correction force
-                    body.add_force(correction, true);
+                    // Apply correction force. Scaled by mass so it's really an acceleration:
+                    // a flat 20N is ~3.5m/s per tick on a segment, enough to fling it through the wall.
+                    let mass = body.mass();
+                    body.add_force(correction * mass, true);
                     
                     // Moderate damping when near boundaries
                     let vel = body.linvel();
                     body.set_linvel(vel * 0.8, true); // Moderate velocity reduction
                     
                     // If too close to boundary, mark for reset
-                    if pos.x.abs() >= half_size - margin/2.0 || pos.y.abs() >= half_size - margin/2.0 {
+                    if pos.x.abs() >= half.x - margin/2.0 || pos.y.abs() >= half.y - margin/2.0 {
                         needs_reset = true;
                     }
                 }
@@ -408,7 +511,8 @@ impl Snake {
             if let Some(body) = rigid_body_set.get_mut(*handle) {
                 let pos = Vector2::new(body.translation().x, body.translation().y);
                 if let Some(force) = self.calculate_boundary_force(pos, world_context) {
-                    body.add_force(force, true);
+                    let mass = body.mass();
+                    body.add_force(force * mass, true);
                 }
             }
         }
@@ -419,11 +523,13 @@ impl Snake {
         dt: f32,
         impulse_joint_set: &mut ImpulseJointSet,
         rigid_body_set: &mut RigidBodySet,
-        mut amplitude_scale: f32,
-        mut frequency_scale: f32,
+        amplitude_scale: f32,
+        frequency_scale: f32,
         energy_cost_scale: f32,
     ) {
-        let id_based_phase = (self.id as f32) * 0.1;
+        let id_based_phase = (self.id.0 as f32) * 0.1;
+        let amplitude_scale = amplitude_scale * self.gait.amplitude;
+        let frequency_scale = frequency_scale * self.gait.frequency;
         self.wiggle_timer += dt * frequency_scale;
 
         // Get the head segment's current orientation and position
@@ -432,8 +538,10 @@ impl Snake {
                 let head_pos = Vector2::new(head_body.translation().x, head_body.translation().y);
                 let head_angle = head_body.rotation().angle();
                 
-                // Calculate desired direction based on target
-                let desired_direction = if let Some(target) = self.target_position {
+                // Calculate desired direction based on the wall being followed, or else the target
+                let desired_direction = if let Some(direction) = self.wall_steering {
+                    direction
+                } else if let Some(target) = self.target_position {
                     (target - head_pos).try_normalize(1e-6).unwrap_or_else(Vector2::zeros)
                 } else {
                     Vector2::new(head_angle.cos(), head_angle.sin())
@@ -447,8 +555,10 @@ impl Snake {
                 let angular_velocity = clamped_angle * 0.1;  // Moderate torque
                 head_body.set_angvel(angular_velocity.clamp(-max_angular_velocity, max_angular_velocity), true);
 
-                // Moderate forward force with maximum velocity
-                let forward_force = current_dir * 0.2 * amplitude_scale;  // Moderate force
+                // Moderate forward force with maximum velocity. Along a wall it swims the way the
+                // wall runs, not the way it happens to face, which would only press it into the wall.
+                let thrust_direction = self.wall_steering.unwrap_or(current_dir);
+                let forward_force = thrust_direction * 0.2 * FORCE_SCALE * amplitude_scale;  // Moderate force
                 let current_vel = head_body.linvel();
                 let max_velocity = 2.0;  // Moderate maximum linear velocity
                 if current_vel.norm() < max_velocity {
@@ -459,15 +569,17 @@ impl Snake {
                 }
 
                 // Moderate wave pattern
-                let wave_length = 1.0;
+                let wave_length = self.gait.wave_length;
                 let wave_amplitude = 0.01 * amplitude_scale;  // Moderate amplitude
 
+                let joint_count = self.joint_handles.len();
                 for (i, handle) in self.joint_handles.iter().enumerate() {
                     if let Some(joint) = impulse_joint_set.get_mut(*handle) {
                         let segment_phase = (i as f32) * wave_length;
                         let phase = self.wiggle_timer + segment_phase + id_based_phase;
                         let target_velocity = (phase.sin() * wave_amplitude) * frequency_scale;
-                        joint.data.set_motor_velocity(JointAxis::AngX, target_velocity, 0.1);  // Moderate motor force
+                        let setting = self.joint_profile.at(i, joint_count);
+                        joint.data.set_motor(JointAxis::AngX, 0.0, target_velocity, setting.stiffness, setting.damping);
                     }
                 }
 
@@ -564,7 +676,7 @@ impl Snake {
     }
 
     // Add method to handle collision events
-    fn handle_collision(&mut self, rigid_body_set: &mut RigidBodySet, other_id: u128) {
+    fn handle_collision(&mut self, rigid_body_set: &mut RigidBodySet, _other_id: CreatureId) {
         // If we collide with another snake, reduce our velocity to prevent glitches
         if let Some(head_handle) = self.segment_handles.first() {
             if let Some(head_body) = rigid_body_set.get_mut(*head_handle) {
@@ -577,7 +689,7 @@ impl Snake {
 }
 
 impl Creature for Snake {
-    fn id(&self) -> u128 {
+    fn id(&self) -> CreatureId {
         self.id
     }
 
@@ -589,6 +701,53 @@ impl Creature for Snake {
         &self.joint_handles
     }
 
+    fn forget_joints(&mut self, joints: &[ImpulseJointHandle]) {
+        self.joint_handles.retain(|handle| !joints.contains(handle));
+    }
+
+    fn joint_profile(&self) -> Option<JointProfile> {
+        Some(self.joint_profile)
+    }
+
+    fn gait(&self) -> Option<Gait> {
+        Some(self.gait)
+    }
+
+    fn set_gait(&mut self, gait: Gait) {
+        self.gait = gait;
+    }
+
+    fn behavior(&self) -> Option<Behavior> {
+        Some(Behavior::Snake(self.behavior))
+    }
+
+    fn set_behavior(&mut self, behavior: Behavior) {
+        if let Behavior::Snake(behavior) = behavior {
+            self.behavior = behavior;
+        }
+    }
+
+    fn tunables(&self) -> Vec<Tunable> {
+        tunable::list(TUNABLES, self)
+    }
+
+    fn set_tunable(&mut self, name: &str, value: f32) -> bool {
+        tunable::set(TUNABLES, self, name, value)
+    }
+
+    fn set_joint_profile(&mut self, profile: JointProfile, impulse_joint_set: &mut ImpulseJointSet) {
+        self.joint_profile = profile;
+        // The motor parts are set again on the next wiggle; the limits only change here
+        let joint_count = self.joint_handles.len();
+        for (i, handle) in self.joint_handles.iter().enumerate() {
+            if let Some(joint) = impulse_joint_set.get_mut(*handle) {
+                let setting = profile.at(i, joint_count);
+                joint.data.set_limits(JointAxis::AngX, [-setting.limit, setting.limit]);
+                joint.data.set_motor(JointAxis::AngX, 0.0, 0.0, setting.stiffness, setting.damping);
+            }
+        }
+    }
+
     // Implement required methods
     fn attributes(&self) -> &CreatureAttributes {
         &self.attributes
@@ -606,6 +765,26 @@ impl Creature for Snake {
         "Snake"
     }
 
+    fn clone_box(&self) -> Box<dyn Creature> {
+        Box::new(self.clone())
+    }
+
+    fn to_data(&self) -> CreatureData {
+        CreatureData::Snake(self.clone())
+    }
+
+    fn save_state(&self) -> Result<Vec<u8>, bincode::Error> {
+        creatures::encode_state(STATE_VERSION, self)
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), bincode::Error> {
+        match creatures::decode_version(state)? {
+            (STATE_VERSION, fields) => *self = bincode::deserialize(fields)?,
+            (version, _) => return Err(creatures::unknown_version("Snake", version, STATE_VERSION)),
+        }
+        Ok(())
+    }
+
     fn current_state(&self) -> CreatureState {
         self.current_state
     }
@@ -613,12 +792,10 @@ impl Creature for Snake {
     fn update_state_and_behavior(
         &mut self,
         dt: f32,
-        _own_id: u128,
+        _own_id: CreatureId,
         rigid_body_set: &mut RigidBodySet,
         impulse_joint_set: &mut ImpulseJointSet,
-        _collider_set: &ColliderSet,
-        _query_pipeline: &QueryPipeline,
-        _all_creatures_info: &Vec<CreatureInfo>,
+        senses: &dyn Senses,
         world_context: &WorldContext,
     ) {
         // Check and correct all segments for boundary violations
@@ -627,6 +804,7 @@ impl Creature for Snake {
         // Update target position and check if stuck
         self.update_target_position(rigid_body_set, world_context);
         self.check_if_stuck(rigid_body_set);
+        self.update_wall_following(rigid_body_set, senses);
         self.target_update_timer += dt;
 
         // --- State Transition Logic --- 
@@ -646,19 +824,20 @@ impl Creature for Snake {
             next_state = CreatureState::Resting;
         } else if self.attributes.is_hungry() {
              // TODO: Add sensing check here. If food nearby, switch to SeekingFood
-             // For now, just keep wandering even if hungry, until we have sensing.
+             // For now, only aggressive snakes hunt, and blindly.
+             let hunting = if self.behavior.aggression > 0.0 { CreatureState::SeekingFood } else { CreatureState::Wandering };
              if self.current_state == CreatureState::Resting { 
                  // If rested enough, start wandering again
-                 if self.attributes.energy > self.attributes.max_energy * 0.5 { // Example threshold to stop resting
-                     next_state = CreatureState::Wandering;
+                 if self.attributes.is_rested(0.5) { // Example threshold to stop resting
+                     next_state = hunting;
                  }
-             } else { // If not resting, default to wandering
-                 next_state = CreatureState::Wandering;
+             } else { // If not resting, hunt or wander
+                 next_state = hunting;
              }
         } else { // Not tired, not hungry
              if self.current_state == CreatureState::Resting { 
                  // If rested enough, start wandering again
-                 if self.attributes.energy > self.attributes.max_energy * 0.8 { // Higher threshold to stop resting if not hungry
+                 if self.attributes.is_rested(0.8) { // Higher threshold to stop resting if not hungry
                      next_state = CreatureState::Wandering;
                  }
              } else { // If not resting, default to wandering
@@ -682,16 +861,19 @@ impl Creature for Snake {
             }
             CreatureState::Resting => {
                 let motor_force_factor = 2.0;
-                for handle in self.joint_handles.iter() {
+                let joint_count = self.joint_handles.len();
+                for (i, handle) in self.joint_handles.iter().enumerate() {
                     if let Some(joint) = impulse_joint_set.get_mut(*handle) {
-                        joint.data.set_motor_velocity(JointAxis::AngX, 0.0, motor_force_factor);
+                        let stiffness = self.joint_profile.at(i, joint_count).stiffness;
+                        joint.data.set_motor(JointAxis::AngX, 0.0, 0.0, stiffness, motor_force_factor);
                     }
                 }
             }
             CreatureState::SeekingFood => {
                 let hunger_factor = 1.0 - (self.attributes.energy / self.attributes.max_energy);
-                let amplitude = 1.5 * (1.0 + hunger_factor);
-                let frequency = 1.5 * (1.0 + hunger_factor * 0.3);
+                let aggression = self.behavior.aggression;
+                let amplitude = 1.5 * (1.0 + hunger_factor) * aggression;
+                let frequency = 1.5 * (1.0 + hunger_factor * 0.3) * aggression;
                 self.apply_wiggle(dt, impulse_joint_set, rigid_body_set, amplitude, frequency, 1.5);
             }
             CreatureState::Fleeing => {
@@ -702,9 +884,8 @@ impl Creature for Snake {
 
     /// Override the default apply_custom_forces for Snake.
     fn apply_custom_forces(&self, rigid_body_set: &mut RigidBodySet, _world_context: &WorldContext) {
-        // Moderate drag coefficients for stability
-        let perp_drag = 15.0;  // Moderate drag for sideways motion
-        let forward_drag = 5.0; // Moderate drag for forward/backward motion
+        let perp_drag = self.perp_drag * FORCE_SCALE;
+        let forward_drag = self.forward_drag * FORCE_SCALE;
 
         for handle in self.get_rigid_body_handles() { 
             Snake::apply_anisotropic_drag(*handle, rigid_body_set, perp_drag, forward_drag);
@@ -719,7 +900,7 @@ impl Creature for Snake {
         world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
         zoom: f32,
         is_hovered: bool,
-        pixels_per_meter: f32, // Added parameter
+        world_context: &WorldContext,
     ) {
         let base_color = match self.current_state() {
             CreatureState::Idle => egui::Color32::from_rgb(100, 100, 200), // Bluish
@@ -729,7 +910,11 @@ impl Creature for Snake {
             CreatureState::Fleeing => egui::Color32::from_rgb(255, 0, 255),   // Magenta
         };
 
-        let screen_radius = self.drawing_radius() * pixels_per_meter * zoom; // Use passed parameter
+        // Young snakes are smaller and paler, old ones more strongly banded
+        let look = Look::of(&self.attributes);
+        let body_color = look.body(base_color);
+        let screen_radius = self.drawing_radius() * look.scale * world_context.pixels_per_meter * zoom;
+        let light = world_context.light_field();
 
         // Get body handles
         let handles = self.get_rigid_body_handles();
@@ -747,7 +932,7 @@ impl Creature for Snake {
                             egui::Color32::WHITE,
                         );
                     }
-                    painter.circle_filled(screen_pos, screen_radius, base_color);
+                    painter.circle_filled(screen_pos, screen_radius, light.tint(body_color, pos.y));
                 }
             }
             return; // Exit early
@@ -768,7 +953,7 @@ impl Creature for Snake {
 
         let mut side1_points: Vec<Vector2<f32>> = Vec::with_capacity(handles.len());
         let mut side2_points: Vec<Vector2<f32>> = Vec::with_capacity(handles.len());
-        let radius = self.drawing_radius();
+        let radius = self.drawing_radius() * look.scale;
 
         // Calculate offset points
         for i in 0..world_positions.len() {
@@ -798,7 +983,7 @@ impl Creature for Snake {
 
             let quad_screen: Vec<egui::Pos2> = quad_world
                 .into_iter()
-                .map(|wp| world_to_screen(wp))
+                .map(world_to_screen)
                 .collect();
 
             if quad_screen.len() == 4 { // Ensure we have 4 points
@@ -810,15 +995,32 @@ impl Creature for Snake {
                         egui::Stroke::new(screen_radius * 0.4, egui::Color32::WHITE),
                     ));
                 }
-                // Draw the main skin segment
+                // Draw the main skin segment, tinted by the depth of its middle. Every other
+                // one is a band, darkening with age.
+                let depth_y = (world_positions[i].y + world_positions[i + 1].y) / 2.0;
+                let color = if i % 2 == 1 { look.marking(base_color) } else { body_color };
                 painter.add(egui::Shape::convex_polygon(
                     quad_screen,
-                    base_color,
+                    light.tint(color, depth_y),
                     egui::Stroke::NONE,
                 ));
             }
         }
 
+        for (segment, &position) in world_positions.iter().enumerate() {
+            appearance::draw_wound(painter, world_to_screen(position), screen_radius, self.attributes.wound_at(segment));
+        }
+
+        // Antennae, facing the way the head leads
+        let pixels_per_world_unit = world_context.pixels_per_meter * zoom;
+        for antenna in &self.antennae {
+            let Some(parent) = rigid_body_set.get(handles[antenna.parent_segment]) else { continue };
+            let heading = world_positions[0] - world_positions[1];
+            let points = antenna.points(world_positions[antenna.parent_segment], heading, *parent.linvel());
+            let color = light.tint(body_color, world_positions[antenna.parent_segment].y);
+            antenna.draw(painter, &points, world_to_screen, pixels_per_world_unit, color);
+        }
+
         // Add debug drawing when hovered
         if is_hovered {
             self.draw_debug_info(painter, rigid_body_set, world_to_screen, zoom);
@@ -827,13 +1029,14 @@ impl Creature for Snake {
 }
 
 // Add a physics hooks implementation to handle collisions
+#[allow(dead_code)]
 struct SnakePhysicsHooks;
 
 impl PhysicsHooks for SnakePhysicsHooks {
     fn filter_contact_pair(&self, context: &PairFilterContext) -> Option<SolverFlags> {
-        // Get the user data (creature IDs) of both colliders
-        let id1 = context.colliders[context.collider1].user_data;
-        let id2 = context.colliders[context.collider2].user_data;
+        // Get the creature IDs of both colliders
+        let id1 = ColliderTag::unpack(context.colliders[context.collider1].user_data).and_then(|tag| tag.creature_id());
+        let id2 = ColliderTag::unpack(context.colliders[context.collider2].user_data).and_then(|tag| tag.creature_id());
 
         // If both colliders are from the same snake, disable contact computation
         if id1 == id2 {
@@ -845,9 +1048,9 @@ impl PhysicsHooks for SnakePhysicsHooks {
     }
 
     fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
-        // Get the user data (creature IDs) of both colliders
-        let id1 = context.colliders[context.collider1].user_data;
-        let id2 = context.colliders[context.collider2].user_data;
+        // Get the creature IDs of both colliders
+        let id1 = ColliderTag::unpack(context.colliders[context.collider1].user_data).and_then(|tag| tag.creature_id());
+        let id2 = ColliderTag::unpack(context.colliders[context.collider2].user_data).and_then(|tag| tag.creature_id());
 
         // If this is a collision between different snakes
         if id1 != id2 {
@@ -863,80 +1066,9 @@ impl PhysicsHooks for SnakePhysicsHooks {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flow::FlowField;
+    use crate::mock_world::ScriptedSenses;
     use nalgebra::Vector2;
-    use std::f32;
-    use rapier2d::prelude::*;
-    use std::collections::HashMap;
-
-    fn setup_test_snake(id: u128, initial_position: Vector2<f32>) -> (
-        Snake,
-        HashMap<RigidBodyHandle, RigidBody>,
-        HashMap<RigidBodyHandle, Collider>,
-        Vec<Option<RevoluteJoint>>
-    ) {
-        let mut rigid_body_set = RigidBodySet::new();
-        let mut collider_set = ColliderSet::new();
-        let mut impulse_joint_set = ImpulseJointSet::new();
-        let mut snake = Snake::new(0.1, 5, 0.2);
-        
-        // Store components for later use
-        let mut bodies = HashMap::new();
-        let mut colliders = HashMap::new();
-        let mut joints = Vec::new();
-
-        // Create segments
-        let mut parent_handle: Option<RigidBodyHandle> = None;
-        let mut rng = rand::thread_rng();
-        let initial_angle: f32 = rng.gen_range(-0.05..0.05);
-
-        for i in 0..snake.segment_count {
-            let segment_x = initial_position.x + (i as f32) * snake.segment_spacing * initial_angle.cos();
-            let segment_y = initial_position.y + (i as f32) * snake.segment_spacing * initial_angle.sin();
-            let orientation = initial_angle;
-
-            // Create RigidBody
-            let rb = RigidBodyBuilder::dynamic()
-                .translation(vector![segment_x, segment_y])
-                .rotation(orientation)
-                .linear_damping(20.0)
-                .angular_damping(10.0)
-                .build();
-            let segment_handle = rigid_body_set.insert(rb);
-            bodies.insert(segment_handle, rigid_body_set.get(segment_handle).unwrap().clone());
-            snake.segment_handles.push(segment_handle);
-
-            // Create Collider
-            let collider = ColliderBuilder::ball(snake.segment_radius)
-                .restitution(0.0)
-                .density(5.0)
-                .friction(0.1)
-                .user_data(id)
-                .build();
-            let collider_handle = collider_set.insert_with_parent(collider.clone(), segment_handle, &mut rigid_body_set);
-            colliders.insert(segment_handle, collider);
-
-            // Create joint
-            if let Some(prev_handle) = parent_handle {
-                let joint = RevoluteJointBuilder::new()
-                    .local_anchor1(Point2::new(snake.segment_spacing / 2.0, 0.0))
-                    .local_anchor2(Point2::new(-snake.segment_spacing / 2.0, 0.0))
-                    .motor_velocity(0.0, 0.0)
-                    .motor_max_force(0.5)
-                    .motor_model(MotorModel::ForceBased)
-                    .limits([-0.05, 0.05])
-                    .build();
-                let joint_handle = impulse_joint_set.insert(prev_handle, segment_handle, joint.clone(), true);
-                snake.joint_handles.push(joint_handle);
-                joints.push(Some(joint));
-            } else {
-                joints.push(None);
-            }
-
-            parent_handle = Some(segment_handle);
-        }
-
-        (snake, bodies, colliders, joints)
-    }
 
     #[test]
     fn test_snake_movement_stability() {
@@ -954,40 +1086,22 @@ mod tests {
         let mut query_pipeline = QueryPipeline::new();
 
         // Create a single snake in the center
-        let (mut snake, bodies, colliders, joints) = setup_test_snake(1, Vector2::new(0.0, 0.0));
-        
-        // Add snake bodies to the physics world
-        for (old_handle, body) in bodies {
-            let new_handle = rigid_body_set.insert(body);
-            // Update the handle in the snake to point to the new body
-            if let Some(pos) = snake.segment_handles.iter().position(|&h| h == old_handle) {
-                snake.segment_handles[pos] = new_handle;
-            }
-        }
-
-        // Add colliders to the physics world
-        for (body_handle, collider) in colliders {
-            if let Some(new_body_handle) = snake.segment_handles.iter().find(|&&h| h == body_handle) {
-                collider_set.insert_with_parent(collider, *new_body_handle, &mut rigid_body_set);
-            }
-        }
+        let mut snake = Snake::new(0.1, 5, 0.2);
+        snake.spawn_rapier(
+            &mut rigid_body_set,
+            &mut collider_set,
+            &mut impulse_joint_set,
+            Vector2::new(0.0, 0.0),
+            CreatureId(1),
+        );
 
-        // Add joints to the physics world
-        for (i, joint) in joints.iter().enumerate() {
-            if let Some(joint) = joint {
-                if i + 1 < snake.segment_handles.len() {
-                    let parent_handle = snake.segment_handles[i];
-                    let child_handle = snake.segment_handles[i + 1];
-                    let new_joint = impulse_joint_set.insert(parent_handle, child_handle, joint.clone(), true);
-                    snake.joint_handles[i] = new_joint;
-                }
-            }
-        }
-        
         // Create world context
         let world_context = WorldContext {
+            world_width: 10.0,
             world_height: 10.0,
             pixels_per_meter: 100.0,
+            water_temperature_offset: 0.0,
+            flow: FlowField::default(),
         };
 
         // Track positions and velocities
@@ -1016,15 +1130,19 @@ mod tests {
             positions.push(frame_positions);
             velocities.push(frame_velocities);
 
+            // Forces added through add_force persist until reset, so clear them each step like the app does
+            for (_, body) in rigid_body_set.iter_mut() {
+                body.reset_forces(false);
+                body.reset_torques(false);
+            }
+
             // Update snake
             snake.update_state_and_behavior(
                 0.016, // 60 FPS
-                1,
+                CreatureId(1),
                 &mut rigid_body_set,
                 &mut impulse_joint_set,
-                &collider_set,
-                &query_pipeline,
-                &Vec::new(),
+                &ScriptedSenses::default(),
                 &world_context,
             );
 
@@ -1099,7 +1217,7 @@ mod tests {
 
             // Check if snake is still within bounds
             for (i, pos) in positions[frame].iter().enumerate() {
-                if pos.x.abs() >= world_context.world_height/2.0 || 
+                if pos.x.abs() >= world_context.world_width/2.0 || 
                    pos.y.abs() >= world_context.world_height/2.0 {
                     println!("\nOUT OF BOUNDS at frame {}: Segment {}", frame, i);
                     println!("  Position: {:?}", pos);
@@ -0,0 +1,145 @@
+//! Chain-of-custody checks for physics handles, for tests that remove things.
+//!
+//! Rapier recycles the slots of removed bodies and colliders, bumping a generation so old
+//! handles stop resolving. That only protects code that keeps the whole handle: anything
+//! that matches on a slot, keeps a stale registry entry or leaves a tag behind ends up
+//! pointing at whichever creature got the slot next. `Custody` remembers who every handle
+//! was issued to, so a test can remove and respawn things and then check nothing crossed over.
+
+use std::collections::{HashMap, HashSet};
+
+use rapier2d::prelude::*;
+
+use crate::collider_tag::{ColliderTag, EntityKind};
+use crate::creature::CreatureId;
+use crate::simulation::Simulation;
+
+/// Who a handle was issued to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Holder {
+    Creature(CreatureId),
+    Egg(u64),
+}
+
+/// A ledger of every body and collider handle seen on a creature or egg, and of which of
+/// those holders have since gone.
+#[derive(Debug, Default)]
+pub struct Custody {
+    bodies: HashMap<RigidBodyHandle, Holder>,
+    colliders: HashMap<ColliderHandle, Holder>,
+    live: HashSet<Holder>,
+    released: HashSet<Holder>,
+}
+
+impl Custody {
+    /// Starts a ledger with everything in `sim` now.
+    pub fn new(sim: &Simulation) -> Self {
+        let mut custody = Self::default();
+        custody.track(sim);
+        custody
+    }
+
+    /// Records the handles of everything in `sim` now, and marks holders that have gone since
+    /// the last call as released. Panics if a handle already on file turns up on a different
+    /// holder: the exact same handle issued twice means a slot was reused without a new generation.
+    pub fn track(&mut self, sim: &Simulation) {
+        let mut live = HashSet::new();
+        for (holder, bodies) in holdings(sim) {
+            live.insert(holder);
+            for body in bodies {
+                record(&mut self.bodies, body, holder);
+                if let Some(rigid_body) = sim.rigid_body_set.get(body) {
+                    for &collider in rigid_body.colliders() {
+                        record(&mut self.colliders, collider, holder);
+                    }
+                }
+            }
+        }
+        self.released.extend(self.live.difference(&live));
+        self.released.retain(|holder| !live.contains(holder));
+        self.live = live;
+    }
+
+    /// How many live bodies sit in a slot that used to belong to a released holder. Tests
+    /// about reuse should check this is nonzero, or they aren't testing anything.
+    pub fn reused_slots(&self, sim: &Simulation) -> usize {
+        let released_slots: HashSet<u32> = self
+            .bodies
+            .iter()
+            .filter(|(_, holder)| self.released.contains(holder))
+            .map(|(handle, _)| handle.into_raw_parts().0)
+            .collect();
+        sim.rigid_body_set.iter().filter(|(handle, _)| released_slots.contains(&handle.into_raw_parts().0)).count()
+    }
+
+    /// Checks that nothing in `sim` still refers to a released holder's handles, and that
+    /// every handle a live creature or egg uses was issued to it. Call `track` first so
+    /// newly spawned things are on file. Panics listing every problem found.
+    pub fn assert_custody(&self, sim: &Simulation) {
+        let mut problems = Vec::new();
+
+        for (&handle, holder) in &self.bodies {
+            if self.released.contains(holder) && sim.rigid_body_set.get(handle).is_some() {
+                problems.push(format!("body {:?} of released {:?} still resolves", handle, holder));
+            }
+        }
+        for (&handle, holder) in &self.colliders {
+            if self.released.contains(holder) && sim.collider_set.get(handle).is_some() {
+                problems.push(format!("collider {:?} of released {:?} still resolves", handle, holder));
+            }
+        }
+
+        for (holder, bodies) in holdings(sim) {
+            for body in bodies {
+                match self.bodies.get(&body) {
+                    Some(&issued_to) if issued_to != holder => {
+                        problems.push(format!("{:?} uses body {:?}, issued to {:?}", holder, body, issued_to));
+                    }
+                    None => problems.push(format!("{:?} uses body {:?}, which isn't on file: call track first", holder, body)),
+                    _ => {}
+                }
+            }
+        }
+
+        for (collider, part) in sim.body_parts.iter() {
+            let holder = Holder::Creature(part.creature);
+            if sim.collider_set.get(collider).is_none() {
+                problems.push(format!("body part registry keeps collider {:?} of {:?}, which is gone", collider, holder));
+            } else if self.colliders.get(&collider).is_some_and(|&issued_to| issued_to != holder) {
+                problems.push(format!("body part registry says collider {:?} is {:?}'s", collider, holder));
+            }
+        }
+
+        for (handle, collider) in sim.collider_set.iter() {
+            let Some(tag) = ColliderTag::unpack(collider.user_data) else { continue };
+            let tagged = match tag.kind {
+                EntityKind::Creature => Holder::Creature(CreatureId(tag.id)),
+                // Eggs are the only food so far
+                EntityKind::Food => Holder::Egg(tag.id),
+                _ => continue,
+            };
+            if self.released.contains(&tagged) {
+                problems.push(format!("collider {:?} is still tagged as released {:?}", handle, tagged));
+            }
+        }
+
+        assert!(problems.is_empty(), "handle custody broken:\n{}", problems.join("\n"));
+    }
+}
+
+/// Every creature and egg in `sim` with the bodies it holds.
+fn holdings(sim: &Simulation) -> Vec<(Holder, Vec<RigidBodyHandle>)> {
+    let creatures = sim
+        .creatures
+        .iter()
+        .map(|creature| (Holder::Creature(creature.id()), creature.get_rigid_body_handles().to_vec()));
+    let eggs = sim.eggs.iter().map(|egg| (Holder::Egg(egg.id), vec![egg.body]));
+    creatures.chain(eggs).collect()
+}
+
+fn record<H: std::hash::Hash + Eq + std::fmt::Debug>(ledger: &mut HashMap<H, Holder>, handle: H, holder: Holder) {
+    if let Some(&issued_to) = ledger.get(&handle) {
+        assert_eq!(issued_to, holder, "{:?} was issued to {:?} and then to {:?}", handle, issued_to, holder);
+    }
+    ledger.insert(handle, holder);
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::creature::Creature;
+use crate::creature_attributes::DietType;
+use crate::simulation::TIMESTEP;
+
+/// Ticks between reviews. Every 30 simulated seconds: slow enough that each nudge has time to show.
+pub const REVIEW_INTERVAL_TICKS: u64 = 60 * 30;
+/// Interventions kept in the log.
+const MAX_LOG: usize = 100;
+
+/// What the director aims for and how far it may push.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectorConfig {
+    /// Below this many prey (plankton), raise their productivity.
+    pub min_prey: usize,
+    /// Above this many prey, lower it.
+    pub max_prey: usize,
+    /// Bounds on `Simulation::food_abundance`.
+    pub productivity_bounds: (f32, f32),
+    /// Bounds on how fast predators (carnivores) burn satiety, as a multiple of their own rate.
+    pub metabolism_bounds: (f32, f32),
+    /// How much one intervention moves a lever.
+    pub step: f32,
+    /// Predators whose average satiety drops below this fraction are starving.
+    pub starving_satiety: f32,
+    /// Above this fraction they're comfortable, and their metabolism may drift back up.
+    pub fed_satiety: f32,
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            min_prey: 8,
+            max_prey: 35,
+            productivity_bounds: (0.5, 2.0),
+            metabolism_bounds: (0.5, 1.5),
+            step: 0.1,
+            starving_satiety: 0.2,
+            fed_satiety: 0.6,
+        }
+    }
+}
+
+/// One change the director made, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intervention {
+    pub tick: u64,
+    pub message: String,
+}
+
+/// The levers the director may pull, borrowed from the simulation for a review.
+pub struct Levers<'a> {
+    pub food_abundance: &'a mut f32,
+    pub predator_metabolism: &'a mut f32,
+}
+
+/// Watches the populations during long unattended runs and gently nudges plankton
+/// productivity and predator metabolism, within bounds, to head off total extinction or a
+/// runaway bloom. Off unless turned on. Logs every change it makes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Director {
+    pub config: DirectorConfig,
+    log: Vec<Intervention>,
+}
+
+impl Director {
+    pub fn new(config: DirectorConfig) -> Self {
+        Self { config, log: Vec::new() }
+    }
+
+    pub fn log(&self) -> &[Intervention] {
+        &self.log
+    }
+
+    /// Reviews the tank if `tick` falls on the review interval, adjusting the levers at most one step each.
+    pub fn review(&mut self, tick: u64, creatures: &[Box<dyn Creature>], levers: Levers) {
+        if tick == 0 || !tick.is_multiple_of(REVIEW_INTERVAL_TICKS) {
+            return;
+        }
+        let config = &self.config;
+        let prey = creatures.iter().filter(|creature| creature.type_name() == "Plankton").count();
+        let predators: Vec<&dyn Creature> = creatures
+            .iter()
+            .map(|creature| creature.as_ref())
+            .filter(|creature| creature.attributes().diet_type == DietType::Carnivore)
+            .collect();
+        let mut changes = Vec::new();
+
+        let (low, high) = config.productivity_bounds;
+        let productivity = *levers.food_abundance;
+        if prey < config.min_prey && productivity < high {
+            *levers.food_abundance = (productivity + config.step).min(high);
+            changes.push(format!("only {} plankton: productivity {:.2} -> {:.2}", prey, productivity, levers.food_abundance));
+        } else if prey > config.max_prey && productivity > low {
+            *levers.food_abundance = (productivity - config.step).max(low);
+            changes.push(format!("{} plankton: productivity {:.2} -> {:.2}", prey, productivity, levers.food_abundance));
+        }
+
+        if !predators.is_empty() {
+            let satiety = predators.iter().map(|p| p.attributes().satiety / p.attributes().max_satiety).sum::<f32>() / predators.len() as f32;
+            let (low, high) = config.metabolism_bounds;
+            let metabolism = *levers.predator_metabolism;
+            if satiety < config.starving_satiety && metabolism > low {
+                *levers.predator_metabolism = (metabolism - config.step).max(low);
+                changes.push(format!("predators starving ({:.0}% fed): metabolism {:.2} -> {:.2}", satiety * 100.0, metabolism, levers.predator_metabolism));
+            } else if satiety > config.fed_satiety && metabolism < 1.0 {
+                // Ease back toward normal once they've recovered, never past it
+                *levers.predator_metabolism = (metabolism + config.step).min(1.0_f32.min(high));
+                changes.push(format!("predators fed ({:.0}%): metabolism {:.2} -> {:.2}", satiety * 100.0, metabolism, levers.predator_metabolism));
+            }
+        }
+
+        for message in changes {
+            let seconds = (tick as f32 * TIMESTEP) as u64;
+            tracing::info!("Director at {}s: {}", seconds, message);
+            if self.log.len() == MAX_LOG {
+                self.log.remove(0);
+            }
+            self.log.push(Intervention { tick, message });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creatures::plankton::Plankton;
+    use crate::creatures::snake::Snake;
+
+    fn tank(plankton: usize, snake_satiety: f32) -> Vec<Box<dyn Creature>> {
+        let mut creatures: Vec<Box<dyn Creature>> = (0..plankton).map(|_| Box::new(Plankton::new(0.08)) as Box<dyn Creature>).collect();
+        let mut snake = Snake::new(0.1, 3, 0.3);
+        snake.attributes_mut().satiety = snake.attributes().max_satiety * snake_satiety;
+        creatures.push(Box::new(snake));
+        creatures
+    }
+
+    #[test]
+    fn nudges_stay_within_bounds_and_are_logged() {
+        let mut director = Director::default();
+        let (mut food, mut metabolism) = (1.0, 1.0);
+        let starving = tank(2, 0.05);
+
+        // Between reviews nothing happens
+        director.review(REVIEW_INTERVAL_TICKS - 1, &starving, Levers { food_abundance: &mut food, predator_metabolism: &mut metabolism });
+        assert_eq!((food, metabolism), (1.0, 1.0));
+
+        for review in 1..=30 {
+            let levers = Levers { food_abundance: &mut food, predator_metabolism: &mut metabolism };
+            director.review(review * REVIEW_INTERVAL_TICKS, &starving, levers);
+        }
+        assert_eq!((food, metabolism), (2.0, 0.5));
+        // One entry per step actually taken: 10 up to the productivity cap, 5 down to the metabolism floor
+        assert_eq!(director.log().len(), 15);
+        assert!(director.log()[0].message.starts_with("only 2 plankton"));
+
+        // A bloom with well-fed predators eases both back
+        director.review(31 * REVIEW_INTERVAL_TICKS, &tank(50, 0.9), Levers { food_abundance: &mut food, predator_metabolism: &mut metabolism });
+        assert!((food - 1.9).abs() < 1e-5 && (metabolism - 0.6).abs() < 1e-5);
+    }
+}
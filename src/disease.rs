@@ -0,0 +1,193 @@
+use nalgebra::Vector2;
+use rand::Rng;
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::body_parts::BodyPartRegistry;
+use crate::creature::{Creature, CreatureId};
+use crate::random;
+
+/// Default chance-per-second scale for passing the infection on. 0 stops it spreading.
+pub const DEFAULT_VIRULENCE: f32 = 0.3;
+/// Seconds a creature stays sick before recovering.
+const INFECTION_SECONDS: f32 = 30.0;
+/// Seconds a recovered creature can't catch it again.
+const IMMUNITY_SECONDS: f32 = 60.0;
+/// Transmissions per second at virulence 1.0, when touching.
+const CONTACT_RATE: f32 = 4.0;
+/// Transmissions per second at virulence 1.0, when merely close.
+const PROXIMITY_RATE: f32 = 0.5;
+/// How close (in meters, between primary segments) counts as close.
+const PROXIMITY_RANGE: f32 = 0.6;
+/// Extra energy an infected creature burns per second, as a fraction of its max energy.
+const ENERGY_DRAIN_FRACTION: f32 = 0.01;
+/// Infected creatures recover energy this much slower while resting.
+const RECOVERY_PENALTY: f32 = 0.5;
+
+/// Where a creature is in the course of the disease.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Infection {
+    #[default]
+    Healthy,
+    /// Sick and contagious. Counts down to recovery.
+    Infected { seconds_left: f32 },
+    /// Recovered. Counts down until it can catch it again.
+    Immune { seconds_left: f32 },
+}
+
+impl Infection {
+    pub fn is_infected(&self) -> bool {
+        matches!(self, Infection::Infected { .. })
+    }
+
+    /// Infects a healthy creature. Does nothing to ones already sick or immune.
+    pub fn catch(&mut self) {
+        if *self == Infection::Healthy {
+            *self = Infection::Infected { seconds_left: INFECTION_SECONDS };
+        }
+    }
+
+    /// Advances the course of the disease by `dt` seconds.
+    fn progress(&mut self, dt: f32) {
+        *self = match *self {
+            Infection::Healthy => Infection::Healthy,
+            Infection::Infected { seconds_left } if seconds_left > dt => Infection::Infected { seconds_left: seconds_left - dt },
+            Infection::Infected { .. } => Infection::Immune { seconds_left: IMMUNITY_SECONDS },
+            Infection::Immune { seconds_left } if seconds_left > dt => Infection::Immune { seconds_left: seconds_left - dt },
+            Infection::Immune { .. } => Infection::Healthy,
+        };
+    }
+}
+
+/// Runs the disease for one tick: sick creatures weaken and recover, and pass the infection to
+/// creatures they touch or swim close to. Call after the physics step so contacts are current.
+pub fn update(
+    creatures: &mut [Box<dyn Creature>],
+    rigid_body_set: &RigidBodySet,
+    narrow_phase: &NarrowPhase,
+    body_parts: &BodyPartRegistry,
+    virulence: f32,
+    dt: f32,
+) {
+    let primary_position = |creature: &dyn Creature| -> Option<Vector2<f32>> {
+        let body = rigid_body_set.get(*creature.get_rigid_body_handles().first()?)?;
+        Some(*body.translation())
+    };
+
+    // Work out who catches it before anyone's state changes, so it spreads one hop per tick
+    let mut rng = random::rng();
+    let mut exposed: Vec<CreatureId> = Vec::new();
+    if virulence > 0.0 {
+        for sick in creatures.iter().filter(|creature| creature.attributes().infection.is_infected()) {
+            let sick_id = sick.id();
+            let sick_position = primary_position(sick.as_ref());
+            let mut touching: Vec<CreatureId> = Vec::new();
+            for body in sick.get_rigid_body_handles().iter().filter_map(|&handle| rigid_body_set.get(handle)) {
+                for &collider in body.colliders() {
+                    for pair in narrow_phase.contact_pairs_with(collider) {
+                        if !pair.has_any_active_contact {
+                            continue;
+                        }
+                        let other = if pair.collider1 == collider { pair.collider2 } else { pair.collider1 };
+                        if let Some(part) = body_parts.get(other).filter(|part| part.creature != sick_id) {
+                            touching.push(part.creature);
+                        }
+                    }
+                }
+            }
+
+            for creature in creatures.iter() {
+                if creature.id() == sick_id || creature.attributes().infection != Infection::Healthy {
+                    continue;
+                }
+                let rate = if touching.contains(&creature.id()) {
+                    CONTACT_RATE
+                } else {
+                    let close = sick_position
+                        .zip(primary_position(creature.as_ref()))
+                        .is_some_and(|(a, b)| (a - b).norm() <= PROXIMITY_RANGE);
+                    if !close {
+                        continue;
+                    }
+                    PROXIMITY_RATE
+                };
+                let chance = 1.0 - (-rate * virulence * dt).exp();
+                if rng.gen::<f32>() < chance {
+                    exposed.push(creature.id());
+                }
+            }
+        }
+    }
+
+    for creature in creatures.iter_mut() {
+        let is_resting = creature.current_state() == crate::creature::CreatureState::Resting;
+        let is_exposed = exposed.contains(&creature.id());
+        let attributes = creature.attributes_mut();
+        if attributes.infection.is_infected() {
+            let mut drain = attributes.max_energy * ENERGY_DRAIN_FRACTION * dt;
+            if is_resting {
+                // Claw back part of what resting recovered this tick
+                drain += attributes.energy_recovery_rate * RECOVERY_PENALTY * dt;
+            }
+            attributes.consume_energy(drain);
+        }
+        attributes.infection.progress(dt);
+        if is_exposed {
+            attributes.infection.catch();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::Scenario;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn runs_its_course() {
+        let mut infection = Infection::Healthy;
+        infection.catch();
+        assert!(infection.is_infected());
+        infection.progress(INFECTION_SECONDS + 0.1);
+        assert!(matches!(infection, Infection::Immune { .. }));
+        // Immune creatures can't catch it again until immunity wears off
+        infection.catch();
+        assert!(!infection.is_infected());
+        infection.progress(IMMUNITY_SECONDS + 0.1);
+        assert_eq!(infection, Infection::Healthy);
+    }
+
+    #[test]
+    fn spreads_to_neighbors_but_not_without_virulence() {
+        // Virulent enough that the neighbor is all but certain to catch it
+        for (virulence, should_spread) in [(10.0, true), (0.0, false)] {
+            let mut sim = Simulation::new(20.0, 16.0);
+            sim.set_virulence(virulence);
+            let sick = sim.spawn_plankton(Vector2::new(0.0, 0.0));
+            let neighbor = sim.spawn_plankton(Vector2::new(0.3, 0.0));
+            let far = sim.spawn_plankton(Vector2::new(6.0, 0.0));
+            sim.infect(sick);
+
+            for _ in 0..180 {
+                sim.tick(TIMESTEP);
+            }
+            let infection = |id| sim.creatures.iter().find(|c| c.id() == id).unwrap().attributes().infection;
+            assert_eq!(infection(neighbor).is_infected(), should_spread);
+            assert_eq!(infection(far), Infection::Healthy);
+        }
+    }
+
+    #[test]
+    fn only_scenarios_that_ask_for_an_outbreak_start_with_one() {
+        let infected = |sim: &Simulation| sim.creatures.iter().filter(|c| c.attributes().infection.is_infected()).count();
+        assert_eq!(infected(&Simulation::default()), 0);
+
+        let mut scenario = Scenario::standard("Outbreak", Vector2::new(20.0, 16.0));
+        scenario.virulence = Some(0.0);
+        scenario.infected = 3;
+        let sim = scenario.build();
+        assert_eq!(infected(&sim), 3);
+        assert_eq!(sim.virulence(), 0.0);
+    }
+}
@@ -0,0 +1,150 @@
+use eframe::egui;
+use nalgebra::Vector2;
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::collider_tag::{ColliderTag, EntityKind};
+use crate::creature::WorldContext;
+use crate::fields::FieldGrid;
+
+/// Egg radius, in meters.
+pub const EGG_RADIUS: f32 = 0.06;
+/// Seconds to hatch at `REFERENCE_TEMPERATURE`.
+pub const BASE_INCUBATION_SECONDS: f32 = 25.0;
+const REFERENCE_TEMPERATURE: f32 = 20.0;
+/// How much faster incubation runs for every 10°C warmer (the Q10 temperature coefficient).
+const INCUBATION_Q10: f32 = 2.0;
+/// Satiety a creature gains from eating an egg.
+pub const EGG_SATIETY: f32 = 15.0;
+
+/// What hatches out of an egg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Species {
+    Plankton,
+}
+
+impl Species {
+    /// The `Creature::type_name` of what hatches.
+    pub fn type_name(self) -> &'static str {
+        match self {
+            Species::Plankton => "Plankton",
+        }
+    }
+}
+
+/// An egg laid by an oviparous creature. Sinks until it touches a wall, then sticks there
+/// until it hatches or something eats it. Not a creature: it doesn't think or move itself.
+///
+/// Its collider is a sensor, so it never pushes anything. A stuck egg is a fixed body, and a
+/// solid one wedged against a creature would pin it in place hard enough to tear its joints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Egg {
+    pub id: u64,
+    pub species: Species,
+    /// Generation of the creature that will hatch out.
+    pub generation: u32,
+    pub body: RigidBodyHandle,
+    /// Fraction of the way to hatching, from 0.0 to 1.0.
+    pub incubation: f32,
+}
+
+impl Egg {
+    /// Adds the egg's body to the physics world. Its sensor collider is tagged as `Food` with the egg's id.
+    pub fn spawn(
+        id: u64,
+        species: Species,
+        generation: u32,
+        position: Vector2<f32>,
+        rigid_body_set: &mut RigidBodySet,
+        collider_set: &mut ColliderSet,
+    ) -> Self {
+        let body = rigid_body_set.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(position)
+                .linear_damping(2.0) // Sinks slowly, like something small in water
+                .angular_damping(5.0)
+                .build(),
+        );
+        let collider = ColliderBuilder::ball(EGG_RADIUS)
+            .sensor(true)
+            .density(20.0)
+            .user_data(Self::tag(id).pack())
+            .build();
+        collider_set.insert_with_parent(collider, body, rigid_body_set);
+        Self { id, species, generation, body, incubation: 0.0 }
+    }
+
+    pub fn tag(id: u64) -> ColliderTag {
+        ColliderTag { kind: EntityKind::Food, id, segment: 0 }
+    }
+
+    /// Incubates for `dt` seconds in the water around the egg. Warmer water hatches eggs sooner.
+    pub fn incubate(&mut self, dt: f32, rigid_body_set: &RigidBodySet, water_temperature: &FieldGrid<f32>) {
+        let Some(body) = rigid_body_set.get(self.body) else { return };
+        let temperature = water_temperature.sample(*body.translation());
+        self.incubation += dt * incubation_rate(temperature) / BASE_INCUBATION_SECONDS;
+    }
+
+    pub fn is_ready_to_hatch(&self) -> bool {
+        self.incubation >= 1.0
+    }
+
+    /// Glues the egg in place. Called when it first touches a wall.
+    pub fn stick(&self, rigid_body_set: &mut RigidBodySet) {
+        if let Some(body) = rigid_body_set.get_mut(self.body) {
+            body.set_body_type(RigidBodyType::Fixed, true);
+        }
+    }
+
+    pub fn draw(
+        &self,
+        painter: &egui::Painter,
+        rigid_body_set: &RigidBodySet,
+        world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
+        zoom: f32,
+        world_context: &WorldContext,
+    ) {
+        let Some(body) = rigid_body_set.get(self.body) else { return };
+        let position = *body.translation();
+        let light = world_context.light_field();
+        let screen_pos = world_to_screen(position);
+        let screen_radius = EGG_RADIUS * world_context.pixels_per_meter * zoom;
+
+        painter.circle_filled(screen_pos, screen_radius, light.tint(egui::Color32::from_rgb(235, 225, 190), position.y));
+        // The embryo grows visibly as it incubates
+        let embryo_radius = screen_radius * 0.6 * self.incubation.clamp(0.0, 1.0);
+        painter.circle_filled(screen_pos, embryo_radius, light.tint(egui::Color32::from_rgb(120, 180, 120), position.y));
+    }
+}
+
+/// Incubation speed relative to `REFERENCE_TEMPERATURE`.
+fn incubation_rate(temperature: f32) -> f32 {
+    INCUBATION_Q10.powf((temperature - REFERENCE_TEMPERATURE) / 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::light::LightField;
+    use crate::simulation::{WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+    #[test]
+    fn warm_eggs_hatch_sooner() {
+        let light = LightField::for_tank(WORLD_HEIGHT_METERS);
+        let water = FieldGrid::from_fn(Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS), 0.5, |point| light.water_temperature_at(point.y));
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut shallow = Egg::spawn(0, Species::Plankton, 1, Vector2::new(0.0, 7.0), &mut bodies, &mut colliders);
+        let mut deep = Egg::spawn(1, Species::Plankton, 1, Vector2::new(0.0, -7.0), &mut bodies, &mut colliders);
+
+        let mut seconds = 0.0;
+        while !shallow.is_ready_to_hatch() {
+            shallow.incubate(0.5, &bodies, &water);
+            deep.incubate(0.5, &bodies, &water);
+            seconds += 0.5;
+        }
+        assert!(!deep.is_ready_to_hatch());
+        assert!(seconds < BASE_INCUBATION_SECONDS);
+        assert_eq!(incubation_rate(REFERENCE_TEMPERATURE), 1.0);
+    }
+}
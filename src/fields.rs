@@ -0,0 +1,213 @@
+use std::ops::{Add, Mul, Sub};
+
+use eframe::egui;
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// Anything a `FieldGrid` can hold: values that can be blended, like `f32` or a `Vector2`.
+pub trait FieldValue: Copy + Default + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self> {}
+
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>> FieldValue for T {}
+
+/// A quantity spread through the water (light, temperature, oxygen, nutrients, scent) as a
+/// grid of square cells over the tank. Read it smoothly with `sample`; let it spread and
+/// fade with `diffuse` and `decay`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldGrid<T> {
+    /// World position of the grid's lower-left corner.
+    origin: Vector2<f32>,
+    cell_size: f32,
+    width: usize,
+    height: usize,
+    /// Row by row, bottom row first.
+    cells: Vec<T>,
+}
+
+impl<T: FieldValue> FieldGrid<T> {
+    /// Covers a tank of `world_size` centered on the origin with cells about `cell_size`
+    /// meters across, all holding the default value.
+    pub fn for_tank(world_size: Vector2<f32>, cell_size: f32) -> Self {
+        let width = (world_size.x / cell_size).ceil().max(1.0) as usize;
+        let height = (world_size.y / cell_size).ceil().max(1.0) as usize;
+        let origin = -Vector2::new(width as f32, height as f32) * cell_size / 2.0;
+        Self { origin, cell_size, width, height, cells: vec![T::default(); width * height] }
+    }
+
+    /// Like `for_tank`, with each cell set to `value` at its center.
+    pub fn from_fn(world_size: Vector2<f32>, cell_size: f32, value: impl Fn(Vector2<f32>) -> T) -> Self {
+        let mut grid = Self::for_tank(world_size, cell_size);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let center = grid.cell_center(x, y);
+                grid.cells[y * grid.width + x] = value(center);
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cell_center(&self, x: usize, y: usize) -> Vector2<f32> {
+        self.origin + Vector2::new(x as f32 + 0.5, y as f32 + 0.5) * self.cell_size
+    }
+
+    /// The cell containing `point`, if it's inside the grid.
+    pub fn cell_at(&self, point: Vector2<f32>) -> Option<(usize, usize)> {
+        let local = (point - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> T {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.cells[y * self.width + x] = value;
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+
+    /// Adds `amount` to the cell containing `point`, e.g. scent left by a passing creature.
+    /// Nothing happens outside the grid.
+    pub fn deposit(&mut self, point: Vector2<f32>, amount: T) {
+        if let Some((x, y)) = self.cell_at(point) {
+            self.set(x, y, self.get(x, y) + amount);
+        }
+    }
+
+    /// The value at `point`, blended bilinearly between cell centers. Points outside the
+    /// grid get the nearest edge value.
+    pub fn sample(&self, point: Vector2<f32>) -> T {
+        let local = (point - self.origin) / self.cell_size - Vector2::repeat(0.5);
+        let u = local.x.clamp(0.0, (self.width - 1) as f32);
+        let v = local.y.clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (u as usize, v as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (u - x0 as f32, v - y0 as f32);
+        let lerp = |a: T, b: T, t: f32| a + (b - a) * t;
+        let bottom = lerp(self.get(x0, y0), self.get(x1, y0), fx);
+        let top = lerp(self.get(x0, y1), self.get(x1, y1), fx);
+        lerp(bottom, top, fy)
+    }
+
+    /// Spreads the field out for `dt` seconds, at `rate` square meters per second. Nothing
+    /// leaks out through the edges, so the total is kept.
+    pub fn diffuse(&mut self, rate: f32, dt: f32) {
+        // Explicit steps are only stable up to a quarter per step
+        let k = (rate * dt / (self.cell_size * self.cell_size)).min(0.25);
+        let old = self.cells.clone();
+        let at = |x: usize, y: usize| old[y * self.width + x];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let here = at(x, y);
+                // An edge acts as a neighbour holding the same value: no flow across it
+                let left = if x > 0 { at(x - 1, y) } else { here };
+                let right = if x + 1 < self.width { at(x + 1, y) } else { here };
+                let below = if y > 0 { at(x, y - 1) } else { here };
+                let above = if y + 1 < self.height { at(x, y + 1) } else { here };
+                self.cells[y * self.width + x] = here + (left + right + below + above - here * 4.0) * k;
+            }
+        }
+    }
+
+    /// Moves every cell toward `target` at its center by `rate` per second (exponentially)
+    /// over `dt` seconds, e.g. water warming toward what the sun and heater set.
+    pub fn approach(&mut self, target: impl Fn(Vector2<f32>) -> T, rate: f32, dt: f32) {
+        let step = 1.0 - (-rate * dt).exp();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let here = self.get(x, y);
+                self.set(x, y, here + (target(self.cell_center(x, y)) - here) * step);
+            }
+        }
+    }
+
+    /// Fades every cell toward zero by `rate` per second (exponentially) over `dt` seconds.
+    pub fn decay(&mut self, rate: f32, dt: f32) {
+        let keep = (-rate * dt).exp();
+        for cell in &mut self.cells {
+            *cell = *cell * keep;
+        }
+    }
+}
+
+impl FieldGrid<f32> {
+    /// Smallest and largest value in the grid.
+    pub fn range(&self) -> (f32, f32) {
+        self.cells.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), &value| (low.min(value), high.max(value)))
+    }
+
+    /// Debug overlay: shades each cell in `color`, from clear at the lowest value to mostly
+    /// opaque at the highest.
+    pub fn draw_overlay(&self, painter: &egui::Painter, world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2, color: egui::Color32) {
+        let (low, high) = self.range();
+        let span = (high - low).max(f32::EPSILON);
+        let half = Vector2::repeat(self.cell_size / 2.0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let center = self.cell_center(x, y);
+                // World y is up and screen y is down, so the corners swap
+                let rect = egui::Rect::from_two_pos(world_to_screen(center - half), world_to_screen(center + half));
+                let strength = (self.get(x, y) - low) / span;
+                painter.rect_filled(rect, 0.0, color.gamma_multiply(strength * 0.6));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_and_fades_without_leaking() {
+        let mut scent = FieldGrid::<f32>::for_tank(Vector2::new(10.0, 6.0), 1.0);
+        assert_eq!((scent.width(), scent.height()), (10, 6));
+        scent.deposit(Vector2::new(0.2, 0.2), 1.0);
+        scent.deposit(Vector2::new(50.0, 0.0), 1.0);
+        assert_eq!(scent.get(5, 3), 1.0);
+        assert_eq!(scent.cells().iter().sum::<f32>(), 1.0);
+
+        for _ in 0..600 {
+            scent.diffuse(0.5, 1.0 / 60.0);
+        }
+        let total: f32 = scent.cells().iter().sum();
+        assert!((total - 1.0).abs() < 1e-4, "{}", total);
+        assert!(scent.get(5, 3) < 0.5 && scent.get(6, 3) > 0.0 && scent.get(0, 0) > 0.0);
+
+        scent.decay(std::f32::consts::LN_2, 1.0);
+        let halved: f32 = scent.cells().iter().sum();
+        assert!((halved - 0.5).abs() < 1e-4, "{}", halved);
+
+        // Halfway between two cell centers is halfway between their values, and beyond the
+        // edge is the edge
+        let mut ramp = FieldGrid::from_fn(Vector2::new(4.0, 1.0), 1.0, |point| Vector2::new(point.x, 0.0));
+        assert!((ramp.sample(Vector2::new(0.0, 0.0)).x - 0.0).abs() < 1e-6);
+        assert!((ramp.sample(Vector2::new(-0.25, 0.3)).x + 0.25).abs() < 1e-6);
+        assert_eq!(ramp.sample(Vector2::new(-9.0, 0.0)).x, -1.5);
+        ramp.set(0, 0, Vector2::new(7.0, 7.0));
+
+        let decoded: FieldGrid<Vector2<f32>> = bincode::deserialize(&bincode::serialize(&ramp).unwrap()).unwrap();
+        assert_eq!(decoded, ramp);
+    }
+}
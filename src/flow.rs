@@ -0,0 +1,137 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// Strength of the background swirl, in m/s: enough to keep a resting swarm slowly turning over.
+pub const DEFAULT_TURBULENCE: f32 = 0.1;
+
+/// Waves making up the turbulence, as (wave vector in 1/m, angular drift in rad/s, phase). A
+/// few incommensurate ones are enough to look irregular without repeating noticeably.
+const TURBULENCE_WAVES: [(f32, f32, f32, f32); 3] = [(0.45, 0.31, 0.13, 0.0), (-0.27, 0.62, 0.07, 1.7), (0.83, -0.52, 0.19, 4.1)];
+
+/// Something that moves the water.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FlowSource {
+    /// A jet: water near `position` moves at `velocity`, fading out over `radius` meters.
+    Pump { position: Vector2<f32>, velocity: Vector2<f32>, radius: f32 },
+    /// Warm water rising from `position` at `speed` m/s in a plume `radius` meters wide,
+    /// weakening with height.
+    Vent { position: Vector2<f32>, speed: f32, radius: f32 },
+}
+
+impl FlowSource {
+    fn velocity_at(&self, point: Vector2<f32>) -> Vector2<f32> {
+        match *self {
+            FlowSource::Pump { position, velocity, radius } => velocity * (-(point - position).norm_squared() / (radius * radius)).exp(),
+            FlowSource::Vent { position, speed, radius } => {
+                let offset = point - position;
+                let across = (-(offset.x * offset.x) / (radius * radius)).exp();
+                // Reaches up several widths, but barely below the vent
+                let along = if offset.y >= 0.0 { (-offset.y / (radius * 6.0)).exp() } else { (-(offset.y * offset.y) / (radius * radius)).exp() };
+                Vector2::new(0.0, speed * across * along)
+            }
+        }
+    }
+}
+
+/// How the water in the tank is moving: the pumps and vents plus a slowly changing swirl of
+/// turbulence. Drifting things are carried along by it (see `velocity_at`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowField {
+    sources: Vec<FlowSource>,
+    /// Typical speed of the turbulence, in m/s. 0 for still water.
+    pub turbulence: f32,
+    /// Seconds the turbulence has been evolving.
+    time: f32,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        Self { sources: Vec::new(), turbulence: DEFAULT_TURBULENCE, time: 0.0 }
+    }
+}
+
+impl FlowField {
+    pub fn sources(&self) -> &[FlowSource] {
+        &self.sources
+    }
+
+    pub fn add_source(&mut self, source: FlowSource) {
+        self.sources.push(source);
+    }
+
+    pub fn clear_sources(&mut self) {
+        self.sources.clear();
+    }
+
+    /// Lets the turbulence evolve by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt;
+    }
+
+    /// Water velocity at `point`, in m/s.
+    pub fn velocity_at(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let sources: Vector2<f32> = self.sources.iter().map(|source| source.velocity_at(point)).sum();
+        sources + self.turbulence_at(point)
+    }
+
+    /// Curl noise: the curl of a smooth, drifting stream function. Being a curl, it has no
+    /// divergence, so it stirs things around without bunching them up or spreading them out.
+    fn turbulence_at(&self, point: Vector2<f32>) -> Vector2<f32> {
+        if self.turbulence == 0.0 {
+            return Vector2::zeros();
+        }
+        let mut velocity = Vector2::zeros();
+        for (kx, ky, drift, phase) in TURBULENCE_WAVES {
+            let k = Vector2::new(kx, ky);
+            // Stream function sin(k·p + drift·t + phase) / |k|; velocity is (dψ/dy, -dψ/dx)
+            let slope = (k.dot(&point) + drift * self.time + phase).cos() / k.norm();
+            velocity += Vector2::new(ky, -kx) * slope;
+        }
+        velocity * (self.turbulence / TURBULENCE_WAVES.len() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turbulence_swirls_without_sources_or_sinks() {
+        let mut flow = FlowField::default();
+        flow.advance(3.0);
+        // Net flow out of a small square is zero
+        let (h, center) = (0.01, Vector2::new(1.3, -2.2));
+        let divergence = (flow.velocity_at(center + Vector2::x() * h).x - flow.velocity_at(center - Vector2::x() * h).x
+            + flow.velocity_at(center + Vector2::y() * h).y
+            - flow.velocity_at(center - Vector2::y() * h).y)
+            / (2.0 * h);
+        assert!(divergence.abs() < 1e-3, "{}", divergence);
+        assert!(flow.velocity_at(center).norm() <= DEFAULT_TURBULENCE);
+
+        let still = FlowField { turbulence: 0.0, ..FlowField::default() };
+        assert_eq!(still.velocity_at(center), Vector2::zeros());
+
+        let mut pumped = still.clone();
+        pumped.add_source(FlowSource::Pump { position: Vector2::zeros(), velocity: Vector2::new(1.0, 0.0), radius: 2.0 });
+        pumped.add_source(FlowSource::Vent { position: Vector2::new(5.0, -5.0), speed: 0.5, radius: 1.0 });
+        assert!((pumped.velocity_at(Vector2::zeros()) - Vector2::new(1.0, 0.0)).norm() < 1e-6);
+        assert!(pumped.velocity_at(Vector2::new(0.0, 10.0)).norm() < 1e-6);
+        let above_vent = pumped.velocity_at(Vector2::new(5.0, -3.0));
+        assert!(above_vent.y > 0.3 && above_vent.x.abs() < 1e-3, "{:?}", above_vent);
+    }
+
+    #[test]
+    fn plankton_drift_downstream_of_a_pump() {
+        use crate::simulation::{Simulation, TIMESTEP};
+
+        let mut sim = Simulation::new(20.0, 16.0);
+        sim.set_turbulence(0.0);
+        let plankton = sim.spawn_plankton(Vector2::new(-4.0, 0.0));
+        sim.add_flow_source(FlowSource::Pump { position: Vector2::new(-3.0, 0.0), velocity: Vector2::new(1.0, 0.0), radius: 3.0 });
+        for _ in 0..120 {
+            sim.tick(TIMESTEP);
+        }
+        let head = sim.creatures.iter().find(|c| c.id() == plankton).unwrap().get_rigid_body_handles()[0];
+        assert!(sim.rigid_body_set[head].translation().x > -3.0, "{:?}", sim.rigid_body_set[head].translation());
+    }
+}
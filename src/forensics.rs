@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sim_thread::SimCommand;
+use crate::simulation::Simulation;
+
+/// Ticks between keyframes: one a second at 60Hz.
+pub const KEYFRAME_INTERVAL: u64 = 60;
+/// Keyframes kept, so a dump reaches back this many seconds before the anomaly.
+const MAX_KEYFRAMES: usize = 5;
+/// Speed, in m/s, no creature body reaches unless the solver has blown up.
+pub const EXPLOSION_SPEED: f32 = 100.0;
+/// Anomalies remembered for the UI. The oldest go first.
+const MAX_ANOMALIES: usize = 20;
+/// Ticks after a dump before another is written, so one bad creature doesn't fill the disk.
+const DUMP_COOLDOWN: u64 = 600;
+pub const DUMP_EXTENSION: &str = "forensic";
+
+/// What kind of thing went wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnomalyKind {
+    /// A body position or velocity became NaN or infinite.
+    NonFinite,
+    /// A body is moving faster than anything in the tank should.
+    Explosion,
+    /// A creature left the tank and was put back in the middle.
+    Escape,
+    /// `invariants::check` found a problem. Only checked in debug builds.
+    Invariant,
+}
+
+/// Something that shouldn't happen, seen at the end of a tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub tick: u64,
+    pub kind: AnomalyKind,
+    pub detail: String,
+    /// Where the forensic dump for it was written, if one was.
+    pub dump: Option<PathBuf>,
+}
+
+/// A copy of the whole simulation at one tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub tick: u64,
+    pub world: Vec<u8>,
+}
+
+/// The last few seconds of a run: a keyframe every `KEYFRAME_INTERVAL` ticks and every
+/// command applied since the oldest one. Loading a keyframe and applying the commands at
+/// their ticks replays the run up to now, give or take the creatures' own randomness.
+#[derive(Debug, Default)]
+pub struct ReplayBuffer {
+    keyframes: VecDeque<Keyframe>,
+    commands: Vec<(u64, SimCommand)>,
+}
+
+impl ReplayBuffer {
+    pub fn record_keyframe(&mut self, tick: u64, world: Vec<u8>) {
+        if self.keyframes.len() == MAX_KEYFRAMES {
+            self.keyframes.pop_front();
+        }
+        self.keyframes.push_back(Keyframe { tick, world });
+        let oldest = self.keyframes.front().map_or(tick, |keyframe| keyframe.tick);
+        self.commands.retain(|(command_tick, _)| *command_tick >= oldest);
+    }
+
+    /// Records a command applied before tick `tick + 1` ran.
+    pub fn record_command(&mut self, tick: u64, command: SimCommand) {
+        self.commands.push((tick, command));
+    }
+}
+
+/// A world to tick forward and the commands to apply along the way, by the tick count
+/// they were applied at.
+pub type Replay = (Simulation, Vec<(u64, SimCommand)>);
+
+/// Everything needed to look into an anomaly: the world as it was when it was seen, and the
+/// replay buffer leading up to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForensicDump {
+    pub anomalies: Vec<Anomaly>,
+    /// The simulation right after the tick the anomalies were seen.
+    pub world: Vec<u8>,
+    pub keyframes: Vec<Keyframe>,
+    pub commands: Vec<(u64, SimCommand)>,
+}
+
+impl ForensicDump {
+    pub fn encode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// The world at the oldest keyframe and the commands that followed it. Tick it forward
+    /// to watch the anomaly happen again. None if the run was too short for a keyframe.
+    pub fn replay(&self) -> Option<Result<Replay, bincode::Error>> {
+        let keyframe = self.keyframes.first()?;
+        let commands = self.commands.iter().filter(|(tick, _)| *tick >= keyframe.tick).cloned().collect();
+        Some(Simulation::decode(&keyframe.world).map(|sim| (sim, commands)))
+    }
+}
+
+/// Keeps a replay buffer and writes a dump into `dir` when an anomaly is seen. Off unless
+/// turned on with `Simulation::record_forensics`, since keyframes aren't free.
+#[derive(Debug)]
+pub struct Forensics {
+    dir: PathBuf,
+    pub(crate) replay: ReplayBuffer,
+    last_dump: Option<u64>,
+}
+
+impl Forensics {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, replay: ReplayBuffer::default(), last_dump: None }
+    }
+
+    /// A new replay buffer writing to the same place, for a world that has started over.
+    pub(crate) fn restart(self) -> Self {
+        Self::new(self.dir)
+    }
+
+    /// Whether a dump may be written at `tick`, or it's too soon after the last.
+    pub(crate) fn ready(&self, tick: u64) -> bool {
+        self.last_dump.is_none_or(|last| tick >= last + DUMP_COOLDOWN)
+    }
+
+    /// Writes a dump of `world` and the replay buffer to a timestamped file, returning its path.
+    pub(crate) fn write(&mut self, tick: u64, anomalies: &[Anomaly], world: Vec<u8>) -> std::io::Result<PathBuf> {
+        self.last_dump = Some(tick);
+        let dump = ForensicDump {
+            anomalies: anomalies.to_vec(),
+            world,
+            keyframes: self.replay.keyframes.iter().cloned().collect(),
+            commands: self.replay.commands.clone(),
+        };
+        let bytes = dump.encode().map_err(std::io::Error::other)?;
+        let path = self.dir.join(dump_file_name(tick));
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    }
+}
+
+/// `softies-anomaly-<unix seconds>-tick<tick>.forensic`.
+fn dump_file_name(tick: u64) -> String {
+    let seconds = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    format!("softies-anomaly-{}-tick{}.{}", seconds, tick, DUMP_EXTENSION)
+}
+
+/// Reads a dump written by `Forensics`.
+pub fn read_dump(path: &Path) -> Result<ForensicDump, String> {
+    let bytes = std::fs::read(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    ForensicDump::decode(&bytes).map_err(|err| format!("{} isn't a forensic dump: {}", path.display(), err))
+}
+
+/// Creature bodies that are non-finite or moving impossibly fast.
+pub fn detect(sim: &Simulation) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    let tick = sim.tick_count();
+    for creature in &sim.creatures {
+        let bad = creature.get_rigid_body_handles().iter().find_map(|&handle| {
+            let body = sim.rigid_body_set.get(handle)?;
+            if !body.translation().iter().chain(body.linvel().iter()).all(|v| v.is_finite()) {
+                Some((AnomalyKind::NonFinite, "a non-finite position or velocity".to_string()))
+            } else if body.linvel().norm() > EXPLOSION_SPEED {
+                Some((AnomalyKind::Explosion, format!("a body moving at {:.0} m/s", body.linvel().norm())))
+            } else {
+                None
+            }
+        });
+        if let Some((kind, what)) = bad {
+            let detail = format!("{} {} has {}", creature.type_name(), creature.id(), what);
+            anomalies.push(Anomaly { tick, kind, detail, dump: None });
+        }
+    }
+    anomalies
+}
+
+/// Adds `new` to `anomalies`, dropping the oldest past `MAX_ANOMALIES`.
+pub(crate) fn remember(anomalies: &mut Vec<Anomaly>, new: Vec<Anomaly>) {
+    anomalies.extend(new);
+    let excess = anomalies.len().saturating_sub(MAX_ANOMALIES);
+    anomalies.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::TIMESTEP;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn explosion_writes_a_replayable_dump() {
+        let dir = std::env::temp_dir().join(format!("softies-forensics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut sim = Simulation::new(20.0, 16.0);
+        let plankton = sim.spawn_plankton(Vector2::zeros());
+        sim.record_forensics(dir.clone());
+        for _ in 0..KEYFRAME_INTERVAL {
+            sim.tick(TIMESTEP);
+        }
+        sim.apply_command(SimCommand::SetVirulence(0.5));
+        assert!(sim.snapshot().panels.anomalies.is_empty());
+
+        let body = sim.creatures[0].get_rigid_body_handles()[0];
+        sim.rigid_body_set[body].set_linvel(Vector2::new(200.0, 0.0), true);
+        sim.tick(TIMESTEP);
+
+        let anomalies = sim.snapshot().panels.anomalies.clone();
+        let explosion = anomalies.iter().find(|anomaly| anomaly.kind == AnomalyKind::Explosion).unwrap();
+        assert!(explosion.detail.contains(&plankton.to_string()), "{}", explosion.detail);
+        let path = explosion.dump.clone().expect("a dump was written");
+        let dump = read_dump(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(Simulation::decode(&dump.world).unwrap().tick_count(), KEYFRAME_INTERVAL + 1);
+        let (replay, commands) = dump.replay().unwrap().unwrap();
+        assert_eq!(replay.tick_count(), KEYFRAME_INTERVAL);
+        assert!(matches!(commands.as_slice(), [(tick, SimCommand::SetVirulence(_))] if *tick == KEYFRAME_INTERVAL));
+    }
+}
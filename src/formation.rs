@@ -0,0 +1,100 @@
+use nalgebra::Vector2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::random;
+
+/// Turn between neighbours in a sunflower spiral, in radians: spreads points evenly over a disc.
+const GOLDEN_ANGLE: f32 = 2.399_963;
+/// Radius scale of the spiral that packs points `spacing` apart, as densely as a hex grid.
+const SPIRAL_SCALE: f32 = 0.525;
+
+/// How a group of creatures spawned together is laid out, so a school starts as a school
+/// instead of scattered all over the tank.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Formation {
+    /// In a row `spacing` meters apart, turned `angle` radians from horizontal.
+    Line { spacing: f32, angle: f32 },
+    /// Evenly around a ring of `radius` meters.
+    Circle { radius: f32 },
+    /// Packed into a rough disc about `spacing` meters apart, each nudged randomly by up to
+    /// `jitter` times the spacing so it doesn't look like a lattice.
+    School { spacing: f32, jitter: f32 },
+}
+
+impl Formation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Formation::Line { .. } => "Line",
+            Formation::Circle { .. } => "Circle",
+            Formation::School { .. } => "School",
+        }
+    }
+
+    /// Where each of `count` creatures goes, around `center`.
+    pub fn positions(&self, center: Vector2<f32>, count: usize) -> Vec<Vector2<f32>> {
+        match *self {
+            Formation::Line { spacing, angle } => {
+                let step = Vector2::new(angle.cos(), angle.sin()) * spacing;
+                let middle = count.saturating_sub(1) as f32 / 2.0;
+                (0..count).map(|i| center + step * (i as f32 - middle)).collect()
+            }
+            Formation::Circle { .. } if count == 1 => vec![center],
+            Formation::Circle { radius } => (0..count)
+                .map(|i| {
+                    let angle = std::f32::consts::TAU * i as f32 / count as f32;
+                    center + Vector2::new(angle.cos(), angle.sin()) * radius
+                })
+                .collect(),
+            Formation::School { spacing, jitter } => {
+                let mut rng = random::rng();
+                let nudge = jitter * spacing;
+                (0..count)
+                    .map(|i| {
+                        let (radius, angle) = (SPIRAL_SCALE * spacing * (i as f32 + 0.5).sqrt(), i as f32 * GOLDEN_ANGLE);
+                        let offset = if nudge > 0.0 { Vector2::new(rng.gen_range(-nudge..nudge), rng.gen_range(-nudge..nudge)) } else { Vector2::zeros() };
+                        center + Vector2::new(angle.cos(), angle.sin()) * radius + offset
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creatures::CreatureData;
+    use crate::simulation::{standard_plankton, Simulation, TIMESTEP};
+
+    #[test]
+    fn groups_start_in_shape() {
+        let center = Vector2::new(1.0, -2.0);
+        let line = Formation::Line { spacing: 2.0, angle: std::f32::consts::FRAC_PI_2 }.positions(center, 3);
+        assert!((line[0] - Vector2::new(1.0, -4.0)).norm() < 1e-5 && (line[1] - center).norm() < 1e-5, "{:?}", line);
+
+        let ring = Formation::Circle { radius: 3.0 }.positions(center, 6);
+        assert!(ring.iter().all(|position| ((position - center).norm() - 3.0).abs() < 1e-5));
+
+        // A school is a disc about as wide as its packing, with nobody on top of anybody
+        let school = Formation::School { spacing: 0.5, jitter: 0.0 }.positions(center, 40);
+        let closest = school
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| school[i + 1..].iter().map(move |b| (a - b).norm()))
+            .fold(f32::INFINITY, f32::min);
+        assert!(closest > 0.4, "{}", closest);
+        assert!(school.iter().all(|position| (position - center).norm() < 0.5 * 40f32.sqrt()));
+
+        let mut sim = Simulation::new(20.0, 16.0);
+        let spawned = sim.spawn_group(CreatureData::Plankton(standard_plankton()), 30, center, Formation::School { spacing: 0.5, jitter: 0.2 });
+        assert!(spawned.len() >= 25, "{}", spawned.len());
+        // Far enough apart not to blow each other away
+        for _ in 0..30 {
+            sim.tick(TIMESTEP);
+        }
+        assert!(sim.anomalies().is_empty(), "{:?}", sim.anomalies());
+        let off_the_edge = sim.spawn_group(CreatureData::Plankton(standard_plankton()), 3, Vector2::new(40.0, 0.0), Formation::Circle { radius: 1.0 });
+        assert!(off_the_edge.is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::creatures::CreatureData;
+use crate::simulation::{Simulation, TIMESTEP};
+
+/// Sandbox size, in meters: roomy enough that a creature rarely meets a wall during a trial.
+const SANDBOX_SIZE: Vector2<f32> = Vector2::new(40.0, 20.0);
+
+/// How a segmented creature swims: the wave it sends down its body. Each behavior state
+/// scales the wave on top of this; these are the creature's own multipliers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Gait {
+    /// Scales the size of the wave and the push that goes with it.
+    pub amplitude: f32,
+    /// Scales how fast the wave travels.
+    pub frequency: f32,
+    /// Phase difference between neighbouring joints, in radians. Smaller means longer waves.
+    pub wave_length: f32,
+}
+
+impl Default for Gait {
+    fn default() -> Self {
+        Self { amplitude: 1.0, frequency: 1.0, wave_length: 1.0 }
+    }
+}
+
+/// The gaits to try: every combination of these values.
+#[derive(Debug, Clone)]
+pub struct GaitSweep {
+    pub amplitudes: Vec<f32>,
+    pub frequencies: Vec<f32>,
+    pub wave_lengths: Vec<f32>,
+    /// Simulated seconds each gait swims for.
+    pub seconds: f32,
+}
+
+impl Default for GaitSweep {
+    fn default() -> Self {
+        Self {
+            amplitudes: vec![0.6, 1.0, 1.4],
+            frequencies: vec![0.6, 1.0, 1.4],
+            wave_lengths: vec![0.5, 1.0, 1.5],
+            seconds: 6.0,
+        }
+    }
+}
+
+impl GaitSweep {
+    pub fn gaits(&self) -> Vec<Gait> {
+        let mut gaits = Vec::new();
+        for &amplitude in &self.amplitudes {
+            for &frequency in &self.frequencies {
+                for &wave_length in &self.wave_lengths {
+                    gaits.push(Gait { amplitude, frequency, wave_length });
+                }
+            }
+        }
+        gaits
+    }
+}
+
+/// How one gait did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaitTrial {
+    pub gait: Gait,
+    /// Average speed of the head, in m/s.
+    pub speed: f32,
+    pub energy_used: f32,
+    /// Meters swum per unit of energy. What the tuner ranks by.
+    pub efficiency: f32,
+}
+
+/// Swims a copy of `creature` with each gait in `sweep`, alone in a headless sandbox, starting
+/// rested and fed each time. Returns the trials best first. Empty if the creature doesn't
+/// have a gait to tune.
+pub fn tune(creature: &CreatureData, sweep: &GaitSweep) -> Vec<GaitTrial> {
+    let mut trials: Vec<GaitTrial> = sweep.gaits().into_iter().filter_map(|gait| trial(creature, gait, sweep.seconds)).collect();
+    trials.sort_by(|a, b| b.efficiency.total_cmp(&a.efficiency));
+    trials
+}
+
+fn trial(creature: &CreatureData, gait: Gait, seconds: f32) -> Option<GaitTrial> {
+    let mut sim = Simulation::new(SANDBOX_SIZE.x, SANDBOX_SIZE.y);
+    let id = sim.spawn_creature(creature.clone(), Vector2::zeros());
+    let subject = sim.creatures.iter_mut().find(|c| c.id() == id)?;
+    subject.gait()?;
+    subject.set_gait(gait);
+    let attributes = subject.attributes_mut();
+    attributes.energy = attributes.max_energy;
+    attributes.satiety = attributes.max_satiety;
+    let start_energy = attributes.energy;
+    let head = *subject.get_rigid_body_handles().first()?;
+
+    let mut distance = 0.0;
+    let mut last = *sim.rigid_body_set.get(head)?.translation();
+    let ticks = (seconds / TIMESTEP).round() as usize;
+    for _ in 0..ticks {
+        sim.tick(TIMESTEP);
+        let position = *sim.rigid_body_set.get(head)?.translation();
+        distance += (position - last).norm();
+        last = position;
+    }
+
+    let energy_used = start_energy - sim.creatures.iter().find(|c| c.id() == id)?.attributes().energy;
+    Some(GaitTrial {
+        gait,
+        speed: distance / seconds,
+        energy_used,
+        efficiency: distance / energy_used.max(1e-3),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creature::Creature;
+    use crate::creatures::plankton::Plankton;
+    use crate::creatures::snake::Snake;
+
+    #[test]
+    fn ranks_gaits_by_distance_per_energy() {
+        let sweep = GaitSweep { amplitudes: vec![0.5, 1.5], frequencies: vec![1.0], wave_lengths: vec![1.0], seconds: 1.0 };
+        let trials = tune(&Snake::new(0.1, 5, 0.3).to_data(), &sweep);
+
+        assert_eq!(trials.len(), 2);
+        assert!(trials[0].efficiency >= trials[1].efficiency);
+        assert!(trials.iter().all(|trial| trial.speed > 0.0 && trial.energy_used > 0.0));
+        // Nothing to tune on a creature without a gait
+        assert!(tune(&Plankton::new(0.08).to_data(), &sweep).is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+use eframe::egui;
+use nalgebra::Vector2;
+use rapier2d::prelude::RigidBodySet;
+
+use crate::creature::{Creature, WorldContext};
+
+// Color of the glass, and how opaque it is at its inner and outer faces
+const GLASS_TINT: (u8, u8, u8) = (170, 215, 235);
+const INNER_ALPHA: f32 = 0.45;
+const OUTER_ALPHA: f32 = 0.12;
+// Creatures within this many meters of a pane show in it
+const REFLECTION_RANGE: f32 = 1.5;
+// How far up the glass the water climbs at the surface, and how quickly it flattens out, in meters
+const MENISCUS_HEIGHT: f32 = 0.12;
+const MENISCUS_WIDTH: f32 = 0.3;
+// Points along the waterline
+const WATERLINE_STEPS: usize = 120;
+
+/// One glass side of the tank: the water side of it runs along `edge`, and it's `thickness`
+/// meters thick in the `outward` direction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pane {
+    /// Middle of the water-side face.
+    pub edge: Vector2<f32>,
+    /// Unit normal pointing out of the tank.
+    pub outward: Vector2<f32>,
+    /// Half the length of the water-side face.
+    pub half_length: f32,
+    pub thickness: f32,
+}
+
+impl Pane {
+    /// `point` mirrored across the water-side face, as a reflection in the glass would show it.
+    pub fn mirror(&self, point: Vector2<f32>) -> Vector2<f32> {
+        point - self.outward * 2.0 * (point - self.edge).dot(&self.outward)
+    }
+
+    /// How far `point` is inside the tank from this pane. Negative once it's past the face.
+    pub fn distance(&self, point: Vector2<f32>) -> f32 {
+        (self.edge - point).dot(&self.outward)
+    }
+
+    /// Corners of the pane: the water-side face, then the outer face, each in the same order.
+    fn corners(&self) -> [Vector2<f32>; 4] {
+        let along = Vector2::new(-self.outward.y, self.outward.x) * self.half_length;
+        let out = self.outward * self.thickness;
+        [self.edge - along, self.edge + along, self.edge + along + out, self.edge - along + out]
+    }
+}
+
+/// How the tank's walls are drawn: as glass with faint reflections of whatever swims close,
+/// and a meniscus where the water meets it at the surface. Purely a look; the physics walls
+/// are unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glass {
+    world_size: Vector2<f32>,
+    thickness: f32,
+}
+
+impl Glass {
+    /// Glass `thickness` meters thick around a tank of `world_size` centered on the origin.
+    pub fn new(world_size: Vector2<f32>, thickness: f32) -> Self {
+        Self { world_size, thickness }
+    }
+
+    /// The two sides and the bottom. The floor runs under the sides, so the corners are filled.
+    /// The top is open water.
+    pub fn panes(&self) -> [Pane; 3] {
+        let (hw, hh, t) = (self.world_size.x / 2.0, self.world_size.y / 2.0, self.thickness);
+        [
+            Pane { edge: Vector2::new(-hw, 0.0), outward: -Vector2::x(), half_length: hh, thickness: t },
+            Pane { edge: Vector2::new(hw, 0.0), outward: Vector2::x(), half_length: hh, thickness: t },
+            Pane { edge: Vector2::new(0.0, -hh), outward: -Vector2::y(), half_length: hw + t, thickness: t },
+        ]
+    }
+
+    /// Height of the water surface at `x`: flat across the middle, climbing the glass at
+    /// either side.
+    pub fn waterline_at(&self, x: f32) -> f32 {
+        let from_glass = (self.world_size.x / 2.0 - x.abs()).max(0.0);
+        self.world_size.y / 2.0 - MENISCUS_HEIGHT * (1.0 - (-from_glass / MENISCUS_WIDTH).exp())
+    }
+
+    /// Mirrors the creatures near each pane into it. Draw before `draw_panes`, which tints
+    /// over them so they're only faint.
+    pub fn draw_reflections(
+        &self,
+        painter: &egui::Painter,
+        world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2,
+        creatures: &[Box<dyn Creature>],
+        rigid_body_set: &RigidBodySet,
+        zoom: f32,
+        world_context: &WorldContext,
+    ) {
+        for pane in self.panes() {
+            let [a, _, c, _] = pane.corners();
+            let clipped = painter.with_clip_rect(egui::Rect::from_two_pos(world_to_screen(a), world_to_screen(c)).intersect(painter.clip_rect()));
+            let mirrored = |point: Vector2<f32>| world_to_screen(pane.mirror(point));
+            for creature in creatures {
+                let near = creature
+                    .get_rigid_body_handles()
+                    .iter()
+                    .filter_map(|&handle| rigid_body_set.get(handle))
+                    .any(|body| pane.distance(*body.translation()) < REFLECTION_RANGE);
+                if near {
+                    creature.draw(&clipped, rigid_body_set, &mirrored, zoom, false, world_context);
+                }
+            }
+        }
+    }
+
+    /// The glass itself: shaded from its water-side face, where it catches the light, to
+    /// nearly clear at the outside, with a bright line along the face.
+    pub fn draw_panes(&self, painter: &egui::Painter, world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2) {
+        let (r, g, b) = GLASS_TINT;
+        let tint = |alpha: f32| egui::Color32::from_rgba_unmultiplied(r, g, b, (alpha * 255.0) as u8);
+        for pane in self.panes() {
+            let corners = pane.corners();
+            let mut mesh = egui::Mesh::default();
+            for (i, &corner) in corners.iter().enumerate() {
+                mesh.colored_vertex(world_to_screen(corner), tint(if i < 2 { INNER_ALPHA } else { OUTER_ALPHA }));
+            }
+            mesh.add_triangle(0, 1, 2);
+            mesh.add_triangle(0, 2, 3);
+            painter.add(egui::Shape::mesh(mesh));
+            painter.line_segment([world_to_screen(corners[0]), world_to_screen(corners[1])], egui::Stroke::new(1.5, tint(0.8)));
+            painter.line_segment([world_to_screen(corners[2]), world_to_screen(corners[3])], egui::Stroke::new(1.0, tint(0.3)));
+        }
+    }
+
+    /// The water surface, curving up where it meets the glass.
+    pub fn draw_meniscus(&self, painter: &egui::Painter, world_to_screen: &dyn Fn(Vector2<f32>) -> egui::Pos2) {
+        let hw = self.world_size.x / 2.0;
+        let points: Vec<egui::Pos2> = (0..=WATERLINE_STEPS)
+            .map(|i| {
+                let x = -hw + self.world_size.x * i as f32 / WATERLINE_STEPS as f32;
+                world_to_screen(Vector2::new(x, self.waterline_at(x)))
+            })
+            .collect();
+        let below: Vec<egui::Pos2> = points.iter().map(|point| *point + egui::vec2(0.0, 3.0)).collect();
+        painter.add(egui::Shape::line(below, egui::Stroke::new(2.0, egui::Color32::from_rgba_unmultiplied(200, 235, 255, 40))));
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, egui::Color32::from_rgba_unmultiplied(230, 245, 255, 170))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflects_across_the_glass_and_climbs_it_at_the_surface() {
+        let glass = Glass::new(Vector2::new(20.0, 16.0), 0.5);
+        let [left, right, floor] = glass.panes();
+        let inside = Vector2::new(-9.5, 2.0);
+        assert_eq!(left.distance(inside), 0.5);
+        assert_eq!(left.mirror(inside), Vector2::new(-10.5, 2.0));
+        assert_eq!(right.mirror(Vector2::new(9.0, -3.0)), Vector2::new(11.0, -3.0));
+        assert_eq!(floor.mirror(Vector2::new(4.0, -7.0)), Vector2::new(4.0, -9.0));
+        // The floor reaches under both sides
+        assert_eq!(floor.corners()[0], Vector2::new(-10.5, -8.0));
+
+        assert!((glass.waterline_at(0.0) - (8.0 - MENISCUS_HEIGHT)).abs() < 1e-4);
+        assert_eq!(glass.waterline_at(10.0), 8.0);
+        assert!(glass.waterline_at(9.9) > glass.waterline_at(9.0));
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+
+/// Downward acceleration in the tank, in m/s². Gentler than Earth's, as the water holds
+/// most of a creature's weight.
+pub const GRAVITY: f32 = 1.0;
+
+/// What a creature wants its swim bladder to do.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DepthGoal {
+    /// Rise or sink to this height (y, in meters) and stay there.
+    Hold(f32),
+    /// Float neutrally wherever it is, just stopping any rise or fall.
+    Drift,
+    /// Let the bladder empty and settle to the bottom.
+    Sink,
+}
+
+/// A swim bladder: lift the creature tunes slowly, at a cost in energy, to hold a depth
+/// instead of swimming up or down all the time. Call `update` from the creature's behavior
+/// and apply `lift` to each body in its custom forces.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BuoyancyController {
+    /// Lift as a fraction of the creature's weight: 1 floats, less sinks, more rises.
+    pub buoyancy: f32,
+    pub goal: DepthGoal,
+    /// Emptiest and fullest the bladder gets.
+    pub min_buoyancy: f32,
+    pub max_buoyancy: f32,
+    /// Fastest the buoyancy can change, per second.
+    pub rate: f32,
+    /// Energy spent per unit of buoyancy changed, either way.
+    pub energy_per_change: f32,
+    /// How hard it corrects, per meter off the target depth.
+    pub depth_gain: f32,
+    /// How hard it brakes, per m/s of vertical speed.
+    pub speed_gain: f32,
+}
+
+impl Default for BuoyancyController {
+    fn default() -> Self {
+        Self {
+            buoyancy: 1.0,
+            goal: DepthGoal::Drift,
+            min_buoyancy: 0.0,
+            max_buoyancy: 2.0,
+            rate: 0.5,
+            energy_per_change: 0.5,
+            depth_gain: 2.0,
+            speed_gain: 10.0,
+        }
+    }
+}
+
+impl BuoyancyController {
+    /// The buoyancy the creature would like right now, at height `y` moving up at `vertical_speed`.
+    pub fn wanted(&self, y: f32, vertical_speed: f32) -> f32 {
+        let wanted = match self.goal {
+            DepthGoal::Hold(target) => 1.0 + (target - y) * self.depth_gain - vertical_speed * self.speed_gain,
+            DepthGoal::Drift => 1.0 - vertical_speed * self.speed_gain,
+            DepthGoal::Sink => self.min_buoyancy,
+        };
+        wanted.clamp(self.min_buoyancy, self.max_buoyancy)
+    }
+
+    /// Moves the buoyancy toward what's wanted, as far as `rate` allows in `dt` seconds.
+    /// Returns the energy that cost.
+    pub fn update(&mut self, dt: f32, y: f32, vertical_speed: f32) -> f32 {
+        let step = self.rate * dt;
+        let change = (self.wanted(y, vertical_speed) - self.buoyancy).clamp(-step, step);
+        self.buoyancy += change;
+        change.abs() * self.energy_per_change
+    }
+
+    /// Upward force on a body of `mass`, in newtons.
+    pub fn lift(&self, mass: f32) -> f32 {
+        self.buoyancy * mass * GRAVITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_a_depth_by_filling_and_emptying() {
+        // A point mass in water: weight, lift and drag
+        let (mass, drag) = (1.0, 12.0);
+        let mut bladder = BuoyancyController { goal: DepthGoal::Hold(2.0), ..BuoyancyController::default() };
+        let (mut y, mut speed, mut energy) = (0.0, 0.0, 0.0);
+        let dt = 1.0 / 60.0;
+        for _ in 0..(60 * 120) {
+            energy += bladder.update(dt, y, speed);
+            speed += (bladder.lift(mass) - mass * GRAVITY - drag * speed * mass) / mass * dt;
+            y += speed * dt;
+        }
+        assert!((y - 2.0).abs() < 0.1, "{}", y);
+        assert!((bladder.buoyancy - 1.0).abs() < 0.05, "{}", bladder.buoyancy);
+        assert!(energy > 0.0);
+
+        // Once there, holding costs next to nothing
+        let mut held = 0.0;
+        for _ in 0..60 {
+            held += bladder.update(dt, y, speed);
+        }
+        assert!(held < 0.01, "{}", held);
+
+        bladder.goal = DepthGoal::Sink;
+        for _ in 0..(60 * 5) {
+            bladder.update(dt, y, speed);
+        }
+        assert_eq!(bladder.buoyancy, bladder.min_buoyancy);
+    }
+
+    #[test]
+    fn holding_a_depth_costs_less_than_thrusting() {
+        // The same point mass held at the same height two ways. Thrust is priced like the
+        // bladder: pushing with a creature's weight for a second costs what changing its
+        // buoyancy by 1 does.
+        let (mass, drag, target, dt) = (1.0, 12.0, 2.0, 1.0 / 60.0);
+        let ticks = 60 * 60;
+
+        let mut bladder = BuoyancyController { goal: DepthGoal::Hold(target), ..BuoyancyController::default() };
+        let (mut y, mut speed, mut floating) = (0.0_f32, 0.0_f32, 0.0);
+        for _ in 0..ticks {
+            floating += bladder.update(dt, y, speed);
+            speed += (bladder.lift(mass) - mass * GRAVITY - drag * speed * mass) / mass * dt;
+            y += speed * dt;
+        }
+        assert!((y - target).abs() < 0.1, "{}", y);
+
+        // Without a bladder it swims up against its whole weight all the time, as hard as the
+        // same controller says
+        let sinker = BuoyancyController { goal: DepthGoal::Hold(target), ..BuoyancyController::default() };
+        let (mut y, mut speed, mut swimming) = (0.0_f32, 0.0_f32, 0.0);
+        for _ in 0..ticks {
+            let thrust = sinker.wanted(y, speed) * mass * GRAVITY;
+            swimming += thrust / (mass * GRAVITY) * sinker.energy_per_change * dt;
+            speed += (thrust - mass * GRAVITY - drag * speed * mass) / mass * dt;
+            y += speed * dt;
+        }
+        assert!((y - target).abs() < 0.1, "{}", y);
+
+        assert!(floating * 10.0 < swimming, "bladder {} vs thrust {}", floating, swimming);
+    }
+}
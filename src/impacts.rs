@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use nalgebra::Vector2;
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::collider_tag::{ColliderTag, EntityKind};
+
+/// Contact force, in newtons, a creature collider must feel before Rapier reports it. Below
+/// this, bodies are just nudging or resting against each other.
+pub const IMPACT_FORCE_THRESHOLD: f32 = 2.0;
+/// How long a spark stays on screen, in seconds.
+pub const SPARK_SECONDS: f32 = 0.4;
+/// Seconds a pair of colliders has to be apart before touching again counts as a new
+/// impact. Bodies rebounding or jostling off each other are still the same collision.
+pub const IMPACT_COOLDOWN_SECONDS: f32 = 0.5;
+/// Sparks kept at once, at full detail. The oldest go first in a pile-up.
+const MAX_SPARKS: usize = 64;
+/// Streaks flying out of a spark, at full detail.
+const MAX_STREAKS: usize = 6;
+
+/// What hit what.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImpactKind {
+    /// Two different creatures.
+    Creatures,
+    /// A creature and a wall or terrain.
+    Wall,
+}
+
+/// A brief flash where something hit hard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Spark {
+    pub kind: ImpactKind,
+    pub position: Vector2<f32>,
+    /// Contact force at the moment of impact, in newtons.
+    pub force: f32,
+    /// Seconds since the impact.
+    pub age: f32,
+    /// How many streaks fly out of it.
+    pub streaks: usize,
+}
+
+impl Spark {
+    /// From 1.0 at the impact down to 0.0 when it disappears.
+    pub fn fade(&self) -> f32 {
+        (1.0 - self.age / SPARK_SECONDS).clamp(0.0, 1.0)
+    }
+
+    /// How big to draw it, relative to the smallest impact: grows slowly with the force.
+    pub fn size(&self) -> f32 {
+        (self.force / IMPACT_FORCE_THRESHOLD).sqrt().min(4.0)
+    }
+}
+
+/// One contact force event from the physics step.
+struct ContactForce {
+    pair: (ColliderHandle, ColliderHandle),
+    point: Vector2<f32>,
+    force: f32,
+}
+
+/// Catches Rapier's contact force events during a step. Rapier hands them over through a
+/// shared reference, hence the mutex.
+#[derive(Default)]
+pub struct ImpactCollector {
+    events: Mutex<Vec<ContactForce>>,
+}
+
+impl EventHandler for ImpactCollector {
+    fn handle_collision_event(&self, _bodies: &RigidBodySet, _colliders: &ColliderSet, _event: CollisionEvent, _contact_pair: Option<&ContactPair>) {}
+
+    fn handle_contact_force_event(&self, _dt: Real, _bodies: &RigidBodySet, _colliders: &ColliderSet, contact_pair: &ContactPair, total_force_magnitude: Real) {
+        let points: Vec<Vector2<f32>> = contact_pair
+            .manifolds
+            .iter()
+            .flat_map(|manifold| manifold.data.solver_contacts.iter())
+            .map(|contact| contact.point.coords)
+            .collect();
+        if points.is_empty() {
+            return;
+        }
+        let point = points.iter().sum::<Vector2<f32>>() / points.len() as f32;
+        let event = ContactForce { pair: (contact_pair.collider1, contact_pair.collider2), point, force: total_force_magnitude };
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+/// Recent hard impacts, as sparks for the renderer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Impacts {
+    sparks: Vec<Spark>,
+    /// Seconds since each pair of colliders last pressed hard on each other, for the ones
+    /// that did within the cooldown. Only the first step of a contact is an impact; the rest
+    /// is bodies leaning on, or bouncing off, each other.
+    recent: HashMap<(ColliderHandle, ColliderHandle), f32>,
+}
+
+impl Impacts {
+    pub fn sparks(&self) -> &[Spark] {
+        &self.sparks
+    }
+
+    /// Ages the sparks by `dt` and turns the step's new hard contacts into sparks, with
+    /// `particle_scale` of the usual number of sparks and streaks (see `LoadLevel`). Returns
+    /// the creature parts in those contacts, with the force each was hit with, however many
+    /// get drawn.
+    pub fn update(&mut self, dt: f32, collector: &ImpactCollector, collider_set: &ColliderSet, particle_scale: f32) -> Vec<(ColliderTag, f32)> {
+        for spark in &mut self.sparks {
+            spark.age += dt;
+        }
+        self.sparks.retain(|spark| spark.age < SPARK_SECONDS);
+        let max_sparks = (MAX_SPARKS as f32 * particle_scale).round() as usize;
+        let streaks = (MAX_STREAKS as f32 * particle_scale).round() as usize;
+
+        for since in self.recent.values_mut() {
+            *since += dt;
+        }
+        self.recent.retain(|_, since| *since < IMPACT_COOLDOWN_SECONDS);
+
+        let events = std::mem::take(&mut *collector.events.lock().unwrap());
+        let mut hits = Vec::new();
+        for event in events {
+            // Rapier may report a pair either way round
+            let (first, second) = event.pair;
+            let pair = if first.into_raw_parts() <= second.into_raw_parts() { (first, second) } else { (second, first) };
+            if self.recent.insert(pair, 0.0).is_some() {
+                continue;
+            }
+            let Some(kind) = impact_kind(collider_set, event.pair) else { continue };
+            if max_sparks > 0 {
+                if self.sparks.len() >= max_sparks {
+                    self.sparks.drain(..=self.sparks.len() - max_sparks);
+                }
+                self.sparks.push(Spark { kind, position: event.point, force: event.force, age: 0.0, streaks });
+            }
+            for handle in [first, second] {
+                if let Some(tag) = collider_set.get(handle).and_then(|collider| ColliderTag::unpack(collider.user_data)).filter(|tag| tag.kind == EntityKind::Creature) {
+                    hits.push((tag, event.force));
+                }
+            }
+        }
+        hits
+    }
+}
+
+/// Whether a contact between these colliders is worth a spark: creature on creature or
+/// creature on wall. Parts of the same creature bumping each other don't count.
+fn impact_kind(collider_set: &ColliderSet, (first, second): (ColliderHandle, ColliderHandle)) -> Option<ImpactKind> {
+    let tag = |handle| collider_set.get(handle).and_then(|collider| ColliderTag::unpack(collider.user_data));
+    let (first, second) = (tag(first)?, tag(second)?);
+    match (first.kind, second.kind) {
+        (EntityKind::Creature, EntityKind::Creature) if first.id != second.id => Some(ImpactKind::Creatures),
+        (EntityKind::Creature, EntityKind::Wall) | (EntityKind::Wall, EntityKind::Creature) => Some(ImpactKind::Wall),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    fn fling(sim: &mut Simulation, index: usize, velocity: Vector2<f32>) {
+        for &handle in sim.creatures[index].get_rigid_body_handles() {
+            sim.rigid_body_set[handle].set_linvel(velocity, true);
+        }
+    }
+
+    #[test]
+    fn hard_hits_spark_once() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        // Plankton are heavily damped, so start them close
+        sim.spawn_plankton(Vector2::new(-0.3, 0.0));
+        sim.spawn_plankton(Vector2::new(0.3, 0.0));
+        sim.spawn_plankton(Vector2::new(9.5, 5.0));
+        fling(&mut sim, 0, Vector2::new(15.0, 0.0));
+        fling(&mut sim, 1, Vector2::new(-15.0, 0.0));
+        fling(&mut sim, 2, Vector2::new(15.0, 0.0));
+
+        let mut kinds = Vec::new();
+        for _ in 0..30 {
+            sim.tick(TIMESTEP);
+            let sparks = sim.snapshot().impacts;
+            kinds.extend(sparks.sparks().iter().filter(|spark| spark.age == 0.0).map(|spark| spark.kind));
+        }
+        assert!(kinds.contains(&ImpactKind::Creatures), "{:?}", kinds);
+        assert!(kinds.contains(&ImpactKind::Wall), "{:?}", kinds);
+        // Each collision is one impact, not one per step the bodies stay in contact or
+        // bounce off each other
+        assert!(kinds.len() <= 6, "{:?}", kinds);
+
+        // Sparks fade out and go
+        let mut impacts = sim.snapshot().impacts;
+        let quiet = ImpactCollector::default();
+        impacts.update(SPARK_SECONDS / 2.0, &quiet, &sim.collider_set, 1.0);
+        assert!(impacts.sparks().iter().all(|spark| spark.fade() <= 0.5));
+        impacts.update(SPARK_SECONDS / 2.0, &quiet, &sim.collider_set, 1.0);
+        assert!(impacts.sparks().is_empty());
+    }
+}
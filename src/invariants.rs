@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+use rapier2d::prelude::*;
+
+use crate::collider_tag::ColliderTag;
+use crate::creature::CreatureId;
+use crate::egg::Egg;
+use crate::simulation::Simulation;
+
+/// How far apart (in meters) a joint's two anchors may drift before we call it broken.
+/// Joints stretch a little under load, especially with the `Fast` preset; this only
+/// catches bodies that have come apart.
+pub const JOINT_ANCHOR_TOLERANCE: f32 = 0.5;
+
+/// Checks that the world is internally consistent, returning a description of each problem.
+/// Run after every tick in debug builds (see `Simulation::tick`).
+///
+/// * every creature's body and joint handles resolve to live objects
+/// * nothing is orphaned: every dynamic body belongs to a creature or an egg, and every
+///   joint to a creature
+/// * every egg's body exists and its colliders are tagged as that egg
+/// * no two creatures share an id or a body (joints may be shared, e.g. attachments)
+/// * colliders on a creature's bodies are tagged with that creature's id, and registered
+///   as the same segment in the body part registry
+/// * every joint's two anchors are within `JOINT_ANCHOR_TOLERANCE` of each other
+/// * no body position or velocity is non-finite, and no attribute is NaN or negative
+pub fn check(sim: &Simulation) -> Vec<String> {
+    let mut violations = Vec::new();
+    let mut body_owners: HashMap<RigidBodyHandle, CreatureId> = HashMap::new();
+    let mut held_joints: HashSet<ImpulseJointHandle> = HashSet::new();
+    let mut ids: HashSet<CreatureId> = HashSet::new();
+
+    for creature in &sim.creatures {
+        let id = creature.id();
+        let name = format!("{} {}", creature.type_name(), id);
+        if !ids.insert(id) {
+            violations.push(format!("{}: id is used by another creature too", name));
+        }
+
+        for &handle in creature.get_rigid_body_handles() {
+            match sim.rigid_body_set.get(handle) {
+                Some(body) => {
+                    if !body.translation().iter().all(|v| v.is_finite()) || !body.linvel().iter().all(|v| v.is_finite()) {
+                        violations.push(format!("{}: body {:?} has non-finite position or velocity", name, handle));
+                    }
+                }
+                None => violations.push(format!("{}: body {:?} doesn't exist", name, handle)),
+            }
+            if let Some(other) = body_owners.insert(handle, id) {
+                violations.push(format!("{}: body {:?} is also owned by creature {}", name, handle, other));
+            }
+        }
+
+        for &handle in creature.get_joint_handles() {
+            held_joints.insert(handle);
+            let Some(joint) = sim.impulse_joint_set.get(handle) else {
+                violations.push(format!("{}: joint {:?} doesn't exist", name, handle));
+                continue;
+            };
+            let (Some(body1), Some(body2)) = (sim.rigid_body_set.get(joint.body1), sim.rigid_body_set.get(joint.body2)) else {
+                violations.push(format!("{}: joint {:?} connects a missing body", name, handle));
+                continue;
+            };
+            let anchor1 = body1.position() * joint.data.local_anchor1();
+            let anchor2 = body2.position() * joint.data.local_anchor2();
+            let separation = nalgebra::distance(&anchor1, &anchor2);
+            if separation > JOINT_ANCHOR_TOLERANCE {
+                violations.push(format!("{}: joint {:?} anchors are {:.3}m apart", name, handle, separation));
+            }
+        }
+
+        let attributes = creature.attributes();
+        for (field, value) in [
+            ("energy", attributes.energy),
+            ("max_energy", attributes.max_energy),
+            ("energy_recovery_rate", attributes.energy_recovery_rate),
+            ("satiety", attributes.satiety),
+            ("max_satiety", attributes.max_satiety),
+            ("metabolic_rate", attributes.metabolic_rate),
+            ("size", attributes.size),
+            ("parasite_load", attributes.parasite_load),
+        ] {
+            if value.is_nan() || value < 0.0 {
+                violations.push(format!("{}: attribute {} is {}", name, field, value));
+            }
+        }
+    }
+
+    let mut egg_bodies: HashSet<RigidBodyHandle> = HashSet::new();
+    for egg in &sim.eggs {
+        egg_bodies.insert(egg.body);
+        let Some(body) = sim.rigid_body_set.get(egg.body) else {
+            violations.push(format!("egg {}: body {:?} doesn't exist", egg.id, egg.body));
+            continue;
+        };
+        for &collider in body.colliders() {
+            let tag = ColliderTag::unpack(sim.collider_set[collider].user_data);
+            if tag != Some(Egg::tag(egg.id)) {
+                violations.push(format!("egg {}: collider {:?} is tagged {:?}", egg.id, collider, tag));
+            }
+        }
+    }
+
+    for (handle, body) in sim.rigid_body_set.iter() {
+        if body.is_dynamic() && !body_owners.contains_key(&handle) && !egg_bodies.contains(&handle) {
+            violations.push(format!("body {:?} is dynamic but no creature owns it", handle));
+        }
+    }
+    for (handle, _) in sim.impulse_joint_set.iter() {
+        if !held_joints.contains(&handle) {
+            violations.push(format!("joint {:?} isn't held by any creature", handle));
+        }
+    }
+
+    for (handle, collider) in sim.collider_set.iter() {
+        let Some(owner) = collider.parent().and_then(|body| body_owners.get(&body)) else {
+            continue;
+        };
+        let tag = ColliderTag::unpack(collider.user_data);
+        if tag.and_then(|tag| tag.creature_id()) != Some(*owner) {
+            violations.push(format!("collider {:?} on creature {}'s body is tagged {:?}", handle, owner, tag));
+        }
+        let part = sim.body_parts.get(handle);
+        if part.map(|part| (part.creature, part.segment)) != tag.map(|tag| (CreatureId(tag.id), tag.segment)) {
+            violations.push(format!("collider {:?} is tagged {:?} but registered as {:?}", handle, tag, part));
+        }
+    }
+    for (handle, part) in sim.body_parts.iter() {
+        if !sim.collider_set.contains(handle) {
+            violations.push(format!("body part registry has {:?} for missing collider {:?}", part, handle));
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_tank_is_consistent() {
+        let mut sim = Simulation::default();
+        assert_eq!(check(&sim), Vec::<String>::new());
+        for _ in 0..120 {
+            sim.tick(1.0 / 60.0);
+        }
+        assert_eq!(check(&sim), Vec::<String>::new());
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let mut sim = Simulation::default();
+        let body = sim.creatures[0].get_rigid_body_handles()[0];
+        sim.creatures[0].attributes_mut().energy = f32::NAN;
+        for (_, collider) in sim.collider_set.iter_mut() {
+            if collider.parent() == Some(body) {
+                collider.user_data = 12345;
+            }
+        }
+        sim.rigid_body_set[sim.creatures[1].get_rigid_body_handles()[0]].set_translation(vector![f32::INFINITY, 0.0], false);
+
+        let stray = sim.rigid_body_set.insert(RigidBodyBuilder::dynamic());
+        let holder = sim.creatures[2].get_rigid_body_handles()[0];
+        sim.impulse_joint_set.insert(holder, stray, FixedJointBuilder::new(), true);
+
+        let violations = check(&sim);
+        assert!(violations.iter().any(|v| v.contains("attribute energy is NaN")), "{:?}", violations);
+        assert!(violations.iter().any(|v| v.contains("no creature owns it")), "{:?}", violations);
+        assert!(violations.iter().any(|v| v.contains("isn't held by any creature")), "{:?}", violations);
+        assert!(violations.iter().any(|v| v.contains("is tagged None")), "{:?}", violations);
+        assert!(violations.iter().any(|v| v.contains("non-finite")), "{:?}", violations);
+    }
+}
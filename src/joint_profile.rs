@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// How one joint of a chain resists bending.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointSetting {
+    /// Spring pulling the joint back to straight. 0 lets it rest at any angle.
+    pub stiffness: f32,
+    /// Resistance to bending quickly, and how hard the joint's motor drives it.
+    pub damping: f32,
+    /// Furthest the joint bends either way, in radians.
+    pub limit: f32,
+}
+
+impl JointSetting {
+    fn lerp(&self, other: &JointSetting, t: f32) -> JointSetting {
+        JointSetting {
+            stiffness: self.stiffness * (1.0 - t) + other.stiffness * t,
+            damping: self.damping * (1.0 - t) + other.damping * t,
+            limit: self.limit * (1.0 - t) + other.limit * t,
+        }
+    }
+}
+
+/// How stiff a segmented body is along its length: the joint nearest the head gets `head`,
+/// the last one `tail`, and those between are interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointProfile {
+    pub head: JointSetting,
+    pub tail: JointSetting,
+}
+
+impl JointProfile {
+    /// The same setting everywhere, as every chain used to be.
+    pub fn uniform(setting: JointSetting) -> Self {
+        Self { head: setting, tail: setting }
+    }
+
+    /// Firm behind the head, loosening toward a floppy tail.
+    pub fn tapered() -> Self {
+        Self {
+            head: JointSetting { stiffness: 0.2, damping: 0.15, limit: 0.02 },
+            tail: JointSetting { stiffness: 0.0, damping: 0.05, limit: 0.1 },
+        }
+    }
+
+    /// The setting for joint `index` of `count`, counting from the head.
+    pub fn at(&self, index: usize, count: usize) -> JointSetting {
+        let t = if count > 1 { index as f32 / (count - 1) as f32 } else { 0.0 };
+        self.head.lerp(&self.tail, t.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_from_head_to_tail() {
+        let profile = JointProfile::tapered();
+        assert_eq!(profile.at(0, 9), profile.head);
+        assert_eq!(profile.at(8, 9), profile.tail);
+        let middle = profile.at(4, 9);
+        assert!((middle.limit - (profile.head.limit + profile.tail.limit) / 2.0).abs() < 1e-6);
+        assert!(middle.stiffness < profile.head.stiffness && middle.stiffness > profile.tail.stiffness);
+        assert_eq!(profile.at(0, 1), profile.head);
+    }
+}
@@ -1,8 +1,58 @@
 pub mod creature_attributes;
 pub mod creature;
+pub mod collider_tag;
+pub mod body_parts;
+pub mod cannibalism;
+pub mod light;
+pub mod fields;
+pub mod glass;
+pub mod flow;
+pub mod hydro;
+pub mod phototaxis;
+pub mod wall_following;
+pub mod random;
+pub mod appendage;
+pub mod appearance;
+pub mod joint_profile;
+pub mod gait;
+pub mod behavior;
+pub mod tunable;
+pub mod egg;
+pub mod disease;
+pub mod player;
+pub mod objectives;
+pub mod director;
+pub mod schedule;
+pub mod notebook;
+pub mod observer;
+pub mod impacts;
+pub mod forensics;
+pub mod formation;
+pub mod signals;
+pub mod silhouette;
+pub mod stats;
+pub mod summary;
+pub mod symbiosis;
 pub mod creatures;
 pub mod app;
+pub mod personality;
+pub mod physics_presets;
+pub mod load_governor;
+pub mod invariants;
+pub mod self_test;
+pub mod simulation;
+pub mod scenario;
+pub mod showcase;
+pub mod sim_thread;
+pub mod arena;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stress;
+#[cfg(test)]
+pub(crate) mod mock_world;
+#[cfg(test)]
+pub(crate) mod custody;
 
+#[cfg(target_arch = "wasm32")]
 use crate::app::SoftiesApp;
 
 #[cfg(target_arch = "wasm32")]
@@ -26,4 +76,14 @@ pub async fn start(canvas_id: &str) -> Result<(), eframe::wasm_bindgen::JsValue>
             Box::new(|_cc| Box::new(SoftiesApp::default())),
         )
         .await
+}
+
+/// Called by worker.js inside the simulation web worker.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn worker_main() -> Result<(), eframe::wasm_bindgen::JsValue> {
+    console_error_panic_hook::set_once();
+    tracing_wasm::set_as_global_default();
+
+    crate::sim_thread::worker::worker_main()
 } 
\ No newline at end of file
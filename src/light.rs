@@ -0,0 +1,87 @@
+use eframe::egui::Color32;
+
+// Fraction of light left after each meter of water
+const TRANSMISSION_PER_METER: f32 = 0.88;
+// Color deep water fades things toward
+const DEEP_WATER: Color32 = Color32::from_rgb(8, 24, 48);
+// How far toward DEEP_WATER a creature in total darkness is drawn. Below 1 so it never vanishes.
+const MAX_DEPTH_TINT: f32 = 0.65;
+// Water temperature (°C) in full sunlight and in total darkness
+const SURFACE_TEMPERATURE: f32 = 24.0;
+const DARK_TEMPERATURE: f32 = 12.0;
+
+/// Sunlight coming in from the top of the tank and dimming with depth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightField {
+    surface_y: f32,
+    // Added to every water temperature, e.g. to model a heated tank
+    temperature_offset: f32,
+}
+
+impl LightField {
+    /// The light in a tank of this height, centered on the origin.
+    pub fn for_tank(world_height: f32) -> Self {
+        Self { surface_y: world_height / 2.0, temperature_offset: 0.0 }
+    }
+
+    /// The same light over water `offset` °C warmer (or colder, if negative).
+    pub fn with_temperature_offset(self, offset: f32) -> Self {
+        Self { temperature_offset: offset, ..self }
+    }
+
+    /// Light reaching height `y`, from 1.0 at the surface toward 0.0 in the depths.
+    pub fn intensity_at(&self, y: f32) -> f32 {
+        let depth = (self.surface_y - y).max(0.0);
+        TRANSMISSION_PER_METER.powf(depth)
+    }
+
+    /// Height at which the light has dimmed to `intensity`: the inverse of `intensity_at`.
+    /// Anything from 1.0 up is the surface.
+    pub fn height_at(&self, intensity: f32) -> f32 {
+        let depth = intensity.clamp(f32::MIN_POSITIVE, 1.0).ln() / TRANSMISSION_PER_METER.ln();
+        self.surface_y - depth
+    }
+
+    /// Water temperature at height `y`, in °C. The sun warms the water as much as it lights it.
+    pub fn water_temperature_at(&self, y: f32) -> f32 {
+        DARK_TEMPERATURE + (SURFACE_TEMPERATURE - DARK_TEMPERATURE) * self.intensity_at(y) + self.temperature_offset
+    }
+
+    /// Fades `color` toward the deep water color the darker it is at `y`, so deeper
+    /// creatures read darker, bluer and lower contrast. Alpha is kept.
+    pub fn tint(&self, color: Color32, y: f32) -> Color32 {
+        let t = (1.0 - self.intensity_at(y)) * MAX_DEPTH_TINT;
+        // Colors are premultiplied, so the water color has to be scaled by alpha too
+        let alpha = color.a() as f32 / 255.0;
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 * alpha - from as f32) * t).round() as u8;
+        Color32::from_rgba_premultiplied(
+            mix(color.r(), DEEP_WATER.r()),
+            mix(color.g(), DEEP_WATER.g()),
+            mix(color.b(), DEEP_WATER.b()),
+            color.a(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deeper_is_darker_and_bluer() {
+        let light = LightField::for_tank(16.0);
+        assert_eq!(light.intensity_at(8.0), 1.0);
+        assert!(light.intensity_at(-8.0) < light.intensity_at(0.0));
+        assert!((light.height_at(light.intensity_at(-3.0)) + 3.0).abs() < 1e-4);
+        assert_eq!(light.height_at(1.5), 8.0);
+        assert_eq!(light.with_temperature_offset(5.0).water_temperature_at(0.0), light.water_temperature_at(0.0) + 5.0);
+
+        let color = Color32::from_rgb(200, 200, 100);
+        assert_eq!(light.tint(color, 8.0), color);
+        let deep = light.tint(color, -8.0);
+        assert!(deep.r() < color.r() && deep.g() < color.g());
+        assert!(deep.b() as f32 / deep.r() as f32 > color.b() as f32 / color.r() as f32);
+
+        assert_eq!(light.tint(Color32::TRANSPARENT, -8.0), Color32::TRANSPARENT);
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+/// How hard the simulation is cutting corners to keep up with real time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum LoadLevel {
+    #[default]
+    Normal,
+    Strained,
+    Overloaded,
+}
+
+impl LoadLevel {
+    /// Creatures run their behavior once every this many ticks (staggered by id).
+    pub fn behavior_interval(&self) -> u64 {
+        match self {
+            LoadLevel::Normal => 1,
+            LoadLevel::Strained => 2,
+            LoadLevel::Overloaded => 4,
+        }
+    }
+
+    /// Fraction of the usual number of particles effects should spawn.
+    pub fn particle_scale(&self) -> f32 {
+        match self {
+            LoadLevel::Normal => 1.0,
+            LoadLevel::Strained => 0.5,
+            LoadLevel::Overloaded => 0.0,
+        }
+    }
+
+    /// Whether new plankton may be spawned to replace lost ones.
+    pub fn allows_respawning(&self) -> bool {
+        *self == LoadLevel::Normal
+    }
+
+    fn degraded(&self) -> LoadLevel {
+        match self {
+            LoadLevel::Normal => LoadLevel::Strained,
+            LoadLevel::Strained | LoadLevel::Overloaded => LoadLevel::Overloaded,
+        }
+    }
+
+    fn recovered(&self) -> LoadLevel {
+        match self {
+            LoadLevel::Normal | LoadLevel::Strained => LoadLevel::Normal,
+            LoadLevel::Overloaded => LoadLevel::Strained,
+        }
+    }
+}
+
+// Smoothing factor for the tick time moving average
+const AVERAGE_WEIGHT: f32 = 0.1;
+// Degrade when the average tick takes this much of the budget...
+const DEGRADE_LOAD: f32 = 0.9;
+// ...and only recover once it has stayed under this for a while, so we don't flap between levels
+const RECOVER_LOAD: f32 = 0.5;
+// Ticks to wait after a change before degrading further, giving the last step time to show
+const DEGRADE_COOLDOWN_TICKS: u32 = 30;
+const RECOVER_AFTER_TICKS: u32 = 180;
+
+/// Watches how long ticks take compared to the time they simulate and picks a `LoadLevel`.
+/// Degrades quickly and recovers slowly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadGovernor {
+    average_tick_seconds: f32,
+    level: LoadLevel,
+    ticks_since_change: u32,
+    calm_ticks: u32,
+}
+
+impl LoadGovernor {
+    pub fn level(&self) -> LoadLevel {
+        self.level
+    }
+
+    /// Average tick time as a fraction of the budget. Above 1.0 means we're falling behind.
+    pub fn load(&self, budget_seconds: f32) -> f32 {
+        self.average_tick_seconds / budget_seconds
+    }
+
+    /// Records how long a tick took in wall-clock time, given the time it was allowed.
+    pub fn record_tick(&mut self, tick_seconds: f32, budget_seconds: f32) {
+        self.average_tick_seconds += (tick_seconds - self.average_tick_seconds) * AVERAGE_WEIGHT;
+        self.ticks_since_change = self.ticks_since_change.saturating_add(1);
+
+        let load = self.load(budget_seconds);
+        if load < RECOVER_LOAD {
+            self.calm_ticks += 1;
+        } else {
+            self.calm_ticks = 0;
+        }
+
+        if load > DEGRADE_LOAD && self.level != LoadLevel::Overloaded && self.ticks_since_change >= DEGRADE_COOLDOWN_TICKS {
+            self.change_level(self.level.degraded());
+        } else if self.level != LoadLevel::Normal && self.calm_ticks >= RECOVER_AFTER_TICKS {
+            self.change_level(self.level.recovered());
+        }
+    }
+
+    fn change_level(&mut self, level: LoadLevel) {
+        self.level = level;
+        self.ticks_since_change = 0;
+        self.calm_ticks = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn slow_ticks_degrade_step_by_step() {
+        let mut governor = LoadGovernor::default();
+        for _ in 0..DEGRADE_COOLDOWN_TICKS {
+            governor.record_tick(BUDGET * 2.0, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Strained);
+
+        for _ in 0..DEGRADE_COOLDOWN_TICKS {
+            governor.record_tick(BUDGET * 2.0, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Overloaded);
+        assert_eq!(governor.level().behavior_interval(), 4);
+        assert!(!governor.level().allows_respawning());
+    }
+
+    #[test]
+    fn recovers_only_after_a_calm_stretch() {
+        let mut governor = LoadGovernor::default();
+        for _ in 0..DEGRADE_COOLDOWN_TICKS {
+            governor.record_tick(BUDGET * 2.0, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Strained);
+
+        // Fast ticks, but not yet for long enough
+        for _ in 0..RECOVER_AFTER_TICKS / 2 {
+            governor.record_tick(BUDGET * 0.1, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Strained);
+
+        for _ in 0..RECOVER_AFTER_TICKS {
+            governor.record_tick(BUDGET * 0.1, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Normal);
+    }
+
+    #[test]
+    fn brief_spike_does_not_degrade() {
+        let mut governor = LoadGovernor::default();
+        for _ in 0..100 {
+            governor.record_tick(BUDGET * 0.2, BUDGET);
+        }
+        governor.record_tick(BUDGET * 3.0, BUDGET);
+        for _ in 0..100 {
+            governor.record_tick(BUDGET * 0.2, BUDGET);
+        }
+        assert_eq!(governor.level(), LoadLevel::Normal);
+    }
+}
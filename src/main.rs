@@ -1,9 +1,10 @@
 use softies::app::SoftiesApp; 
-
-// Keep module declarations, but main doesn't use them directly
-mod creature;
-mod creatures;
-mod creature_attributes; // Re-enable this module for the binary crate
+use softies::arena::Contender;
+use softies::physics_presets::PhysicsPreset;
+use softies::scenario::Scenario;
+use softies::showcase;
+use softies::silhouette::Silhouette;
+use softies::simulation::Simulation;
 
 // Constants for the aquarium
 #[allow(dead_code)]
@@ -17,7 +18,114 @@ const MAX_ZOOM: f32 = 5.0;
 #[allow(dead_code)]
 const CAMERA_BOUND_PADDING: f32 = 0.3; // 30% padding
 
+/// Returns the creature count from `--stress N`, if given.
+fn stress_arg(args: &[String]) -> Option<Result<usize, String>> {
+    let position = args.iter().position(|arg| arg == "--stress")?;
+    Some(match args.get(position + 1) {
+        Some(count) => count.parse().map_err(|_| format!("--stress expects a creature count, got '{}'", count)),
+        None => Err("--stress expects a creature count".to_string()),
+    })
+}
+
+/// Returns the preset from `--preset NAME`, or `Balanced` if not given.
+fn preset_arg(args: &[String]) -> Result<PhysicsPreset, String> {
+    let Some(position) = args.iter().position(|arg| arg == "--preset") else { return Ok(PhysicsPreset::default()) };
+    match args.get(position + 1) {
+        Some(name) => PhysicsPreset::parse(name).ok_or_else(|| format!("--preset expects accurate, balanced or fast, got '{}'", name)),
+        None => Err("--preset expects accurate, balanced or fast".to_string()),
+    }
+}
+
+/// Returns the two contenders from `--arena A B`, if given.
+fn arena_arg(args: &[String]) -> Option<Result<(Contender, Contender), String>> {
+    let position = args.iter().position(|arg| arg == "--arena")?;
+    let contender = |index: usize| match args.get(position + index) {
+        Some(name) => Contender::parse(name).ok_or_else(|| format!("--arena doesn't know the species '{}'", name)),
+        None => Err("--arena expects two species, e.g. --arena snake plankton".to_string()),
+    };
+    Some(contender(1).and_then(|first| Ok((first, contender(2)?))))
+}
+
+/// Returns the scenario from `--open FILE`, if given. A PNG is read as a layout: black is
+/// solid rock, white is water.
+fn open_arg(args: &[String]) -> Option<Result<Scenario, String>> {
+    let position = args.iter().position(|arg| arg == "--open")?;
+    let Some(path) = args.get(position + 1) else { return Some(Err("--open expects a scenario file or a PNG layout".to_string())) };
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => return Some(Err(format!("Couldn't read {}: {}", path, err))),
+    };
+    let path = std::path::Path::new(path);
+    if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png")) {
+        let name = path.file_stem().map_or("Layout".into(), |stem| stem.to_string_lossy());
+        return Some(
+            Silhouette::from_image(&bytes)
+                .map(|terrain| Scenario::from_silhouette(&name, terrain))
+                .map_err(|err| format!("Couldn't read {} as a layout: {}", path.display(), err)),
+        );
+    }
+    Some(Scenario::decode(&bytes).map_err(|err| format!("{} isn't a scenario file: {}", path.display(), err)))
+}
+
+/// Returns the directory from `--forensics DIR`, if given, creating it if need be.
+fn forensics_arg(args: &[String]) -> Option<Result<std::path::PathBuf, String>> {
+    let position = args.iter().position(|arg| arg == "--forensics")?;
+    let Some(dir) = args.get(position + 1) else { return Some(Err("--forensics expects a directory to write dumps to".to_string())) };
+    let dir = std::path::PathBuf::from(dir);
+    Some(std::fs::create_dir_all(&dir).map(|()| dir.clone()).map_err(|err| format!("Couldn't create {}: {}", dir.display(), err)))
+}
+
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    match stress_arg(&args).map(|count| count.and_then(|count| Ok((count, preset_arg(&args)?)))) {
+        Some(Ok((creature_count, preset))) => {
+            // Headless: no window, just the numbers
+            println!("{}", softies::stress::run(creature_count, preset));
+            return Ok(());
+        }
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+    match arena_arg(&args) {
+        Some(Ok((first, second))) => {
+            println!("{}", softies::arena::run(first, second));
+            return Ok(());
+        }
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
+    // A seeded, scripted run for recordings: the same every time, given no input
+    let demo = args.iter().any(|arg| arg == "--demo");
+    let mut simulation = match open_arg(&args) {
+        Some(_) if demo => {
+            eprintln!("--demo plays its own scenario, so it can't be combined with --open");
+            std::process::exit(2);
+        }
+        Some(Ok(scenario)) => scenario.build(),
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+        None if demo => showcase::scenario().build(),
+        None => Simulation::default(),
+    };
+    // If asked, anomalies leave a dump in the given directory, for a ready-made repro
+    match forensics_arg(&args) {
+        Some(Ok(dir)) => simulation.record_forensics(dir),
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
     // Setup tracing for native panic info with more verbose output
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::DEBUG)
@@ -37,6 +145,9 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Softies Aquarium",
         native_options,
-        Box::new(|_cc| Box::new(SoftiesApp::default())),
+        Box::new(move |_cc| {
+            let app = SoftiesApp::new(simulation);
+            Box::new(if demo { app.with_camera_path(showcase::camera_path()) } else { app })
+        }),
     )
 }
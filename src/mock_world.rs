@@ -0,0 +1,113 @@
+//! Test doubles for creature behavior, so state transitions and target selection can be
+//! tested without a physics pipeline.
+
+use nalgebra::Vector2;
+use rapier2d::prelude::*;
+
+use crate::creature::{Creature, CreatureId, CreatureInfo, Senses, WorldContext};
+use crate::flow::FlowField;
+use crate::simulation::{PIXELS_PER_METER, WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+/// `Senses` with scripted answers. Ignores colliders entirely: a creature is "near" if its
+/// scripted position plus radius reaches into the query circle.
+#[derive(Default)]
+pub struct ScriptedSenses {
+    pub creatures: Vec<CreatureInfo>,
+}
+
+impl Senses for ScriptedSenses {
+    fn all_creatures(&self) -> &[CreatureInfo] {
+        &self.creatures
+    }
+
+    fn creatures_near(&self, _bodies: &RigidBodySet, center: Vector2<f32>, radius: f32, own_id: CreatureId) -> Vec<&CreatureInfo> {
+        self.creatures
+            .iter()
+            .filter(|info| info.id != own_id && (info.position - center).norm() <= radius + info.radius)
+            .collect()
+    }
+}
+
+/// Just enough world to call `update_state_and_behavior`: physics sets for the creature under
+/// test to spawn into and act on, scripted neighbors, and a world context.
+/// Nothing is ever stepped, so a creature's actions show up as velocity changes, not movement.
+pub struct MockWorld {
+    pub rigid_body_set: RigidBodySet,
+    pub collider_set: ColliderSet,
+    pub impulse_joint_set: ImpulseJointSet,
+    pub senses: ScriptedSenses,
+    pub context: WorldContext,
+    next_neighbor_id: u64,
+}
+
+impl MockWorld {
+    /// Id to spawn the creature under test with. Scripted neighbors get ids after it.
+    pub const SUBJECT_ID: CreatureId = CreatureId(0);
+
+    pub fn new() -> Self {
+        Self {
+            rigid_body_set: RigidBodySet::new(),
+            collider_set: ColliderSet::new(),
+            impulse_joint_set: ImpulseJointSet::new(),
+            senses: ScriptedSenses::default(),
+            context: WorldContext {
+                world_width: WORLD_WIDTH_METERS,
+                world_height: WORLD_HEIGHT_METERS,
+                pixels_per_meter: PIXELS_PER_METER,
+                water_temperature_offset: 0.0,
+                flow: FlowField::default(),
+            },
+            next_neighbor_id: Self::SUBJECT_ID.0 + 1,
+        }
+    }
+
+    /// Scripts a creature the subject can sense. It has no physics presence. Returns its id.
+    pub fn add_neighbor(&mut self, creature_type_name: &'static str, position: Vector2<f32>, velocity: Vector2<f32>) -> CreatureId {
+        let id = CreatureId(self.next_neighbor_id);
+        self.next_neighbor_id += 1;
+        self.senses.creatures.push(CreatureInfo {
+            id,
+            creature_type_name,
+            primary_body_handle: RigidBodyHandle::invalid(),
+            position,
+            velocity,
+            radius: 0.1,
+        });
+        id
+    }
+
+    /// Runs one behavior update for `creature`.
+    pub fn update(&mut self, creature: &mut dyn Creature, dt: f32) {
+        creature.update_state_and_behavior(
+            dt,
+            creature.id(),
+            &mut self.rigid_body_set,
+            &mut self.impulse_joint_set,
+            &self.senses,
+            &self.context,
+        );
+    }
+
+    /// Velocity of the creature's primary body.
+    pub fn velocity(&self, creature: &dyn Creature) -> Vector2<f32> {
+        *self.rigid_body_set[creature.get_rigid_body_handles()[0]].linvel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_senses_respect_radius_and_self() {
+        let mut world = MockWorld::new();
+        let near = world.add_neighbor("Plankton", Vector2::new(0.5, 0.0), Vector2::zeros());
+        world.add_neighbor("Plankton", Vector2::new(5.0, 0.0), Vector2::zeros());
+
+        let found = world.senses.creatures_near(&world.rigid_body_set, Vector2::zeros(), 1.0, MockWorld::SUBJECT_ID);
+        assert_eq!(found.iter().map(|info| info.id).collect::<Vec<_>>(), vec![near]);
+
+        let found = world.senses.creatures_near(&world.rigid_body_set, Vector2::new(0.5, 0.0), 1.0, near);
+        assert!(found.is_empty());
+    }
+}
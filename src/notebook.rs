@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::creature::CreatureId;
+use crate::simulation::TIMESTEP;
+
+/// One observation, stamped with when in the simulation it was made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    /// Stays the same while other notes come and go, unlike the note's place in the list.
+    pub id: u64,
+    pub tick: u64,
+    pub text: String,
+    /// The creature the note is about, if any.
+    pub creature: Option<CreatureId>,
+}
+
+impl Note {
+    /// Simulated time of the note as `h:mm:ss`.
+    pub fn timestamp(&self) -> String {
+        let seconds = (self.tick as f64 * TIMESTEP as f64) as u64;
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+}
+
+/// Notes the user has jotted down about this world. Part of the `Simulation`, so they are
+/// saved and restored along with it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Notebook {
+    notes: Vec<Note>,
+    next_id: u64,
+}
+
+impl Notebook {
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Adds a note made at `tick`. Blank notes are ignored.
+    pub fn add(&mut self, tick: u64, text: &str, creature: Option<CreatureId>) {
+        let text = text.trim();
+        if !text.is_empty() {
+            self.notes.push(Note { id: self.next_id, tick, text: text.to_string(), creature });
+            self.next_id += 1;
+        }
+    }
+
+    /// Removes the note with this id, if it's still there.
+    pub fn remove(&mut self, id: u64) {
+        self.notes.retain(|note| note.id != id);
+    }
+
+    /// The whole notebook as Markdown, one bullet per note.
+    pub fn to_markdown(&self) -> String {
+        self.notes
+            .iter()
+            .map(|note| match note.creature {
+                Some(creature) => format!("- [{}] (creature {}) {}\n", note.timestamp(), creature, note.text),
+                None => format!("- [{}] {}\n", note.timestamp(), note.text),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_stamped_with_simulated_time() {
+        let mut notebook = Notebook::default();
+        notebook.add(60 * 75, "  Snakes cluster in the corner ", Some(CreatureId(3)));
+        notebook.add(60 * 3600, "Bloom", None);
+        notebook.add(10, "   ", None);
+
+        assert_eq!(notebook.notes().len(), 2);
+        assert_eq!(notebook.notes()[0].timestamp(), "0:01:15");
+        assert_eq!(
+            notebook.to_markdown(),
+            "- [0:01:15] (creature 3) Snakes cluster in the corner\n- [1:00:00] Bloom\n"
+        );
+        // Ids outlive the notes' positions: removing the first again is a no-op, not the second
+        let first = notebook.notes()[0].id;
+        notebook.remove(first);
+        notebook.remove(first);
+        notebook.remove(99);
+        assert_eq!(notebook.notes().len(), 1);
+        assert_eq!(notebook.notes()[0].text, "Bloom");
+    }
+}
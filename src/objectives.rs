@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::creature::Creature;
+
+/// Something for the user to aim for in the tank. Optional: none are active until picked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Keep at least `count` creatures of a type alive for `seconds` in a row.
+    KeepPopulation { type_name: String, count: usize, seconds: f32 },
+    /// Raise a creature of a type that is `generations` hatchings removed from the first ones.
+    BreedGenerations { type_name: String, generations: u32 },
+}
+
+impl Objective {
+    /// The goals offered in the UI.
+    pub fn suggestions() -> Vec<Objective> {
+        vec![
+            Objective::KeepPopulation { type_name: "Plankton".to_string(), count: 30, seconds: 600.0 },
+            Objective::KeepPopulation { type_name: "Snake".to_string(), count: 3, seconds: 300.0 },
+            Objective::BreedGenerations { type_name: "Plankton".to_string(), generations: 5 },
+        ]
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Objective::KeepPopulation { type_name, count, seconds } => {
+                format!("Keep {} {} alive for {:.0} minutes", count, type_name.to_lowercase(), seconds / 60.0)
+            }
+            Objective::BreedGenerations { type_name, generations } => {
+                format!("Breed {} generations of {}", generations, type_name.to_lowercase())
+            }
+        }
+    }
+}
+
+/// An active objective and how far along it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectiveProgress {
+    pub objective: Objective,
+    /// From 0.0 to 1.0. Population goals drop back to 0 whenever the count dips.
+    pub progress: f32,
+    /// Tick it was completed on. Completed objectives stay completed.
+    pub completed_at: Option<u64>,
+    // Seconds the population goal has been held so far
+    held_seconds: f32,
+}
+
+impl ObjectiveProgress {
+    pub fn new(objective: Objective) -> Self {
+        Self { objective, progress: 0.0, completed_at: None, held_seconds: 0.0 }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    /// Checks the objective against the creatures after tick `tick`, which lasted `dt` seconds.
+    pub fn update(&mut self, creatures: &[Box<dyn Creature>], dt: f32, tick: u64) {
+        if self.is_complete() {
+            return;
+        }
+        self.progress = match &self.objective {
+            Objective::KeepPopulation { type_name, count, seconds } => {
+                if of_type(creatures, type_name).count() >= *count {
+                    self.held_seconds += dt;
+                } else {
+                    self.held_seconds = 0.0;
+                }
+                self.held_seconds / seconds
+            }
+            Objective::BreedGenerations { type_name, generations } => {
+                let highest = of_type(creatures, type_name).map(|creature| creature.attributes().generation).max().unwrap_or(0);
+                // Progress is kept, so a lineage dying out doesn't undo it
+                self.progress.max(highest as f32 / *generations as f32)
+            }
+        }
+        .min(1.0);
+        if self.progress >= 1.0 {
+            self.completed_at = Some(tick);
+        }
+    }
+}
+
+fn of_type<'a>(creatures: &'a [Box<dyn Creature>], type_name: &'a str) -> impl Iterator<Item = &'a Box<dyn Creature>> {
+    creatures.iter().filter(move |creature| creature.type_name() == type_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creatures::plankton::Plankton;
+
+    fn plankton(count: usize, generation: u32) -> Vec<Box<dyn Creature>> {
+        (0..count)
+            .map(|_| {
+                let mut plankton = Plankton::new(0.08);
+                plankton.attributes_mut().generation = generation;
+                Box::new(plankton) as Box<dyn Creature>
+            })
+            .collect()
+    }
+
+    #[test]
+    fn population_must_hold_without_a_break() {
+        let objective = Objective::KeepPopulation { type_name: "Plankton".to_string(), count: 3, seconds: 2.0 };
+        let mut progress = ObjectiveProgress::new(objective);
+        progress.update(&plankton(3, 0), 1.5, 1);
+        assert_eq!(progress.progress, 0.75);
+        progress.update(&plankton(2, 0), 1.0, 2);
+        assert_eq!(progress.progress, 0.0);
+        progress.update(&plankton(4, 0), 1.0, 3);
+        progress.update(&plankton(4, 0), 1.0, 4);
+        assert_eq!(progress.completed_at, Some(4));
+        // Done is done
+        progress.update(&[], 1.0, 5);
+        assert!(progress.is_complete());
+    }
+
+    #[test]
+    fn generations_count_the_furthest_descendant() {
+        let objective = Objective::BreedGenerations { type_name: "Plankton".to_string(), generations: 4 };
+        let mut progress = ObjectiveProgress::new(objective);
+        progress.update(&plankton(5, 2), 0.1, 1);
+        assert_eq!(progress.progress, 0.5);
+        progress.update(&plankton(5, 1), 0.1, 2);
+        assert_eq!(progress.progress, 0.5);
+        progress.update(&plankton(1, 4), 0.1, 3);
+        assert!(progress.is_complete());
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use nalgebra::Vector2;
+use rapier2d::prelude::RigidBodySet;
+use serde::{Deserialize, Serialize};
+
+use crate::creature::{Creature, CreatureId, CreatureState};
+
+/// Observations kept per watched creature: the last 10 seconds at 60Hz. Readers that fall
+/// further behind than this miss the oldest.
+pub const MAX_OBSERVATIONS: usize = 600;
+
+/// A change of behavior state, the closest thing creatures have to a decision.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub tick: u64,
+    pub from: CreatureState,
+    pub to: CreatureState,
+}
+
+/// How one creature was doing at the end of one tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub tick: u64,
+    pub state: CreatureState,
+    pub energy: f32,
+    pub satiety: f32,
+    /// Of the creature's first segment.
+    pub position: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+    /// The most recent state change seen since the creature was first watched.
+    pub last_decision: Option<Decision>,
+}
+
+/// One watched creature and what has been seen of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Watch {
+    creature: CreatureId,
+    /// How many subscribers asked for it. Watching stops when the last one leaves.
+    subscribers: u32,
+    observations: VecDeque<Observation>,
+    /// Tick the creature was first found missing, i.e. died or was removed.
+    gone_at: Option<u64>,
+}
+
+/// Per-tick updates on the creatures someone has subscribed to, so inspectors, scripts and
+/// the like can follow a creature without reaching into the creatures list. Lives in the
+/// `Simulation` and is copied into each snapshot; readers pick up the observations newer
+/// than the last tick they saw, so none are lost between snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Observers {
+    watches: Vec<Watch>,
+}
+
+impl Observers {
+    pub fn subscribe(&mut self, creature: CreatureId) {
+        match self.watches.iter_mut().find(|watch| watch.creature == creature) {
+            Some(watch) => watch.subscribers += 1,
+            None => self.watches.push(Watch { creature, subscribers: 1, observations: VecDeque::new(), gone_at: None }),
+        }
+    }
+
+    /// Drops one subscription to `creature`. Its observations are discarded with the last.
+    pub fn unsubscribe(&mut self, creature: CreatureId) {
+        if let Some(watch) = self.watches.iter_mut().find(|watch| watch.creature == creature) {
+            watch.subscribers -= 1;
+        }
+        self.watches.retain(|watch| watch.subscribers > 0);
+    }
+
+    pub fn is_watching(&self, creature: CreatureId) -> bool {
+        self.watches.iter().any(|watch| watch.creature == creature)
+    }
+
+    /// Records a tick's worth of observations for every watched creature that's still around.
+    pub fn observe(&mut self, tick: u64, creatures: &[Box<dyn Creature>], rigid_body_set: &RigidBodySet) {
+        for watch in &mut self.watches {
+            if watch.gone_at.is_some() {
+                continue;
+            }
+            let creature = creatures.iter().find(|creature| creature.id() == watch.creature);
+            let body = creature.and_then(|creature| rigid_body_set.get(*creature.get_rigid_body_handles().first()?));
+            let (Some(creature), Some(body)) = (creature, body) else {
+                watch.gone_at = Some(tick);
+                continue;
+            };
+
+            let state = creature.current_state();
+            let previous = watch.observations.back();
+            let last_decision = match previous {
+                Some(previous) if previous.state != state => Some(Decision { tick, from: previous.state, to: state }),
+                Some(previous) => previous.last_decision,
+                None => None,
+            };
+            if watch.observations.len() == MAX_OBSERVATIONS {
+                watch.observations.pop_front();
+            }
+            watch.observations.push_back(Observation {
+                tick,
+                state,
+                energy: creature.attributes().energy,
+                satiety: creature.attributes().satiety,
+                position: *body.translation(),
+                velocity: *body.linvel(),
+                last_decision,
+            });
+        }
+    }
+
+    /// Observations of `creature` made after `tick`, oldest first. Pass the tick of the last
+    /// one you've handled to get only new ones, or 0 for all that are kept.
+    pub fn since(&self, creature: CreatureId, tick: u64) -> impl Iterator<Item = &Observation> {
+        self.watches
+            .iter()
+            .filter(move |watch| watch.creature == creature)
+            .flat_map(|watch| watch.observations.iter())
+            .filter(move |observation| observation.tick > tick)
+    }
+
+    pub fn latest(&self, creature: CreatureId) -> Option<&Observation> {
+        self.watches.iter().find(|watch| watch.creature == creature)?.observations.back()
+    }
+
+    /// When a watched creature was found to be gone, if it has.
+    pub fn gone_at(&self, creature: CreatureId) -> Option<u64> {
+        self.watches.iter().find(|watch| watch.creature == creature)?.gone_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn subscribers_follow_a_creature_tick_by_tick() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let plankton = sim.spawn_plankton(nalgebra::Vector2::zeros());
+        sim.subscribe(plankton);
+        sim.subscribe(plankton);
+        for _ in 0..5 {
+            sim.tick(TIMESTEP);
+        }
+        let seen: Vec<u64> = sim.snapshot().panels.observers.since(plankton, 2).map(|observation| observation.tick).collect();
+        assert_eq!(seen, vec![3, 4, 5]);
+
+        // Worn out, it decides to rest
+        sim.creatures[0].attributes_mut().energy = 0.0;
+        for _ in 0..5 {
+            sim.tick(TIMESTEP);
+        }
+        let latest = sim.observers().latest(plankton).unwrap();
+        assert_eq!(latest.state, CreatureState::Resting);
+        assert_eq!(latest.last_decision.map(|decision| decision.to), Some(CreatureState::Resting));
+
+        // Still watched until the second subscriber leaves too
+        sim.unsubscribe(plankton);
+        sim.remove_creature(plankton);
+        sim.tick(TIMESTEP);
+        assert_eq!(sim.observers().gone_at(plankton), Some(11));
+        sim.unsubscribe(plankton);
+        assert!(!sim.observers().is_watching(plankton));
+    }
+}
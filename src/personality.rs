@@ -0,0 +1,107 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+// No trait goes below this, so nobody's thresholds collapse to nothing
+const MIN_TRAIT: f32 = 0.2;
+
+/// How one individual differs from the rest of its species. Each trait scales some of its
+/// behavior thresholds (see `CreatureAttributes`); 1 is the species as written.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Personality {
+    /// Bolder ones go after food while less hungry, and turn on their own kind sooner.
+    pub boldness: f32,
+    /// More active ones tire later and are up again sooner after resting.
+    pub activity: f32,
+    /// More sociable ones stick closer to a flock.
+    pub sociality: f32,
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Self { boldness: 1.0, activity: 1.0, sociality: 1.0 }
+    }
+}
+
+/// How one trait varies: around `mean`, up to `spread` either side, most often near the middle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TraitSpread {
+    pub mean: f32,
+    pub spread: f32,
+}
+
+impl TraitSpread {
+    fn sample(&self, rng: &mut impl Rng) -> f32 {
+        // The difference of two uniforms is triangular: bell-ish, and never past the spread
+        let offset = rng.gen_range(0.0..=1.0) - rng.gen_range(0.0..=1.0);
+        (self.mean + self.spread * offset).max(MIN_TRAIT)
+    }
+}
+
+/// The personalities a tank hands out at spawn, one trait at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PersonalitySpread {
+    pub boldness: TraitSpread,
+    pub activity: TraitSpread,
+    pub sociality: TraitSpread,
+}
+
+impl Default for PersonalitySpread {
+    /// No variation: everyone is the species as written. Scenarios opt in to more.
+    fn default() -> Self {
+        Self::varied(0.0)
+    }
+}
+
+impl PersonalitySpread {
+    /// Every trait around 1, up to `spread` either side.
+    pub fn varied(spread: f32) -> Self {
+        let spread = TraitSpread { mean: 1.0, spread };
+        Self { boldness: spread, activity: spread, sociality: spread }
+    }
+
+    /// A personality drawn from its own generator seeded with `seed`, so the same seed always
+    /// gives the same one, whatever else has been drawn.
+    pub fn sample(&self, seed: u64) -> Personality {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Personality {
+            boldness: self.boldness.sample(&mut rng),
+            activity: self.activity.sample(&mut rng),
+            sociality: self.sociality.sample(&mut rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creature_attributes::{CreatureAttributes, DietType};
+
+    #[test]
+    fn seeded_personalities_shift_thresholds() {
+        let spread = PersonalitySpread::varied(0.4);
+        assert_eq!(spread.sample(7), spread.sample(7));
+        assert_ne!(spread.sample(7), spread.sample(8));
+        for seed in 0..200 {
+            let personality = spread.sample(seed);
+            for value in [personality.boldness, personality.activity, personality.sociality] {
+                assert!((0.6..=1.4).contains(&value), "{:?}", personality);
+            }
+        }
+        // Unless a scenario asks for variety, every creature is the species as written
+        assert_eq!(PersonalitySpread::default().sample(3), Personality::default());
+
+        // Same species, same state, different minds
+        let mut bold = CreatureAttributes::new(100.0, 1.0, 100.0, 0.1, DietType::Carnivore, 1.0, vec![], vec![]);
+        bold.satiety = 55.0;
+        bold.energy = 18.0;
+        let mut timid = bold.clone();
+        bold.personality = Personality { boldness: 1.3, activity: 1.3, sociality: 1.0 };
+        timid.personality = Personality { boldness: 0.8, activity: 0.8, sociality: 1.0 };
+        assert!(bold.is_hungry() && !timid.is_hungry());
+        assert!(!bold.is_tired() && timid.is_tired());
+        bold.energy = 70.0;
+        timid.energy = 70.0;
+        assert!(bold.is_rested(0.8) && !timid.is_rested(0.8));
+    }
+}
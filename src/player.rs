@@ -0,0 +1,142 @@
+use nalgebra::Vector2;
+use rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::creature::{Creature, CreatureId};
+
+/// Forward acceleration of the whole creature at full thrust, in m/s².
+const THRUST_ACCELERATION: f32 = 6.0;
+/// Sideways acceleration at full turn, in m/s². Applied at the head, so it swings the body round.
+const TURN_ACCELERATION: f32 = 4.0;
+/// Energy spent per second at full thrust.
+const THRUST_ENERGY_PER_SECOND: f32 = 2.0;
+/// Energy a bite takes out of its victim, and satiety it gives the biter.
+pub const BITE_DAMAGE: f32 = 10.0;
+pub const BITE_SATIETY: f32 = 8.0;
+/// How badly a bite wounds the segment bitten, from 0 to 1.
+pub const BITE_WOUND: f32 = 0.5;
+
+/// What the player is asking their creature to do this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PlayerInput {
+    /// -1.0 (reverse) to 1.0 (full ahead).
+    pub thrust: f32,
+    /// -1.0 (right) to 1.0 (left).
+    pub turn: f32,
+    /// Bite whatever the head is touching. Only acted on once per press.
+    pub bite: bool,
+}
+
+/// Stands in for a creature's own behavior while the player controls it. The simulation skips
+/// the possessed creature's `update_state_and_behavior` and calls `steer` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerBrain {
+    pub creature: CreatureId,
+    input: PlayerInput,
+    // Set by a new bite press, cleared once the bite lands
+    bite_pending: bool,
+}
+
+impl PlayerBrain {
+    pub fn new(creature: CreatureId) -> Self {
+        Self { creature, input: PlayerInput::default(), bite_pending: false }
+    }
+
+    pub fn set_input(&mut self, input: PlayerInput) {
+        if input.bite && !self.input.bite {
+            self.bite_pending = true;
+        }
+        self.input = PlayerInput {
+            thrust: input.thrust.clamp(-1.0, 1.0),
+            turn: input.turn.clamp(-1.0, 1.0),
+            bite: input.bite,
+        };
+    }
+
+    /// Whether a bite is waiting. Clears it.
+    pub fn take_bite(&mut self) -> bool {
+        std::mem::take(&mut self.bite_pending)
+    }
+
+    /// Pulls the creature along by its head according to the current input.
+    pub fn steer(&self, creature: &mut dyn Creature, rigid_body_set: &mut RigidBodySet, dt: f32) {
+        let Some(facing) = facing(creature, rigid_body_set) else { return };
+        // The head drags the rest of the body, so push for all of it
+        let mass: f32 = creature
+            .get_rigid_body_handles()
+            .iter()
+            .filter_map(|&handle| rigid_body_set.get(handle))
+            .map(|body| body.mass())
+            .sum();
+        let Some(&head) = creature.get_rigid_body_handles().first() else { return };
+        let Some(body) = rigid_body_set.get_mut(head) else { return };
+
+        let left = Vector2::new(-facing.y, facing.x);
+        let acceleration = facing * self.input.thrust * THRUST_ACCELERATION + left * self.input.turn * TURN_ACCELERATION;
+        body.add_force(acceleration * mass, true);
+
+        creature.attributes_mut().consume_energy(self.input.thrust.abs() * THRUST_ENERGY_PER_SECOND * dt);
+    }
+}
+
+/// Direction the creature's head points: away from the next segment, or along the head's own
+/// rotation for single-segment creatures.
+pub fn facing(creature: &dyn Creature, rigid_body_set: &RigidBodySet) -> Option<Vector2<f32>> {
+    let handles = creature.get_rigid_body_handles();
+    let head = rigid_body_set.get(*handles.first()?)?;
+    let from_neck = handles
+        .get(1)
+        .and_then(|&neck| rigid_body_set.get(neck))
+        .and_then(|neck| (head.translation() - neck.translation()).try_normalize(1e-6));
+    let angle = head.rotation().angle();
+    Some(from_neck.unwrap_or_else(|| Vector2::new(angle.cos(), angle.sin())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn thrust_moves_the_head_forward() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let snake = sim.spawn_snake(Vector2::new(-2.0, 0.0));
+        assert!(sim.possess(Some(snake)));
+        let head = sim.creatures[0].get_rigid_body_handles()[0];
+        let start = *sim.rigid_body_set[head].translation();
+        let forward = facing(sim.creatures[0].as_ref(), &sim.rigid_body_set).unwrap();
+
+        sim.set_player_input(PlayerInput { thrust: 1.0, ..Default::default() });
+        for _ in 0..60 {
+            sim.tick(TIMESTEP);
+        }
+        let moved = sim.rigid_body_set[head].translation() - start;
+        assert!(moved.dot(&forward) > 0.2, "moved {:?} facing {:?}", moved, forward);
+    }
+
+    #[test]
+    fn biting_hurts_whatever_the_head_touches() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let snake = sim.spawn_snake(Vector2::zeros());
+        let head = *sim.rigid_body_set[sim.creatures[0].get_rigid_body_handles()[0]].translation();
+        let forward = facing(sim.creatures[0].as_ref(), &sim.rigid_body_set).unwrap();
+        let victim = sim.spawn_plankton(head + forward * 0.17);
+        sim.possess(Some(snake));
+        sim.creatures[0].attributes_mut().satiety = 10.0;
+        sim.tick(TIMESTEP); // Let the physics find the contact
+
+        sim.set_player_input(PlayerInput { bite: true, ..Default::default() });
+        let energy_before = sim.creatures[1].attributes().energy;
+        sim.tick(TIMESTEP);
+        sim.tick(TIMESTEP); // Holding the key doesn't bite again
+
+        let victim = sim.creatures.iter().find(|c| c.id() == victim).unwrap();
+        assert!((energy_before - victim.attributes().energy - BITE_DAMAGE).abs() < 1.0);
+        // And leave a mark
+        assert!(victim.attributes().wounds.iter().any(|&wound| wound > BITE_WOUND - 0.01));
+        assert!(sim.creatures[0].attributes().satiety > 10.0 + BITE_SATIETY - 1.0);
+        // Bites are loud
+        let noises = sim.sounds().signals().iter().filter(|signal| signal.kind == crate::signals::SignalKind::Noise).count();
+        assert_eq!(noises, 1);
+    }
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+thread_local! {
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Makes `rng()` on this thread draw from a generator seeded with `seed`, so the same calls
+/// give the same numbers on every run and platform.
+pub fn seed(seed: u64) {
+    SEEDED.with(|seeded| *seeded.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+}
+
+/// The simulation's source of randomness: a drop-in for `rand::thread_rng()`, which it
+/// falls back to until `seed` is called on this thread.
+pub fn rng() -> SimRng {
+    SimRng
+}
+
+/// Handle returned by `rng()`. Holds nothing itself; each draw goes to the thread's generator.
+#[derive(Debug, Clone, Copy)]
+pub struct SimRng;
+
+impl SimRng {
+    fn with<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+        SEEDED.with(|seeded| match seeded.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut rand::thread_rng()),
+        })
+    }
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        Self::with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Self::with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Self::with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        Self::with(|rng| rng.try_fill_bytes(dest))
+    }
+}
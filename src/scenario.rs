@@ -0,0 +1,352 @@
+use nalgebra::Vector2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::behavior::Behavior;
+use crate::cannibalism::Cannibalism;
+use crate::personality::PersonalitySpread;
+use crate::random;
+use crate::physics_presets::PhysicsPreset;
+use crate::creature::{Creature, CreatureId};
+use crate::creatures::CreatureData;
+use crate::formation::Formation;
+use crate::schedule::ScheduledEvent;
+use crate::silhouette::Silhouette;
+use crate::simulation::{standard_plankton, standard_snake, Simulation, WorldSnapshot, WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+/// File extension for saved scenarios.
+pub const FILE_EXTENSION: &str = "softies";
+/// Smallest demo tank, in meters. Demo tanks grow to fit longer creatures.
+const DEMO_TANK_SIZE: Vector2<f32> = Vector2::new(6.0, 4.0);
+
+/// A creature and where to put it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Placement {
+    /// The whole creature: tuned parameters, attributes and internal state. Its physics
+    /// handles are stale and get replaced when it is spawned.
+    pub creature: CreatureData,
+    pub position: Vector2<f32>,
+}
+
+/// Creatures stocked by density rather than count: copies of one creature, at random spots
+/// in open water, so a bigger tank gets proportionally more of them.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Population {
+    /// What each copy starts as. Its physics handles are stale, as in a `Placement`.
+    pub creature: CreatureData,
+    pub per_square_meter: f32,
+    /// Replaces the template's behavior constants, so two groups of one species can act
+    /// differently. Ignored if it's for another species.
+    pub behavior: Option<Behavior>,
+    /// Stocks them as one group in this formation, at a random spot, instead of scattered.
+    pub formation: Option<Formation>,
+}
+
+impl Population {
+    /// How many to stock in `open_area` square meters of water.
+    pub fn count(&self, open_area: f32) -> usize {
+        (self.per_square_meter * open_area).round() as usize
+    }
+
+    /// The standard tank's snakes and plankton, as densities.
+    pub fn standard() -> Vec<Population> {
+        let area = WORLD_WIDTH_METERS * WORLD_HEIGHT_METERS;
+        vec![
+            Population { creature: CreatureData::Snake(standard_snake()), per_square_meter: 3.0 / area, behavior: None, formation: None },
+            Population { creature: CreatureData::Plankton(standard_plankton()), per_square_meter: 20.0 / area, behavior: None, formation: None },
+        ]
+    }
+}
+
+/// A tank and what goes in it: everything needed to build a `Simulation` from scratch.
+/// Saved as bincode, like the simulation itself.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub world_size: Vector2<f32>,
+    /// Solid scenery, stretched over the whole tank.
+    pub terrain: Option<Silhouette>,
+    pub placements: Vec<Placement>,
+    /// Stocked after the placements, scaled to the tank's open water.
+    pub populations: Vec<Population>,
+    /// Spawns partway through the run, e.g. a predator let in on cue.
+    pub schedule: Vec<ScheduledEvent>,
+    /// Makes every run of the scenario play out the same. See `Simulation::set_seed`.
+    pub seed: Option<u64>,
+    /// Species whose starving members eat their own kind. None do unless listed.
+    pub cannibalism: Vec<Cannibalism>,
+    /// What its creatures' personalities are drawn from. `None` for none: every creature is
+    /// its species as written.
+    pub personalities: Option<PersonalitySpread>,
+    /// Rapier tuning to run with. `None` for the default, `Balanced`.
+    pub physics_preset: Option<PhysicsPreset>,
+    /// How readily disease spreads. `None` for `disease::DEFAULT_VIRULENCE`.
+    pub virulence: Option<f32>,
+    /// How many creatures start out sick, picked at random once the tank is stocked.
+    pub infected: usize,
+}
+
+impl Scenario {
+    /// Builds the tank and spawns everything in it.
+    pub fn build(&self) -> Simulation {
+        let mut sim = Simulation::new(self.world_size.x, self.world_size.y);
+        if let Some(preset) = self.physics_preset {
+            sim.set_physics_preset(preset);
+        }
+        if let Some(seed) = self.seed {
+            sim.set_seed(seed);
+        }
+        if let Some(terrain) = &self.terrain {
+            sim.set_terrain(terrain.clone());
+        }
+        if let Some(virulence) = self.virulence {
+            sim.set_virulence(virulence);
+        }
+        if let Some(personalities) = self.personalities {
+            sim.set_personalities(personalities);
+        }
+        for rule in &self.cannibalism {
+            sim.set_cannibalism(rule.clone());
+        }
+        for placement in &self.placements {
+            sim.spawn_creature(placement.creature.clone(), placement.position);
+        }
+        let open_area = sim.open_area();
+        for population in &self.populations {
+            let mut creature = population.creature.clone().into_creature();
+            if let Some(behavior) = population.behavior {
+                creature.set_behavior(behavior);
+            }
+            let creature = creature.to_data();
+            let count = population.count(open_area);
+            if let Some(formation) = population.formation {
+                let center = sim.random_position(2.0);
+                sim.spawn_group(creature, count, center, formation);
+                continue;
+            }
+            for _ in 0..count {
+                if sim.spawn_in_open_water(creature.clone()).is_none() {
+                    break; // Too crowded
+                }
+            }
+        }
+        let mut healthy: Vec<CreatureId> = sim.creatures.iter().map(|creature| creature.id()).collect();
+        for _ in 0..self.infected.min(healthy.len()) {
+            let index = random::rng().gen_range(0..healthy.len());
+            sim.infect(healthy.swap_remove(index));
+        }
+        for event in &self.schedule {
+            sim.schedule_event(event.clone());
+        }
+        sim.origin = Some(self.clone());
+        sim
+    }
+
+    /// An open tank of any size, stocked as densely as the standard one.
+    pub fn standard(name: &str, world_size: Vector2<f32>) -> Scenario {
+        Scenario { name: name.to_string(), world_size, terrain: None, placements: Vec::new(), populations: Population::standard(), schedule: Vec::new(), seed: None, cannibalism: Vec::new(), personalities: None, physics_preset: None, virulence: None, infected: 0 }
+    }
+
+    /// A standard-sized tank laid out like `terrain`, stocked like the standard tank (three
+    /// snakes and a swarm of plankton) wherever there's open water.
+    pub fn from_silhouette(name: &str, terrain: Silhouette) -> Scenario {
+        let world_size = Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS);
+        let mut sim = Simulation::new(world_size.x, world_size.y);
+        sim.set_terrain(terrain.clone());
+        sim.set_snake_count(3);
+        for _ in 0..20 {
+            let position = sim.random_position(1.0);
+            sim.spawn_plankton(position);
+        }
+        let snapshot = sim.snapshot();
+        let placements = snapshot
+            .creatures
+            .iter()
+            .filter_map(|creature| {
+                let head = snapshot.rigid_body_set.get(*creature.get_rigid_body_handles().first()?)?;
+                Some(Placement { creature: creature.to_data(), position: *head.translation() })
+            })
+            .collect();
+        Scenario { name: name.to_string(), world_size, terrain: Some(terrain), placements, populations: Vec::new(), schedule: Vec::new(), seed: None, cannibalism: Vec::new(), personalities: None, physics_preset: None, virulence: None, infected: 0 }
+    }
+
+    /// A small tank holding only a copy of one creature from `snapshot`, as it is now and
+    /// with the same physics preset, for sharing it as a minimal setup. `None` if there's no such creature.
+    pub fn demo_of(snapshot: &WorldSnapshot, id: CreatureId) -> Option<Scenario> {
+        let creature = snapshot.creatures.iter().find(|creature| creature.id() == id)?;
+        let length = body_length(creature.as_ref(), snapshot);
+        let world_size = DEMO_TANK_SIZE.sup(&Vector2::repeat(length * 2.0 + 2.0));
+        // Segmented creatures extend to the right of their spawn point, so center the body
+        let position = Vector2::new(-length / 2.0, 0.0);
+        Some(Scenario {
+            name: format!("{} {}", creature.type_name(), id),
+            world_size,
+            terrain: None,
+            placements: vec![Placement { creature: creature.to_data(), position }],
+            populations: Vec::new(),
+            schedule: Vec::new(),
+            seed: None,
+            cannibalism: Vec::new(),
+            personalities: None,
+            physics_preset: Some(snapshot.physics_preset),
+            virulence: None,
+            infected: 0,
+        })
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// A file name for this scenario: its name, lowercased with dashes.
+    pub fn file_name(&self) -> String {
+        let stem: String = self.name.to_lowercase().chars().map(|c| if c.is_alphanumeric() { c } else { '-' }).collect();
+        format!("{}.{}", stem, FILE_EXTENSION)
+    }
+}
+
+/// Distance from the creature's first segment to its furthest one.
+fn body_length(creature: &dyn Creature, snapshot: &WorldSnapshot) -> f32 {
+    let positions: Vec<Vector2<f32>> = creature
+        .get_rigid_body_handles()
+        .iter()
+        .filter_map(|&handle| snapshot.rigid_body_set.get(handle))
+        .map(|body| *body.translation())
+        .collect();
+    let Some(first) = positions.first() else { return 0.0 };
+    positions.iter().map(|position| (position - first).norm()).fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creature::CreatureState;
+    use crate::simulation::TIMESTEP;
+
+    #[test]
+    fn demo_keeps_one_tuned_creature() {
+        let mut sim = Simulation::default();
+        sim.set_physics_preset(PhysicsPreset::Accurate);
+        let snake = sim.creatures.iter().find(|c| c.type_name() == "Snake").unwrap().id();
+        let tuned = sim.creatures.iter_mut().find(|c| c.id() == snake).unwrap();
+        tuned.attributes_mut().max_energy = 321.0;
+        let segments = tuned.get_rigid_body_handles().len();
+
+        let demo = Scenario::demo_of(&sim.snapshot(), snake).unwrap();
+        assert_eq!(demo.file_name(), format!("snake-{}.softies", snake));
+        let mut built = Scenario::decode(&demo.encode().unwrap()).unwrap().build();
+
+        assert_eq!(built.creatures.len(), 1);
+        assert_eq!(built.physics_preset(), PhysicsPreset::Accurate);
+        let copy = &built.creatures[0];
+        assert_eq!(copy.type_name(), "Snake");
+        assert_eq!(copy.attributes().max_energy, 321.0);
+        assert_eq!(copy.get_rigid_body_handles().len(), segments);
+        // The whole body fits in the small tank
+        let half = built.world_size() / 2.0;
+        for &handle in copy.get_rigid_body_handles() {
+            let position = built.rigid_body_set[handle].translation();
+            assert!(position.x.abs() < half.x && position.y.abs() < half.y);
+        }
+        built.tick(TIMESTEP);
+        assert!(Scenario::demo_of(&sim.snapshot(), CreatureId(999)).is_none());
+    }
+
+    #[test]
+    fn silhouette_layout_keeps_creatures_in_open_water() {
+        // A solid bottom half
+        let terrain = Silhouette::from_rows(&["....", "....", "####", "####"]);
+        let scenario = Scenario::from_silhouette("Shelf", terrain);
+        assert_eq!(scenario.placements.len(), 23);
+        assert!(scenario.placements.iter().all(|placement| placement.position.y > 0.0));
+
+        let mut built = Scenario::decode(&scenario.encode().unwrap()).unwrap().build();
+        let snapshot = built.snapshot();
+        let outlines = snapshot.collider_set.iter().filter(|(_, collider)| collider.shape().as_polyline().is_some()).count();
+        assert_eq!(outlines, 1);
+        for _ in 0..60 {
+            built.tick(TIMESTEP);
+        }
+        // Nothing fell through the rock
+        for creature in &built.creatures {
+            for &handle in creature.get_rigid_body_handles() {
+                assert!(built.rigid_body_set[handle].translation().y > -0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn populations_scale_with_open_water() {
+        let count = |sim: &Simulation, name: &str| sim.creatures.iter().filter(|c| c.type_name() == name).count();
+        let standard = Scenario::standard("Standard", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS)).build();
+        assert_eq!((count(&standard, "Snake"), count(&standard, "Plankton")), (3, 20));
+
+        let big = Scenario::standard("Big", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS) * 2.0).build();
+        assert_eq!((count(&big, "Snake"), count(&big, "Plankton")), (12, 80));
+
+        // Half the tank is rock, so half as many
+        let mut shelf = Scenario::standard("Shelf", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS) * 2.0);
+        shelf.terrain = Some(Silhouette::from_rows(&["....", "....", "####", "####"]));
+        let shelf = Scenario::decode(&shelf.encode().unwrap()).unwrap().build();
+        assert_eq!(count(&shelf, "Plankton"), 40);
+        assert!(shelf.creatures.iter().all(|creature| creature.get_rigid_body_handles().iter().all(|&handle| shelf.rigid_body_set[handle].translation().y > 0.0)));
+
+        // A school starts bunched up
+        let mut schooled = Scenario::standard("School", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS));
+        schooled.populations.retain(|population| matches!(population.creature, CreatureData::Plankton(_)));
+        schooled.populations[0].formation = Some(Formation::School { spacing: 0.5, jitter: 0.2 });
+        let schooled = schooled.build();
+        let heads: Vec<Vector2<f32>> = schooled.creatures.iter().map(|c| *schooled.rigid_body_set[c.get_rigid_body_handles()[0]].translation()).collect();
+        let middle = heads.iter().sum::<Vector2<f32>>() / heads.len() as f32;
+        assert!(heads.len() > 15 && heads.iter().all(|head| (head - middle).norm() < 2.5), "{:?}", heads);
+    }
+
+    #[test]
+    fn groups_of_one_species_keep_their_own_behavior() {
+        use crate::behavior::{BoidWeights, SnakeBehavior};
+
+        let tight = BoidWeights { cohesion: 0.6, separation: 0.05, ..BoidWeights::default() };
+        let loose = BoidWeights { cohesion: 0.0, separation: 0.6, ..BoidWeights::default() };
+        let hunters = SnakeBehavior { aggression: 1.0 };
+        let group = |creature, behavior| Population { creature, per_square_meter: 2.0 / 320.0, behavior: Some(behavior), formation: None };
+        let mut scenario = Scenario::standard("Groups", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS));
+        scenario.populations = vec![
+            group(CreatureData::Plankton(standard_plankton()), Behavior::Plankton(tight)),
+            group(CreatureData::Plankton(standard_plankton()), Behavior::Plankton(loose)),
+            group(CreatureData::Snake(standard_snake()), Behavior::Snake(hunters)),
+            // For the wrong species, so it's ignored
+            group(CreatureData::Plankton(standard_plankton()), Behavior::Snake(hunters)),
+        ];
+        let mut sim = Scenario::decode(&scenario.encode().unwrap()).unwrap().build();
+
+        let behaviors: Vec<Option<Behavior>> = sim.creatures.iter().map(|creature| creature.behavior()).collect();
+        let plankton = |weights| Some(Behavior::Plankton(weights));
+        assert_eq!(
+            behaviors,
+            vec![
+                plankton(tight),
+                plankton(tight),
+                plankton(loose),
+                plankton(loose),
+                Some(Behavior::Snake(hunters)),
+                Some(Behavior::Snake(hunters)),
+                plankton(BoidWeights::default()),
+                plankton(BoidWeights::default()),
+            ]
+        );
+
+        // A hungry hunter goes looking for food; the standard snake doesn't
+        let default_snake = sim.spawn_in_open_water(CreatureData::Snake(standard_snake())).unwrap();
+        for creature in sim.creatures.iter_mut().filter(|creature| creature.type_name() == "Snake") {
+            creature.attributes_mut().satiety = 0.0;
+        }
+        sim.tick(TIMESTEP);
+        let state = |id| sim.creatures.iter().find(|creature| creature.id() == id).unwrap().current_state();
+        assert_eq!(state(sim.creatures[4].id()), CreatureState::SeekingFood);
+        assert_eq!(state(default_snake), CreatureState::Wandering);
+    }
+}
@@ -0,0 +1,46 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::behavior::Behavior;
+use crate::creatures::CreatureData;
+use crate::formation::Formation;
+
+/// Something a scripted run does partway through.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ScheduledAction {
+    /// Release `count` copies of `creature` in `formation` around `position`, with
+    /// `satiety` as a fraction of their maximum (below half they're hungry).
+    Release { creature: CreatureData, behavior: Option<Behavior>, count: usize, position: Vector2<f32>, formation: Formation, satiety: f32 },
+}
+
+/// An action and when it happens, in simulated seconds since the start of the run.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub at: f32,
+    pub action: ScheduledAction,
+}
+
+/// Events still to come, soonest first. Timed by tick count rather than the wall clock,
+/// so a seeded run plays them out identically every time.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Schedule {
+    events: Vec<ScheduledEvent>,
+}
+
+impl Schedule {
+    /// Adds `event` after any others at the same time.
+    pub fn add(&mut self, event: ScheduledEvent) {
+        let index = self.events.partition_point(|other| other.at <= event.at);
+        self.events.insert(index, event);
+    }
+
+    pub fn events(&self) -> &[ScheduledEvent] {
+        &self.events
+    }
+
+    /// Takes out the events due by `seconds`, in order.
+    pub fn due(&mut self, seconds: f32) -> Vec<ScheduledEvent> {
+        let count = self.events.partition_point(|event| event.at <= seconds);
+        self.events.drain(..count).collect()
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use crate::invariants::{self, JOINT_ANCHOR_TOLERANCE};
+use crate::simulation::{Simulation, TIMESTEP};
+
+/// How many times over the world's kinetic energy may grow in one tick. Creatures swim, so it
+/// isn't conserved, but only a solver blowing up multiplies it.
+const MAX_ENERGY_GROWTH: f32 = 4.0;
+/// Joules per dynamic body allowed on top, so a tank at rest can start moving.
+const ENERGY_SLACK_PER_BODY: f32 = 0.05;
+/// Offenders named in a failed check's detail. The rest are counted.
+const MAX_NAMED: usize = 3;
+
+/// One check in a self-test, and how it went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub passed: bool,
+    /// What was measured, or what was wrong.
+    pub detail: String,
+}
+
+/// What a self-test found on the live world, for the UI to show and a user to paste into a
+/// bug report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub tick: u64,
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// e.g. "Tick 600: 3 of 4 checks passed".
+    pub fn summary(&self) -> String {
+        let passed = self.checks.iter().filter(|check| check.passed).count();
+        format!("Tick {}: {} of {} checks passed", self.tick, passed, self.checks.len())
+    }
+
+    /// The summary and every check, one per line, as plain text.
+    pub fn to_text(&self) -> String {
+        let mut text = self.summary();
+        for check in &self.checks {
+            text += &format!("\n[{}] {}: {}", if check.passed { "PASS" } else { "FAIL" }, check.name, check.detail);
+        }
+        text
+    }
+}
+
+/// Runs every check on `sim` as it stands. Leaves it untouched: the energy check steps a copy.
+pub fn run(sim: &Simulation) -> SelfTestReport {
+    SelfTestReport { tick: sim.tick_count(), checks: vec![energy(sim), joints(sim), bodies(sim), sensing(sim)] }
+}
+
+fn result(name: &str, problems: Vec<String>, fine: String) -> CheckResult {
+    let detail = match problems.len() {
+        0 => fine,
+        count if count > MAX_NAMED => format!("{}; and {} more", problems[..MAX_NAMED].join("; "), count - MAX_NAMED),
+        _ => problems.join("; "),
+    };
+    CheckResult { name: name.to_string(), passed: problems.is_empty(), detail }
+}
+
+fn kinetic_energy(sim: &Simulation) -> f32 {
+    sim.rigid_body_set.iter().filter(|(_, body)| body.is_dynamic()).map(|(_, body)| body.kinetic_energy()).sum()
+}
+
+/// Creatures' energy and satiety are within their limits, and a tick on a copy of the world
+/// doesn't multiply its kinetic energy. The copy is only stepped if the world is consistent
+/// (see `invariants`), as ticking a broken one can't be trusted and panics in debug builds.
+fn energy(sim: &Simulation) -> CheckResult {
+    let mut problems = Vec::new();
+    for creature in &sim.creatures {
+        let attributes = creature.attributes();
+        if !(0.0..=attributes.max_energy).contains(&attributes.energy) || !(0.0..=attributes.max_satiety).contains(&attributes.satiety) {
+            problems.push(format!(
+                "{} {} has energy {:.1}/{:.1} and satiety {:.1}/{:.1}",
+                creature.type_name(), creature.id(), attributes.energy, attributes.max_energy, attributes.satiety, attributes.max_satiety
+            ));
+        }
+    }
+    let before = kinetic_energy(sim);
+    if !invariants::check(sim).is_empty() {
+        return result("Energy", problems, format!("kinetic energy {:.3} J; not stepped, as the world is inconsistent", before));
+    }
+    let copy = sim.encode().ok().and_then(|bytes| Simulation::decode(&bytes).ok());
+    let Some(mut copy) = copy else {
+        problems.push("couldn't copy the world to step it".to_string());
+        return result("Energy", problems, String::new());
+    };
+    copy.tick(TIMESTEP);
+    let after = kinetic_energy(&copy);
+    let bodies = sim.rigid_body_set.iter().filter(|(_, body)| body.is_dynamic()).count();
+    if !after.is_finite() || after > before * MAX_ENERGY_GROWTH + ENERGY_SLACK_PER_BODY * bodies as f32 {
+        problems.push(format!("kinetic energy went from {:.3} J to {:.3} J in one tick", before, after));
+    }
+    result("Energy", problems, format!("kinetic energy {:.3} J, then {:.3} J a tick later", before, after))
+}
+
+/// How far apart each joint's two anchors have drifted, as a distribution. Fails if any
+/// joint has come apart (see `invariants::JOINT_ANCHOR_TOLERANCE`).
+fn joints(sim: &Simulation) -> CheckResult {
+    let mut errors: Vec<f32> = Vec::new();
+    let mut problems = Vec::new();
+    for (handle, joint) in sim.impulse_joint_set.iter() {
+        let (Some(body1), Some(body2)) = (sim.rigid_body_set.get(joint.body1), sim.rigid_body_set.get(joint.body2)) else {
+            problems.push(format!("joint {:?} connects a missing body", handle));
+            continue;
+        };
+        let error = nalgebra::distance(&(body1.position() * joint.data.local_anchor1()), &(body2.position() * joint.data.local_anchor2()));
+        if error.is_nan() || error > JOINT_ANCHOR_TOLERANCE {
+            problems.push(format!("joint {:?} anchors are {:.3} m apart", handle, error));
+        }
+        errors.push(error);
+    }
+    if errors.is_empty() {
+        return result("Joints", problems, "no joints".to_string());
+    }
+    errors.sort_by(f32::total_cmp);
+    let at = |fraction: f32| errors[((errors.len() - 1) as f32 * fraction).round() as usize] * 1000.0;
+    result(
+        "Joints",
+        problems,
+        format!("{} joints, anchor error median {:.1} mm, 95th percentile {:.1} mm, worst {:.1} mm", errors.len(), at(0.5), at(0.95), at(1.0)),
+    )
+}
+
+/// Every creature has bodies, each of them live and with its colliders registered as that
+/// creature's segment at the same index.
+fn bodies(sim: &Simulation) -> CheckResult {
+    let mut problems = Vec::new();
+    let mut total = 0;
+    for creature in &sim.creatures {
+        let name = format!("{} {}", creature.type_name(), creature.id());
+        let handles = creature.get_rigid_body_handles();
+        if handles.is_empty() {
+            problems.push(format!("{} has no bodies", name));
+        }
+        total += handles.len();
+        for (segment, &handle) in handles.iter().enumerate() {
+            let Some(body) = sim.rigid_body_set.get(handle) else {
+                problems.push(format!("{}'s body {} doesn't exist", name, segment));
+                continue;
+            };
+            let registered = body
+                .colliders()
+                .iter()
+                .all(|&collider| sim.body_parts.get(collider).is_some_and(|part| part.creature == creature.id() && part.segment as usize == segment));
+            if body.colliders().is_empty() || !registered {
+                problems.push(format!("{}'s body {} isn't registered as its segment {}", name, segment, segment));
+            }
+        }
+    }
+    result("Bodies", problems, format!("{} creatures with {} bodies between them", sim.creatures.len(), total))
+}
+
+/// A point query at the middle of each creature segment, the way sensing looks things up,
+/// finds that segment.
+fn sensing(sim: &Simulation) -> CheckResult {
+    let mut problems = Vec::new();
+    let mut probes = 0;
+    for creature in &sim.creatures {
+        for (segment, body) in creature.get_rigid_body_handles().iter().enumerate().filter_map(|(i, &handle)| Some((i, sim.rigid_body_set.get(handle)?))) {
+            probes += 1;
+            let found = sim.body_parts_at(*body.translation());
+            if !found.iter().any(|part| part.creature == creature.id() && part.segment as usize == segment) {
+                problems.push(format!("{} {}'s segment {} isn't found where it is", creature.type_name(), creature.id(), segment));
+            }
+        }
+    }
+    result("Sensing", problems, format!("found all {} segments where they are", probes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_healthy_tank_passes_and_a_broken_one_says_why() {
+        let mut sim = Simulation::default();
+        for _ in 0..60 {
+            sim.tick(TIMESTEP);
+        }
+        let report = run(&sim);
+        assert!(report.passed(), "{}", report.to_text());
+        assert_eq!(report.summary(), "Tick 60: 4 of 4 checks passed");
+
+        // Pull a snake's tail off, and drop it from the body part registry
+        let snake = sim.creatures.iter().find(|creature| creature.type_name() == "Snake").unwrap();
+        let (id, tail) = (snake.id(), *snake.get_rigid_body_handles().last().unwrap());
+        sim.rigid_body_set[tail].set_translation(nalgebra::vector![0.0, 5.0], false);
+        sim.body_parts.unregister(id);
+
+        let report = run(&sim);
+        let failed: Vec<&str> = report.checks.iter().filter(|check| !check.passed).map(|check| check.name.as_str()).collect();
+        assert_eq!(failed, ["Joints", "Bodies", "Sensing"], "{}", report.to_text());
+        assert!(report.to_text().contains("[FAIL] Bodies: Snake"));
+    }
+}
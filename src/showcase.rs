@@ -0,0 +1,146 @@
+use nalgebra::Vector2;
+
+use crate::behavior::{Behavior, SnakeBehavior};
+use crate::creatures::CreatureData;
+use crate::formation::Formation;
+use crate::random;
+use crate::scenario::Scenario;
+use crate::schedule::{ScheduledAction, ScheduledEvent};
+use crate::simulation::{standard_plankton, standard_snake, WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+/// Seed of the showcase run. Changing it changes every recording made from it.
+pub const SEED: u64 = 0x050F_71E5;
+/// When, in simulated seconds, a hungry snake is let in among the plankton.
+pub const CHASE_AT: f32 = 30.0;
+const CHASE_START: Vector2<f32> = Vector2::new(-7.0, 3.0);
+/// When a cloud of plankton appears.
+pub const BLOOM_AT: f32 = 60.0;
+const BLOOM_CENTER: Vector2<f32> = Vector2::new(3.0, -2.0);
+
+/// Where the camera looks and how far it's zoomed in, at a moment of the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraKey {
+    /// Simulated seconds since the start of the run.
+    pub at: f32,
+    pub center: Vector2<f32>,
+    pub zoom: f32,
+}
+
+/// Camera moves timed to the simulation rather than the wall clock, so a recording frames
+/// each tick the same way every time. Eases from key to key and holds on the last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraPath {
+    keys: Vec<CameraKey>,
+}
+
+impl CameraPath {
+    pub fn new(mut keys: Vec<CameraKey>) -> Self {
+        keys.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self { keys }
+    }
+
+    /// The view center and zoom at `seconds`. None for an empty path.
+    pub fn at(&self, seconds: f32) -> Option<(Vector2<f32>, f32)> {
+        let next = self.keys.partition_point(|key| key.at <= seconds);
+        let (from, to) = match (next.checked_sub(1).and_then(|i| self.keys.get(i)), self.keys.get(next)) {
+            (Some(from), Some(to)) => (from, to),
+            (Some(key), None) | (None, Some(key)) => return Some((key.center, key.zoom)),
+            (None, None) => return None,
+        };
+        let t = ((seconds - from.at) / (to.at - from.at)).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        // Zoom eases geometrically, so zooming in and out look equally smooth
+        Some((from.center.lerp(&to.center, eased), from.zoom * (to.zoom / from.zoom).powf(eased)))
+    }
+
+    /// Seconds at which the path stops moving.
+    pub fn end(&self) -> f32 {
+        self.keys.last().map_or(0.0, |key| key.at)
+    }
+}
+
+/// The curated tank behind `--demo`: a standard tank, seeded, with a chase at `CHASE_AT`
+/// and a plankton bloom at `BLOOM_AT`.
+pub fn scenario() -> Scenario {
+    // The creature templates roll their own timers as they're made
+    random::seed(SEED);
+    let mut scenario = Scenario::standard("Showcase", Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS));
+    scenario.seed = Some(SEED);
+    scenario.schedule = vec![
+        ScheduledEvent {
+            at: CHASE_AT,
+            action: ScheduledAction::Release {
+                creature: CreatureData::Snake(standard_snake()),
+                behavior: Some(Behavior::Snake(SnakeBehavior { aggression: 1.5 })),
+                count: 1,
+                position: CHASE_START,
+                formation: Formation::Line { spacing: 2.0, angle: 0.0 },
+                satiety: 0.2,
+            },
+        },
+        ScheduledEvent {
+            at: BLOOM_AT,
+            action: ScheduledAction::Release {
+                creature: CreatureData::Plankton(standard_plankton()),
+                behavior: None,
+                count: 40,
+                position: BLOOM_CENTER,
+                formation: Formation::School { spacing: 0.5, jitter: 0.2 },
+                satiety: 1.0,
+            },
+        },
+    ];
+    scenario
+}
+
+/// The camera moves that go with `scenario`: the whole tank, in on the chase, back out,
+/// in on the bloom, and out again.
+pub fn camera_path() -> CameraPath {
+    let wide = |at| CameraKey { at, center: Vector2::zeros(), zoom: 1.0 };
+    CameraPath::new(vec![
+        wide(0.0),
+        wide(CHASE_AT - 5.0),
+        CameraKey { at: CHASE_AT, center: CHASE_START, zoom: 1.8 },
+        CameraKey { at: CHASE_AT + 15.0, center: CHASE_START + Vector2::new(4.0, -1.0), zoom: 1.5 },
+        wide(BLOOM_AT - 6.0),
+        wide(BLOOM_AT - 2.0),
+        CameraKey { at: BLOOM_AT + 2.0, center: BLOOM_CENTER, zoom: 2.0 },
+        CameraKey { at: BLOOM_AT + 15.0, center: BLOOM_CENTER, zoom: 1.6 },
+        wide(BLOOM_AT + 25.0),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::TIMESTEP;
+
+    #[test]
+    fn seeded_runs_repeat_exactly() {
+        // The real schedule, brought forward so the test doesn't run for a minute
+        let mut scenario = scenario();
+        for (event, at) in scenario.schedule.iter_mut().zip([0.2, 0.4]) {
+            event.at = at;
+        }
+        let run = || {
+            let mut sim = scenario.build();
+            for _ in 0..40 {
+                sim.tick(TIMESTEP);
+            }
+            let snapshot = sim.snapshot();
+            let positions: Vec<Vector2<f32>> = snapshot.rigid_body_set.iter().map(|(_, body)| *body.translation()).collect();
+            (snapshot.creatures.len(), sim.schedule().events().len(), positions)
+        };
+        let (count, pending, positions) = run();
+        assert_eq!(pending, 0);
+        assert_eq!((count, pending, positions.clone()), run());
+        let stocked = scenario.build().snapshot().creatures.len();
+        // The snake and most of the bloom: spots too close to the swarm are skipped
+        assert!(count > stocked + 30, "{} after {}", count, stocked);
+
+        let path = camera_path();
+        assert_eq!(path.at(0.0), Some((Vector2::zeros(), 1.0)));
+        assert_eq!(path.at(CHASE_AT), Some((CHASE_START, 1.8)));
+        assert_eq!(path.at(path.end() + 10.0), Some((Vector2::zeros(), 1.0)));
+    }
+}
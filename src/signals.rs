@@ -0,0 +1,114 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::creature::CreatureId;
+
+/// How fast sound spreads through the tank, in m/s. Far slower than real water so the
+/// wavefronts are visible.
+pub const SPEED: f32 = 4.0;
+
+/// What made a sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalKind {
+    /// Deliberate communication, e.g. announcing a clutch of eggs.
+    Call,
+    /// A side effect of something else happening, e.g. a bite.
+    Noise,
+}
+
+/// A sound spreading out from where it was made, as a ring growing at `SPEED`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signal {
+    pub kind: SignalKind,
+    /// Who made it. `None` for sounds with no single source.
+    pub emitter: Option<CreatureId>,
+    pub origin: Vector2<f32>,
+    /// How far it carries before fading out, in meters.
+    pub range: f32,
+    /// Seconds since it was made.
+    pub age: f32,
+}
+
+impl Signal {
+    pub fn new(kind: SignalKind, emitter: Option<CreatureId>, origin: Vector2<f32>, range: f32) -> Self {
+        Self { kind, emitter, origin, range, age: 0.0 }
+    }
+
+    /// Distance the wavefront has reached.
+    pub fn radius(&self) -> f32 {
+        self.age * SPEED
+    }
+
+    /// How loud the wavefront still is, from 1.0 when made to 0.0 at the edge of its range.
+    pub fn strength(&self) -> f32 {
+        (1.0 - self.radius() / self.range).clamp(0.0, 1.0)
+    }
+
+    pub fn has_faded(&self) -> bool {
+        self.radius() >= self.range
+    }
+
+    /// Whether the wavefront passed `point` during the last `dt` seconds, i.e. whether a
+    /// listener there heard it this tick.
+    pub fn reaches(&self, point: Vector2<f32>, dt: f32) -> bool {
+        let distance = (point - self.origin).norm();
+        distance <= self.range && distance <= self.radius() && distance > self.radius() - SPEED * dt
+    }
+}
+
+/// Every sound currently spreading through the tank.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Soundscape {
+    signals: Vec<Signal>,
+}
+
+impl Soundscape {
+    pub fn signals(&self) -> &[Signal] {
+        &self.signals
+    }
+
+    pub fn emit(&mut self, signal: Signal) {
+        self.signals.push(signal);
+    }
+
+    /// Spreads every sound by `dt` seconds and drops the ones that have faded.
+    pub fn update(&mut self, dt: f32) {
+        for signal in &mut self.signals {
+            signal.age += dt;
+        }
+        self.signals.retain(|signal| !signal.has_faded());
+    }
+
+    /// Sounds a listener at `position` heard during the last `dt` seconds, other than its own.
+    pub fn heard_at(&self, position: Vector2<f32>, listener: Option<CreatureId>, dt: f32) -> impl Iterator<Item = &Signal> {
+        self.signals
+            .iter()
+            .filter(move |signal| (listener.is_none() || signal.emitter != listener) && signal.reaches(position, dt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_spreads_and_fades() {
+        let mut soundscape = Soundscape::default();
+        soundscape.emit(Signal::new(SignalKind::Call, Some(CreatureId(1)), Vector2::zeros(), 2.0));
+        let listener = Vector2::new(1.0, 0.0);
+        let dt = 0.1;
+
+        let mut heard_after = None;
+        for step in 1..10 {
+            soundscape.update(dt);
+            if soundscape.heard_at(listener, Some(CreatureId(2)), dt).count() > 0 {
+                assert!(heard_after.is_none(), "heard twice");
+                heard_after = Some(step as f32 * dt);
+            }
+            // The caller doesn't hear itself
+            assert_eq!(soundscape.heard_at(listener, Some(CreatureId(1)), dt).count(), 0);
+        }
+        assert!((heard_after.unwrap() - 1.0 / SPEED).abs() <= dt);
+        assert!(soundscape.signals().is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// Most cells across a silhouette. Bigger images are sampled down to this, which is plenty
+/// for hand-drawn layouts and keeps the outline to a few thousand segments.
+const MAX_COLUMNS: u32 = 200;
+/// Pixels darker than this (out of 255) are solid.
+const DARK_THRESHOLD: u8 = 128;
+/// Pixels more transparent than this are open water, whatever their color.
+const OPAQUE_THRESHOLD: u8 = 128;
+
+/// A point halfway between two neighbouring cell centers, in doubled grid coordinates.
+type EdgePoint = (i64, i64);
+
+/// A layout of solid rock and open water, as a grid stretched over the whole tank. Usually
+/// drawn in a paint program: black is solid, white (or transparent) is water.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Silhouette {
+    columns: usize,
+    rows: usize,
+    /// Row-major from the top left, like the image.
+    solid: Vec<bool>,
+}
+
+impl Silhouette {
+    /// A grid from rows of text, top row first: `#` is solid, anything else is water.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let columns = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let mut solid = vec![false; columns * rows.len()];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                solid[y * columns + x] = c == '#';
+            }
+        }
+        Self { columns, rows: rows.len(), solid }
+    }
+
+    /// Decodes an image (PNG) and thresholds it, sampling big images down to `MAX_COLUMNS` across.
+    pub fn from_image(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.into_luma_alpha8();
+        let (width, height) = image.dimensions();
+        let scale = (width as f32 / MAX_COLUMNS as f32).max(1.0);
+        let columns = ((width as f32 / scale) as usize).max(1);
+        let rows = ((height as f32 / scale) as usize).max(1);
+        let mut solid = Vec::with_capacity(columns * rows);
+        for y in 0..rows {
+            for x in 0..columns {
+                // Sample the middle of the block of pixels this cell covers
+                let px = (((x as f32 + 0.5) * scale) as u32).min(width - 1);
+                let py = (((y as f32 + 0.5) * scale) as u32).min(height - 1);
+                let [luma, alpha] = image.get_pixel(px, py).0;
+                solid.push(luma < DARK_THRESHOLD && alpha >= OPAQUE_THRESHOLD);
+            }
+        }
+        Ok(Self { columns, rows, solid })
+    }
+
+    fn is_solid_cell(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.columns && (y as usize) < self.rows && self.solid[y as usize * self.columns + x as usize]
+    }
+
+    /// Size of one cell in meters, when stretched over a tank of `world_size`.
+    fn cell_size(&self, world_size: Vector2<f32>) -> Vector2<f32> {
+        Vector2::new(world_size.x / self.columns.max(1) as f32, world_size.y / self.rows.max(1) as f32)
+    }
+
+    /// How much of the grid is solid, from 0.0 to 1.0.
+    pub fn solid_fraction(&self) -> f32 {
+        if self.solid.is_empty() {
+            return 0.0;
+        }
+        self.solid.iter().filter(|&&solid| solid).count() as f32 / self.solid.len() as f32
+    }
+
+    /// Whether any solid cell lies within `margin` meters (roughly: it checks a square) of
+    /// `position`, in a tank of `world_size` centered on the origin.
+    pub fn is_solid_near(&self, position: Vector2<f32>, margin: f32, world_size: Vector2<f32>) -> bool {
+        let cell = self.cell_size(world_size);
+        let x = ((position.x + world_size.x / 2.0) / cell.x).floor() as i64;
+        let y = ((world_size.y / 2.0 - position.y) / cell.y).floor() as i64;
+        let reach_x = (margin / cell.x).ceil() as i64;
+        let reach_y = (margin / cell.y).ceil() as i64;
+        (y - reach_y..=y + reach_y).any(|cy| (x - reach_x..=x + reach_x).any(|cx| self.is_solid_cell(cx, cy)))
+    }
+
+    /// The boundary between solid and water, traced with marching squares over the cell
+    /// centers, as the vertices and segments of a polyline in world coordinates. Solid cells
+    /// on the edge of the image are closed off along the tank's walls.
+    pub fn outline(&self, world_size: Vector2<f32>) -> (Vec<Point2<f32>>, Vec<[u32; 2]>) {
+        let cell = self.cell_size(world_size);
+        let mut vertices = Vec::new();
+        let mut segments = Vec::new();
+        // Each vertex sits halfway along an edge between two cell centers. Keyed by doubled
+        // grid coordinates so neighbouring squares share their vertices.
+        let mut vertex_at: HashMap<EdgePoint, u32> = HashMap::new();
+        let mut vertex = |key: EdgePoint| {
+            *vertex_at.entry(key).or_insert_with(|| {
+                vertices.push(Point2::new(
+                    -world_size.x / 2.0 + (key.0 as f32 / 2.0 + 0.5) * cell.x,
+                    world_size.y / 2.0 - (key.1 as f32 / 2.0 + 0.5) * cell.y,
+                ));
+                vertices.len() as u32 - 1
+            })
+        };
+
+        // Start one outside the grid so solid cells on its edge get closed off
+        for y in -1..self.rows as i64 {
+            for x in -1..self.columns as i64 {
+                let case = (self.is_solid_cell(x, y) as u8) << 3
+                    | (self.is_solid_cell(x + 1, y) as u8) << 2
+                    | (self.is_solid_cell(x + 1, y + 1) as u8) << 1
+                    | self.is_solid_cell(x, y + 1) as u8;
+                let top = (2 * x + 1, 2 * y);
+                let right = (2 * x + 2, 2 * y + 1);
+                let bottom = (2 * x + 1, 2 * y + 2);
+                let left = (2 * x, 2 * y + 1);
+                let edges: &[(EdgePoint, EdgePoint)] = match case {
+                    1 | 14 => &[(left, bottom)],
+                    2 | 13 => &[(bottom, right)],
+                    3 | 12 => &[(left, right)],
+                    4 | 11 => &[(top, right)],
+                    6 | 9 => &[(top, bottom)],
+                    7 | 8 => &[(left, top)],
+                    // Diagonal corners: keep the solid ones apart
+                    5 => &[(left, top), (bottom, right)],
+                    10 => &[(top, right), (left, bottom)],
+                    _ => &[],
+                };
+                for &(a, b) in edges {
+                    segments.push([vertex(a), vertex(b)]);
+                }
+            }
+        }
+        (vertices, segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_a_block_with_a_closed_loop() {
+        let silhouette = Silhouette::from_rows(&[
+            "....", //
+            ".##.",
+            ".##.",
+            "....",
+        ]);
+        let world_size = Vector2::new(4.0, 4.0);
+        let (vertices, segments) = silhouette.outline(world_size);
+
+        // One segment along each side plus the four cut corners
+        assert_eq!(segments.len(), 8);
+        assert_eq!(vertices.len(), 8);
+        // Closed: every vertex joins exactly two segments
+        for index in 0..vertices.len() as u32 {
+            assert_eq!(segments.iter().filter(|segment| segment.contains(&index)).count(), 2);
+        }
+        // Halfway between the solid and open cell centers
+        assert!(vertices.iter().all(|v| v.x.abs() <= 1.0 && v.y.abs() <= 1.0));
+        assert!(vertices.contains(&Point2::new(-1.0, 0.5)));
+
+        assert!(silhouette.is_solid_near(Vector2::zeros(), 0.0, world_size));
+        assert!(!silhouette.is_solid_near(Vector2::new(-1.5, 1.5), 0.0, world_size));
+        assert!(silhouette.is_solid_near(Vector2::new(-1.5, 1.5), 0.5, world_size));
+    }
+
+    #[test]
+    fn reads_dark_pixels_as_solid() {
+        let mut image = image::GrayAlphaImage::from_pixel(4, 2, image::LumaA([255, 255]));
+        image.put_pixel(0, 0, image::LumaA([0, 255]));
+        image.put_pixel(1, 0, image::LumaA([0, 0])); // Transparent: water
+        let mut bytes = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+
+        assert_eq!(Silhouette::from_image(&bytes).unwrap(), Silhouette::from_rows(&["#...", "...."]));
+        assert!(Silhouette::from_image(b"not an image").is_err());
+    }
+}
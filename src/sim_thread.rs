@@ -0,0 +1,593 @@
+use std::sync::{Arc, Mutex};
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::creature::CreatureId;
+use crate::creatures::CreatureData;
+use crate::flow::FlowSource;
+use crate::formation::Formation;
+use crate::gait::Gait;
+use crate::joint_profile::JointProfile;
+use crate::objectives::Objective;
+use crate::physics_presets::PhysicsPreset;
+use crate::player::PlayerInput;
+use crate::simulation::{Simulation, WorldSnapshot, TIMESTEP};
+
+/// Most ticks the inline runner will run in one UI frame. Time beyond that is dropped rather
+/// than simulated with a bigger dt, which would destabilize the physics.
+const MAX_INLINE_TICKS_PER_FRAME: u32 = 4;
+
+/// Wall-clock seconds since some fixed point. std's `Instant` panics on wasm.
+fn now_seconds() -> f64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        EPOCH.get_or_init(std::time::Instant::now).elapsed().as_secs_f64()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() / 1000.0
+    }
+}
+
+/// Ticks once at the fixed timestep and tells the simulation how long that took, so it can
+/// shed work when it's falling behind.
+fn timed_tick(simulation: &mut Simulation) {
+    let start = now_seconds();
+    simulation.tick(TIMESTEP);
+    simulation.record_tick_time((now_seconds() - start) as f32);
+}
+
+/// Requests from the UI that change the simulation. Applied before the next tick,
+/// so the UI never needs direct access to the live world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SimCommand {
+    SetPhysicsPreset(PhysicsPreset),
+    SetVirulence(f32),
+    SetFoodAbundance(f32),
+    SetTemperatureOffset(f32),
+    /// Turn the auto-balancing director on or off.
+    SetDirector(bool),
+    /// Spawn or remove snakes to reach this many.
+    SetSnakeCount(usize),
+    /// Take control of a creature, or with `None` let it go.
+    Possess(Option<CreatureId>),
+    PlayerInput(PlayerInput),
+    AddObjective(Objective),
+    RemoveObjective(Objective),
+    /// Jot a note in the notebook, stamped with the tick it arrives on.
+    AddNote { text: String, creature: Option<CreatureId> },
+    /// Delete the note with this `Note::id`.
+    RemoveNote(u64),
+    /// Start or stop per-tick observations of a creature. Subscriptions are counted, so
+    /// each `Subscribe` needs its own `Unsubscribe`.
+    Subscribe(CreatureId),
+    Unsubscribe(CreatureId),
+    /// Retune how stiff a creature's joints are from head to tail.
+    SetJointProfile { creature: CreatureId, profile: JointProfile },
+    /// Give a creature a new swimming gait, e.g. one the gait tuner found.
+    SetGait { creature: CreatureId, gait: Gait },
+    /// Spawn up to `count` copies of a creature in a formation around `center`.
+    SpawnGroup { creature: Box<CreatureData>, count: usize, center: Vector2<f32>, formation: Formation },
+    /// Set one of a creature's tunable parameters by name.
+    SetTunable { creature: CreatureId, name: String, value: f32 },
+    AddFlowSource(FlowSource),
+    ClearFlowSources,
+    SetTurbulence(f32),
+    /// Remove every creature and egg.
+    ClearAll,
+    /// Remove every creature and egg of the species with this `Creature::type_name`.
+    ClearSpecies(String),
+    /// Rebuild the tank from the scenario it started as.
+    ResetToScenario,
+    /// Check the world's health and publish the report in the snapshot.
+    RunSelfTest,
+}
+
+impl Simulation {
+    pub fn apply_command(&mut self, command: SimCommand) {
+        self.record_command(&command);
+        match command {
+            SimCommand::SetPhysicsPreset(preset) => self.set_physics_preset(preset),
+            SimCommand::SetVirulence(virulence) => self.set_virulence(virulence),
+            SimCommand::SetFoodAbundance(abundance) => self.set_food_abundance(abundance),
+            SimCommand::SetTemperatureOffset(offset) => self.set_temperature_offset(offset),
+            SimCommand::SetDirector(enabled) => self.set_director(enabled),
+            SimCommand::SetSnakeCount(count) => self.set_snake_count(count),
+            SimCommand::Possess(id) => {
+                self.possess(id);
+            }
+            SimCommand::PlayerInput(input) => self.set_player_input(input),
+            SimCommand::AddObjective(objective) => self.add_objective(objective),
+            SimCommand::RemoveObjective(objective) => self.remove_objective(&objective),
+            SimCommand::AddNote { text, creature } => self.add_note(&text, creature),
+            SimCommand::RemoveNote(id) => self.remove_note(id),
+            SimCommand::Subscribe(id) => self.subscribe(id),
+            SimCommand::Unsubscribe(id) => self.unsubscribe(id),
+            SimCommand::SetJointProfile { creature, profile } => {
+                self.set_joint_profile(creature, profile);
+            }
+            SimCommand::SetGait { creature, gait } => {
+                self.set_gait(creature, gait);
+            }
+            SimCommand::SpawnGroup { creature, count, center, formation } => {
+                self.spawn_group(*creature, count, center, formation);
+            }
+            SimCommand::SetTunable { creature, name, value } => {
+                self.set_tunable(creature, &name, value);
+            }
+            SimCommand::AddFlowSource(source) => self.add_flow_source(source),
+            SimCommand::ClearFlowSources => self.clear_flow_sources(),
+            SimCommand::SetTurbulence(turbulence) => self.set_turbulence(turbulence),
+            SimCommand::ClearAll => {
+                self.clear_all();
+            }
+            SimCommand::ClearSpecies(type_name) => {
+                self.clear_species(&type_name);
+            }
+            SimCommand::ResetToScenario => self.reset(),
+            SimCommand::RunSelfTest => {
+                self.run_self_test();
+            }
+        }
+    }
+}
+
+/// Back buffer of a double-buffered snapshot exchange. The simulation writes the newest
+/// snapshot here and the renderer takes it, keeping its previous one (the front buffer)
+/// when nothing new has arrived.
+#[derive(Default, Clone)]
+pub struct SnapshotBuffer {
+    latest: Arc<Mutex<Option<WorldSnapshot>>>,
+}
+
+impl SnapshotBuffer {
+    pub fn publish(&self, snapshot: WorldSnapshot) {
+        *self.latest.lock().unwrap() = Some(snapshot);
+    }
+
+    pub fn take(&self) -> Option<WorldSnapshot> {
+        self.latest.lock().unwrap().take()
+    }
+
+    /// Whether the renderer has taken the last snapshot published, so it's worth making another.
+    pub fn is_taken(&self) -> bool {
+        self.latest.lock().unwrap().is_none()
+    }
+}
+
+/// Owns the simulation and decides where it runs.
+/// Native builds tick on a dedicated thread and wasm builds in a web worker. If the worker
+/// can't be started, wasm falls back to ticking inline from the UI.
+pub enum SimulationRunner {
+    Inline {
+        simulation: Box<Simulation>,
+        // Frame time not yet simulated, less than one timestep after each poll
+        unsimulated: f32,
+    },
+    #[cfg(not(target_arch = "wasm32"))]
+    Threaded(threaded::ThreadedSimulation),
+    #[cfg(target_arch = "wasm32")]
+    Worker(worker::WorkerSimulation),
+}
+
+impl SimulationRunner {
+    /// Starts the simulation using the best runner for this platform.
+    pub fn start(simulation: Simulation) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            SimulationRunner::Threaded(threaded::ThreadedSimulation::spawn(simulation))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            match worker::WorkerSimulation::spawn(&simulation) {
+                Ok(worker) => SimulationRunner::Worker(worker),
+                Err(err) => {
+                    tracing::warn!("Couldn't start the simulation worker, ticking on the main thread: {:?}", err);
+                    SimulationRunner::inline(simulation)
+                }
+            }
+        }
+    }
+
+    /// Runs the simulation on the calling thread, ticked from `poll`.
+    pub fn inline(simulation: Simulation) -> Self {
+        SimulationRunner::Inline {
+            simulation: Box::new(simulation),
+            unsimulated: 0.0,
+        }
+    }
+
+    pub fn send(&mut self, command: SimCommand) {
+        match self {
+            SimulationRunner::Inline { simulation, .. } => simulation.apply_command(command),
+            #[cfg(not(target_arch = "wasm32"))]
+            SimulationRunner::Threaded(threaded) => threaded.send(command),
+            #[cfg(target_arch = "wasm32")]
+            SimulationRunner::Worker(worker) => worker.send(command),
+        }
+    }
+
+    /// Called once per UI frame. Advances the inline simulation by `dt` in fixed timesteps,
+    /// and returns the newest snapshot if one was produced since the last call.
+    pub fn poll(&mut self, dt: f32) -> Option<WorldSnapshot> {
+        match self {
+            SimulationRunner::Inline { simulation, unsimulated } => {
+                *unsimulated = (*unsimulated + dt).min(TIMESTEP * MAX_INLINE_TICKS_PER_FRAME as f32);
+                let mut ticked = false;
+                while *unsimulated >= TIMESTEP {
+                    timed_tick(simulation);
+                    *unsimulated -= TIMESTEP;
+                    ticked = true;
+                }
+                ticked.then(|| simulation.snapshot())
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            SimulationRunner::Threaded(threaded) => threaded.snapshots.take(),
+            #[cfg(target_arch = "wasm32")]
+            SimulationRunner::Worker(worker) => {
+                if worker.has_failed() {
+                    tracing::warn!("Simulation worker failed, ticking on the main thread instead");
+                    *self = SimulationRunner::inline(worker.initial_simulation());
+                    return self.poll(dt);
+                }
+                worker.take_snapshot()
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod threaded {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    use super::{timed_tick, SimCommand, SnapshotBuffer};
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    /// A simulation ticking at a fixed rate on its own thread.
+    /// Stops and joins the thread when dropped.
+    pub struct ThreadedSimulation {
+        pub(super) snapshots: SnapshotBuffer,
+        commands: Sender<SimCommand>,
+        running: Arc<AtomicBool>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl ThreadedSimulation {
+        pub fn spawn(mut simulation: Simulation) -> Self {
+            let snapshots = SnapshotBuffer::default();
+            let (commands, command_receiver) = mpsc::channel();
+            let running = Arc::new(AtomicBool::new(true));
+
+            // Publish the initial state so the first frame has something to draw
+            snapshots.publish(simulation.snapshot());
+
+            let thread_snapshots = snapshots.clone();
+            let thread_running = running.clone();
+            let handle = thread::Builder::new()
+                .name("simulation".to_string())
+                .spawn(move || run(simulation, command_receiver, thread_snapshots, thread_running))
+                .expect("failed to spawn simulation thread");
+
+            Self {
+                snapshots,
+                commands,
+                running,
+                handle: Some(handle),
+            }
+        }
+
+        pub fn send(&self, command: SimCommand) {
+            // The thread only goes away when we're dropped, so a failed send can be ignored
+            let _ = self.commands.send(command);
+        }
+    }
+
+    impl Drop for ThreadedSimulation {
+        fn drop(&mut self) {
+            self.running.store(false, Ordering::Relaxed);
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+
+    fn run(
+        mut simulation: Simulation,
+        commands: Receiver<SimCommand>,
+        snapshots: SnapshotBuffer,
+        running: Arc<AtomicBool>,
+    ) {
+        let tick_duration = Duration::from_secs_f32(TIMESTEP);
+        let mut next_tick = Instant::now();
+
+        while running.load(Ordering::Relaxed) {
+            for command in commands.try_iter() {
+                simulation.apply_command(command);
+            }
+
+            timed_tick(&mut simulation);
+            // Copying the world is expensive, so only do it once the renderer has taken the
+            // last copy. It takes one per frame, which is less often than we tick.
+            if snapshots.is_taken() {
+                snapshots.publish(simulation.snapshot());
+            }
+
+            // Hold a steady tick rate. If we fall behind, don't try to catch up in a burst.
+            next_tick += tick_duration;
+            let now = Instant::now();
+            if next_tick > now {
+                thread::sleep(next_tick - now);
+            } else {
+                next_tick = now;
+            }
+        }
+    }
+}
+
+/// Runs the simulation in a web worker. The worker loads this same crate via `worker.js`, is
+/// handed the encoded simulation, and posts an encoded snapshot back whenever the page has
+/// taken the last one, so at most one per frame.
+#[cfg(target_arch = "wasm32")]
+pub mod worker {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Serialize};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use web_sys::{DedicatedWorkerGlobalScope, ErrorEvent, Event, MessageEvent, Worker, WorkerOptions, WorkerType};
+
+    use super::{timed_tick, SimCommand};
+    use crate::simulation::{PanelSnapshot, Simulation, WorldSnapshot, TIMESTEP};
+
+    /// Module script that loads the wasm module inside the worker. Served next to index.html.
+    const WORKER_SCRIPT: &str = "./worker.js";
+
+    /// Messages from the page to the worker.
+    #[derive(Serialize, Deserialize)]
+    enum ToWorker {
+        /// An encoded `Simulation` to start ticking.
+        Start(Vec<u8>),
+        Command(SimCommand),
+        /// The page took the last snapshot and is ready for another.
+        SnapshotTaken,
+    }
+
+    /// A snapshot from the worker to the page. The panels only come along when they've
+    /// changed since the last one sent; otherwise the page keeps the ones it has.
+    type FromWorker = (WorldSnapshot, Option<PanelSnapshot>);
+
+    fn message_bytes(event: &MessageEvent) -> Vec<u8> {
+        js_sys::Uint8Array::new(&event.data()).to_vec()
+    }
+
+    /// The page's handle on the worker. Terminates the worker when dropped.
+    pub struct WorkerSimulation {
+        worker: Worker,
+        latest: Rc<RefCell<Option<WorldSnapshot>>>,
+        failed: Rc<Cell<bool>>,
+        // Kept so we can fall back to ticking inline if the worker dies
+        initial_state: Vec<u8>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_error: Closure<dyn FnMut(Event)>,
+    }
+
+    impl WorkerSimulation {
+        pub fn spawn(simulation: &Simulation) -> Result<Self, JsValue> {
+            let initial_state = simulation.encode().map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+            let options = WorkerOptions::new();
+            options.set_type(WorkerType::Module);
+            let worker = Worker::new_with_options(WORKER_SCRIPT, &options)?;
+
+            let latest = Rc::new(RefCell::new(None));
+            let on_message = {
+                let latest = latest.clone();
+                let mut panels = Arc::default();
+                Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                    match bincode::deserialize::<FromWorker>(&message_bytes(&event)) {
+                        Ok((mut snapshot, changed)) => {
+                            if let Some(changed) = changed {
+                                panels = Arc::new(changed);
+                            }
+                            snapshot.panels = panels.clone();
+                            *latest.borrow_mut() = Some(snapshot);
+                        }
+                        Err(err) => tracing::error!("Dropping undecodable snapshot from the worker: {}", err),
+                    }
+                })
+            };
+            worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            // Loading errors (e.g. worker.js missing) only show up asynchronously
+            let failed = Rc::new(Cell::new(false));
+            let on_error = {
+                let failed = failed.clone();
+                Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+                    // A script that fails to load reports a plain Event, not an ErrorEvent
+                    let message = event.dyn_ref::<ErrorEvent>().map(ErrorEvent::message);
+                    tracing::error!("Simulation worker error: {}", message.as_deref().unwrap_or("failed to load"));
+                    failed.set(true);
+                })
+            };
+            worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+            let this = Self {
+                worker,
+                latest,
+                failed,
+                initial_state,
+                _on_message: on_message,
+                _on_error: on_error,
+            };
+            this.post(&ToWorker::Start(this.initial_state.clone()))?;
+            Ok(this)
+        }
+
+        pub fn send(&self, command: SimCommand) {
+            if let Err(err) = self.post(&ToWorker::Command(command)) {
+                tracing::error!("Failed to send command to the simulation worker: {:?}", err);
+            }
+        }
+
+        pub fn take_snapshot(&self) -> Option<WorldSnapshot> {
+            let snapshot = self.latest.borrow_mut().take();
+            if snapshot.is_some() {
+                if let Err(err) = self.post(&ToWorker::SnapshotTaken) {
+                    tracing::error!("Failed to ask the simulation worker for another snapshot: {:?}", err);
+                }
+            }
+            snapshot
+        }
+
+        pub fn has_failed(&self) -> bool {
+            self.failed.get()
+        }
+
+        /// The simulation as it was handed to the worker.
+        pub fn initial_simulation(&self) -> Simulation {
+            Simulation::decode(&self.initial_state).unwrap_or_default()
+        }
+
+        fn post(&self, message: &ToWorker) -> Result<(), JsValue> {
+            let bytes = bincode::serialize(message).map_err(|err| JsValue::from_str(&err.to_string()))?;
+            self.worker.post_message(&js_sys::Uint8Array::from(bytes.as_slice()))
+        }
+    }
+
+    impl Drop for WorkerSimulation {
+        fn drop(&mut self) {
+            self.worker.terminate();
+        }
+    }
+
+    /// Entry point inside the worker, called by worker.js once the module has loaded.
+    /// Waits for the page to send a simulation, then ticks it at a fixed rate.
+    pub fn worker_main() -> Result<(), JsValue> {
+        let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+        let simulation: Rc<RefCell<Option<Simulation>>> = Rc::new(RefCell::new(None));
+        // Whether the page has taken the last snapshot posted. Snapshots it hasn't asked for
+        // would only be decoded and thrown away.
+        let page_ready = Rc::new(Cell::new(true));
+
+        let on_message = {
+            let simulation = simulation.clone();
+            let page_ready = page_ready.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                match bincode::deserialize::<ToWorker>(&message_bytes(&event)) {
+                    Ok(ToWorker::Start(state)) => match Simulation::decode(&state) {
+                        Ok(started) => *simulation.borrow_mut() = Some(started),
+                        Err(err) => tracing::error!("Couldn't decode the simulation sent to the worker: {}", err),
+                    },
+                    Ok(ToWorker::Command(command)) => {
+                        if let Some(simulation) = simulation.borrow_mut().as_mut() {
+                            simulation.apply_command(command);
+                        }
+                    }
+                    Ok(ToWorker::SnapshotTaken) => page_ready.set(true),
+                    Err(err) => tracing::error!("Dropping undecodable message from the page: {}", err),
+                }
+            })
+        };
+        scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget(); // Lives as long as the worker
+
+        let tick = {
+            let scope = scope.clone();
+            // The panels the page was last sent
+            let mut sent_panels: Option<Arc<PanelSnapshot>> = None;
+            Closure::<dyn FnMut()>::new(move || {
+                let mut simulation = simulation.borrow_mut();
+                let Some(simulation) = simulation.as_mut() else {
+                    return;
+                };
+                timed_tick(simulation);
+                if !page_ready.get() {
+                    return;
+                }
+
+                let snapshot = simulation.snapshot();
+                let unchanged = sent_panels.as_ref().is_some_and(|sent| Arc::ptr_eq(sent, &snapshot.panels));
+                let panels = (!unchanged).then_some(&*snapshot.panels);
+                match bincode::serialize(&(&snapshot, panels)) {
+                    Ok(bytes) => {
+                        // Transfer the buffer rather than copying it into the page
+                        let array = js_sys::Uint8Array::from(bytes.as_slice());
+                        let transfer = js_sys::Array::of1(&array.buffer());
+                        match scope.post_message_with_transfer(&array, &transfer) {
+                            Ok(()) => {
+                                page_ready.set(false);
+                                sent_panels = Some(snapshot.panels.clone());
+                            }
+                            Err(err) => tracing::error!("Failed to post snapshot: {:?}", err),
+                        }
+                    }
+                    Err(err) => tracing::error!("Failed to encode snapshot: {}", err),
+                }
+            })
+        };
+        scope.set_interval_with_callback_and_timeout_and_arguments_0(
+            tick.as_ref().unchecked_ref(),
+            (TIMESTEP * 1000.0) as i32,
+        )?;
+        tick.forget(); // Lives as long as the worker
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn threaded_simulation_publishes_advancing_snapshots() {
+        let mut runner = SimulationRunner::start(Simulation::default());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut latest_tick = 0;
+        while latest_tick < 3 && Instant::now() < deadline {
+            if let Some(snapshot) = runner.poll(0.0) {
+                latest_tick = snapshot.tick;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(latest_tick >= 3, "simulation thread only reached tick {}", latest_tick);
+    }
+
+    #[test]
+    fn inline_runner_ticks_at_the_fixed_rate() {
+        let mut runner = SimulationRunner::inline(Simulation::default());
+
+        // Less than a timestep: nothing to show yet
+        assert!(runner.poll(TIMESTEP * 0.5).is_none());
+        assert_eq!(runner.poll(TIMESTEP * 0.5).map(|snapshot| snapshot.tick), Some(1));
+
+        // A long stall is capped rather than simulated with a huge dt
+        let snapshot = runner.poll(10.0).unwrap();
+        assert_eq!(snapshot.tick, 1 + MAX_INLINE_TICKS_PER_FRAME as u64);
+    }
+
+    #[test]
+    fn commands_reach_the_simulation_thread() {
+        let mut runner = SimulationRunner::start(Simulation::default());
+        runner.send(SimCommand::SetPhysicsPreset(PhysicsPreset::Fast));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut preset = PhysicsPreset::default();
+        while preset != PhysicsPreset::Fast && Instant::now() < deadline {
+            if let Some(snapshot) = runner.poll(0.0) {
+                preset = snapshot.physics_preset;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(preset, PhysicsPreset::Fast);
+    }
+}
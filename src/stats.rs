@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::creature::Creature;
+
+/// Ticks between population samples. One a second at 60Hz.
+pub const SAMPLE_INTERVAL_TICKS: u64 = 60;
+/// Samples kept. Older ones are dropped, so the history covers the last 10 minutes.
+const MAX_SAMPLES: usize = 600;
+
+/// How many of each type of creature there were, sampled once a second.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PopulationHistory {
+    /// Creature type names, in the order their counts appear in each sample.
+    type_names: Vec<String>,
+    /// Simulated seconds and the count of each type at that time.
+    samples: VecDeque<(f32, Vec<u32>)>,
+}
+
+impl PopulationHistory {
+    /// Takes a sample if `tick` falls on the sampling interval.
+    pub fn record(&mut self, tick: u64, seconds: f32, creatures: &[Box<dyn Creature>]) {
+        if !tick.is_multiple_of(SAMPLE_INTERVAL_TICKS) {
+            return;
+        }
+        let mut counts = vec![0; self.type_names.len()];
+        for creature in creatures {
+            match self.type_names.iter().position(|name| name == creature.type_name()) {
+                Some(column) => counts[column] += 1,
+                None => {
+                    // A type we haven't seen before: a new column, zero in every earlier sample
+                    self.type_names.push(creature.type_name().to_string());
+                    for (_, earlier) in &mut self.samples {
+                        earlier.push(0);
+                    }
+                    counts.push(1);
+                }
+            }
+        }
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((seconds, counts));
+    }
+
+    pub fn type_names(&self) -> &[String] {
+        &self.type_names
+    }
+
+    /// `(seconds, count)` points for one type, oldest first.
+    pub fn series(&self, type_name: &str) -> Vec<(f32, u32)> {
+        let Some(column) = self.type_names.iter().position(|name| name == type_name) else { return Vec::new() };
+        self.samples.iter().map(|(seconds, counts)| (*seconds, counts[column])).collect()
+    }
+
+    /// The most recent count for a type, 0 if there's no sample of it.
+    pub fn latest(&self, type_name: &str) -> u32 {
+        self.series(type_name).last().map_or(0, |&(_, count)| count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creatures::plankton::Plankton;
+    use crate::creatures::snake::Snake;
+
+    #[test]
+    fn samples_each_type_on_the_interval() {
+        let mut creatures: Vec<Box<dyn Creature>> = vec![Box::new(Plankton::new(0.08)), Box::new(Plankton::new(0.08))];
+        let mut history = PopulationHistory::default();
+        history.record(0, 0.0, &creatures);
+        history.record(1, 0.1, &creatures); // Between samples: ignored
+        creatures.push(Box::new(Snake::new(0.1, 3, 0.3)));
+        history.record(SAMPLE_INTERVAL_TICKS, 1.0, &creatures);
+
+        assert_eq!(history.series("Plankton"), vec![(0.0, 2), (1.0, 2)]);
+        assert_eq!(history.series("Snake"), vec![(0.0, 0), (1.0, 1)]);
+        assert_eq!(history.latest("Snake"), 1);
+        assert_eq!(history.latest("Eel"), 0);
+
+        for sample in 2..MAX_SAMPLES as u64 + 10 {
+            history.record(sample * SAMPLE_INTERVAL_TICKS, sample as f32, &creatures);
+        }
+        assert_eq!(history.series("Plankton").len(), MAX_SAMPLES);
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use nalgebra::Vector2;
+
+use crate::physics_presets::PhysicsPreset;
+use crate::simulation::{Simulation, TIMESTEP, WORLD_HEIGHT_METERS, WORLD_WIDTH_METERS};
+
+/// Number of creatures in the standard tank. Stress tanks are scaled so each creature
+/// gets about as much room as it would there.
+const STANDARD_CREATURE_COUNT: usize = 23;
+/// One snake for every this many creatures, roughly the standard tank's mix.
+const CREATURES_PER_SNAKE: usize = 8;
+
+/// Ticks run before measuring, so spawn overlap has settled and caches are warm.
+pub const WARMUP_TICKS: u32 = 120;
+/// Ticks measured for the report. 10 seconds of simulated time.
+pub const MEASURED_TICKS: u32 = 600;
+
+/// Builds a tank with `creature_count` mixed creatures, sized in proportion to the count.
+pub fn stress_simulation(creature_count: usize) -> Simulation {
+    let scale = (creature_count as f32 / STANDARD_CREATURE_COUNT as f32).sqrt().max(1.0);
+    let mut sim = Simulation::new(WORLD_WIDTH_METERS * scale, WORLD_HEIGHT_METERS * scale);
+
+    let snake_count = creature_count / CREATURES_PER_SNAKE;
+    for _ in 0..snake_count {
+        // Snakes extend to the right of their spawn point, so leave room for the body
+        let position = sim.random_position(2.0) - Vector2::new(1.5, 0.0);
+        sim.spawn_snake(position);
+    }
+    for _ in snake_count..creature_count {
+        let position = sim.random_position(1.0);
+        sim.spawn_plankton(position);
+    }
+    sim
+}
+
+/// Timing results from a stress run.
+#[derive(Debug, Clone)]
+pub struct StressReport {
+    pub creature_count: usize,
+    pub preset: PhysicsPreset,
+    pub world_size: Vector2<f32>,
+    pub measured_ticks: u32,
+    pub total: Duration,
+    pub worst_tick: Duration,
+    /// How far apart joints' two anchors drifted, in meters, averaged over every joint and
+    /// measured tick. What a cheaper preset gives up.
+    pub mean_joint_error: f32,
+    pub worst_joint_error: f32,
+}
+
+impl StressReport {
+    /// Ticks per second the simulation could sustain, ignoring the real-time cap.
+    pub fn ticks_per_second(&self) -> f64 {
+        self.measured_ticks as f64 / self.total.as_secs_f64()
+    }
+
+    pub fn mean_tick(&self) -> Duration {
+        self.total / self.measured_ticks
+    }
+
+    /// Whether the worst tick still fit in the real-time budget of one timestep.
+    pub fn keeps_up(&self) -> bool {
+        self.worst_tick.as_secs_f32() <= TIMESTEP
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Stress test: {} creatures in a {:.1}m x {:.1}m tank with the {} preset, {} ticks after {} warmup",
+            self.creature_count,
+            self.world_size.x,
+            self.world_size.y,
+            self.preset.label(),
+            self.measured_ticks,
+            WARMUP_TICKS
+        )?;
+        writeln!(f, "  sustained tick rate: {:.1} ticks/s", self.ticks_per_second())?;
+        writeln!(f, "  mean tick time:      {:.2} ms", self.mean_tick().as_secs_f64() * 1000.0)?;
+        write!(
+            f,
+            "  worst tick time:     {:.2} ms ({} the {:.2} ms budget)",
+            self.worst_tick.as_secs_f64() * 1000.0,
+            if self.keeps_up() { "within" } else { "over" },
+            TIMESTEP * 1000.0
+        )?;
+        writeln!(f)?;
+        write!(
+            f,
+            "  joint anchor error:  {:.2} mm mean, {:.2} mm worst",
+            self.mean_joint_error * 1000.0,
+            self.worst_joint_error * 1000.0
+        )
+    }
+}
+
+/// How far apart each joint's two anchors are, in meters.
+fn joint_errors(sim: &Simulation) -> impl Iterator<Item = f32> + '_ {
+    sim.impulse_joint_set.iter().filter_map(|(_, joint)| {
+        let body1 = sim.rigid_body_set.get(joint.body1)?;
+        let body2 = sim.rigid_body_set.get(joint.body2)?;
+        Some(nalgebra::distance(&(body1.position() * joint.data.local_anchor1()), &(body2.position() * joint.data.local_anchor2())))
+    })
+}
+
+/// Runs `warmup_ticks` untimed, then times `measured_ticks` ticks of `sim`. Joint errors are
+/// checked between ticks, outside the timing.
+pub fn measure(sim: &mut Simulation, creature_count: usize, warmup_ticks: u32, measured_ticks: u32) -> StressReport {
+    for _ in 0..warmup_ticks {
+        sim.tick(TIMESTEP);
+    }
+
+    let mut total = Duration::ZERO;
+    let mut worst_tick = Duration::ZERO;
+    let (mut joint_error_sum, mut joint_samples, mut worst_joint_error) = (0.0, 0, 0.0_f32);
+    for _ in 0..measured_ticks {
+        let start = Instant::now();
+        sim.tick(TIMESTEP);
+        let elapsed = start.elapsed();
+        total += elapsed;
+        worst_tick = worst_tick.max(elapsed);
+
+        for error in joint_errors(sim) {
+            joint_error_sum += error;
+            joint_samples += 1;
+            worst_joint_error = worst_joint_error.max(error);
+        }
+    }
+
+    StressReport {
+        creature_count,
+        preset: sim.physics_preset(),
+        world_size: sim.world_size(),
+        measured_ticks,
+        total,
+        worst_tick,
+        mean_joint_error: if joint_samples == 0 { 0.0 } else { joint_error_sum / joint_samples as f32 },
+        worst_joint_error,
+    }
+}
+
+/// Builds a stress tank with `creature_count` creatures and measures it with the standard
+/// settings and `preset`.
+pub fn run(creature_count: usize, preset: PhysicsPreset) -> StressReport {
+    let mut sim = stress_simulation(creature_count);
+    sim.set_physics_preset(preset);
+    measure(&mut sim, creature_count, WARMUP_TICKS, MEASURED_TICKS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stress_tank_grows_with_creature_count() {
+        let small = stress_simulation(10);
+        let large = stress_simulation(230);
+        assert_eq!(small.world_size(), Vector2::new(WORLD_WIDTH_METERS, WORLD_HEIGHT_METERS));
+        assert!(large.world_size().x > small.world_size().x * 3.0);
+        assert_eq!(large.creatures.len(), 230);
+        let snakes = large.creatures.iter().filter(|c| c.type_name() == "Snake").count();
+        assert_eq!(snakes, 230 / CREATURES_PER_SNAKE);
+    }
+
+    #[test]
+    fn measure_reports_every_tick() {
+        let mut sim = stress_simulation(5);
+        sim.set_physics_preset(PhysicsPreset::Fast);
+        let report = measure(&mut sim, 5, 2, 10);
+        assert_eq!(report.measured_ticks, 10);
+        assert_eq!(report.preset, PhysicsPreset::Fast);
+        assert!(report.worst_joint_error >= report.mean_joint_error);
+        assert_eq!(sim.tick_count(), 12);
+        assert!(report.worst_tick >= report.mean_tick());
+        assert!(report.ticks_per_second() > 0.0);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::creature::CreatureId;
+use crate::simulation::{WorldSnapshot, TIMESTEP};
+
+/// One sentence on what's in the tank, for anyone who can't see the canvas (it is also the
+/// canvas's screen reader label).
+pub fn tank_summary(snapshot: &WorldSnapshot) -> String {
+    let mut kinds: Vec<(&str, usize)> = Vec::new();
+    for creature in &snapshot.creatures {
+        match kinds.iter_mut().find(|(name, _)| *name == creature.type_name()) {
+            Some((_, count)) => *count += 1,
+            None => kinds.push((creature.type_name(), 1)),
+        }
+    }
+    let mut parts: Vec<String> = kinds.iter().map(|(name, count)| plural(*count, &name.to_lowercase())).collect();
+    if parts.is_empty() {
+        parts.push("no creatures".to_string());
+    }
+    if !snapshot.eggs.is_empty() {
+        parts.push(plural(snapshot.eggs.len(), "egg"));
+    }
+    let infected = snapshot.creatures.iter().filter(|creature| creature.attributes().infection.is_infected()).count();
+    if infected > 0 {
+        parts.push(format!("{} infected", infected));
+    }
+
+    let seconds = (snapshot.tick as f32 * TIMESTEP) as u64;
+    format!("Tank at {}:{:02}: {}.", seconds / 60, seconds % 60, parts.join(", "))
+}
+
+/// A sentence on one creature: what it is, what it's doing, how it's faring and where it is.
+pub fn creature_summary(snapshot: &WorldSnapshot, id: CreatureId) -> Option<String> {
+    let creature = snapshot.creatures.iter().find(|creature| creature.id() == id)?;
+    let attributes = creature.attributes();
+    let position = creature
+        .get_rigid_body_handles()
+        .first()
+        .and_then(|&handle| snapshot.rigid_body_set.get(handle))
+        .map(|body| *body.translation())?;
+
+    let half = snapshot.world_size / 2.0;
+    let horizontal = if position.x < -half.x / 3.0 { "left" } else if position.x > half.x / 3.0 { "right" } else { "middle" };
+    let vertical = if position.y < -half.y / 3.0 { "bottom" } else if position.y > half.y / 3.0 { "top" } else { "middle" };
+    let area = if horizontal == vertical { "middle".to_string() } else { format!("{} {}", vertical, horizontal) };
+
+    let mut summary = format!(
+        "{} {}, {:?}, energy {:.0}%, satiety {:.0}%, in the {} of the tank",
+        creature.type_name(),
+        id,
+        creature.current_state(),
+        attributes.energy / attributes.max_energy * 100.0,
+        attributes.satiety / attributes.max_satiety * 100.0,
+        area
+    );
+    if attributes.infection.is_infected() {
+        summary.push_str(", infected");
+    }
+    if snapshot.player == Some(id) {
+        summary.push_str(", under your control");
+    }
+    summary.push('.');
+    Some(summary)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 || noun.ends_with('s') || noun == "plankton" {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector2;
+
+    use crate::simulation::Simulation;
+
+    #[test]
+    fn describes_the_tank_and_its_creatures() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let snake = sim.spawn_snake(Vector2::new(-8.0, 6.0));
+        let plankton = sim.spawn_plankton(Vector2::zeros());
+        sim.spawn_plankton(Vector2::new(2.0, 0.0));
+        sim.infect(plankton);
+        sim.possess(Some(snake));
+        let snapshot = sim.snapshot();
+
+        assert_eq!(tank_summary(&snapshot), "Tank at 0:00: 1 snake, 2 plankton, 1 infected.");
+        let description = creature_summary(&snapshot, snake).unwrap();
+        assert!(description.starts_with("Snake 0, "), "{}", description);
+        assert!(description.contains("energy 100%"), "{}", description);
+        assert!(description.ends_with("in the top left of the tank, under your control."), "{}", description);
+        assert!(creature_summary(&snapshot, plankton).unwrap().contains("in the middle of the tank, infected"));
+        assert_eq!(creature_summary(&snapshot, CreatureId(99)), None);
+
+        assert_eq!(tank_summary(&Simulation::new(20.0, 16.0).snapshot()), "Tank at 0:00: no creatures.");
+    }
+}
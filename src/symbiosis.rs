@@ -0,0 +1,135 @@
+use nalgebra::Vector2;
+use rapier2d::prelude::*;
+
+use crate::creature::{Creature, CreatureState};
+
+/// Tag marking a creature as a cleaner.
+pub const CLEANER_TAG: &str = "cleaner";
+/// Parasite load a host picks up per second.
+const PARASITES_PER_SECOND: f32 = 0.5;
+/// Energy a host loses per second for each unit of parasite load.
+const ENERGY_DRAIN_PER_PARASITE: f32 = 0.01;
+/// How close (in meters, center to center) a cleaner must be to one of a host's segments.
+const CLEANING_RANGE: f32 = 0.4;
+/// Parasite load one cleaner removes per second.
+const CLEANING_RATE: f32 = 4.0;
+/// Hosts moving slower than this (m/s) count as idle even when not resting.
+const IDLE_SPEED: f32 = 0.15;
+/// A host must be at least this many times the cleaner's size.
+const MIN_HOST_SIZE_RATIO: f32 = 4.0;
+/// What each unit of parasites removed is worth to each side.
+const HOST_ENERGY_PER_PARASITE: f32 = 0.5;
+const CLEANER_SATIETY_PER_PARASITE: f32 = 1.0;
+
+/// Parasites build up on everything that isn't a cleaner and slowly sap its energy. Cleaners
+/// pick them off larger creatures idling nearby, so wherever a cleaner hangs about is a
+/// cleaning station: the host gets its energy back and the cleaner gets fed.
+pub fn update(creatures: &mut [Box<dyn Creature>], rigid_body_set: &RigidBodySet, dt: f32) {
+    for creature in creatures.iter_mut() {
+        let attributes = creature.attributes_mut();
+        if attributes.self_tags.iter().any(|tag| tag == CLEANER_TAG) {
+            continue;
+        }
+        attributes.gain_parasites(PARASITES_PER_SECOND * dt);
+        let drain = attributes.parasite_load * ENERGY_DRAIN_PER_PARASITE * dt;
+        attributes.consume_energy(drain);
+    }
+
+    let idle_hosts: Vec<usize> = (0..creatures.len()).filter(|&i| is_idle(creatures[i].as_ref(), rigid_body_set)).collect();
+    if idle_hosts.is_empty() {
+        return;
+    }
+
+    for cleaner in 0..creatures.len() {
+        if !creatures[cleaner].attributes().self_tags.iter().any(|tag| tag == CLEANER_TAG) {
+            continue;
+        }
+        let Some(position) = primary_position(creatures[cleaner].as_ref(), rigid_body_set) else { continue };
+        let cleaner_size = creatures[cleaner].attributes().size;
+
+        // Clean the nearest idle, infested host in reach
+        let host = idle_hosts
+            .iter()
+            .copied()
+            .filter(|&host| host != cleaner)
+            .filter(|&host| {
+                let attributes = creatures[host].attributes();
+                attributes.parasite_load > 0.0 && attributes.size >= cleaner_size * MIN_HOST_SIZE_RATIO
+            })
+            .filter_map(|host| Some((host, distance_to(creatures[host].as_ref(), position, rigid_body_set)?)))
+            .filter(|&(_, distance)| distance <= CLEANING_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((host, _)) = host else { continue };
+
+        let removed = creatures[host].attributes_mut().remove_parasites(CLEANING_RATE * dt);
+        let host_attributes = creatures[host].attributes_mut();
+        host_attributes.energy = (host_attributes.energy + removed * HOST_ENERGY_PER_PARASITE).min(host_attributes.max_energy);
+        creatures[cleaner].attributes_mut().gain_satiety(removed * CLEANER_SATIETY_PER_PARASITE);
+    }
+}
+
+fn is_idle(creature: &dyn Creature, rigid_body_set: &RigidBodySet) -> bool {
+    if matches!(creature.current_state(), CreatureState::Resting | CreatureState::Idle) {
+        return true;
+    }
+    creature
+        .get_rigid_body_handles()
+        .first()
+        .and_then(|&handle| rigid_body_set.get(handle))
+        .is_some_and(|body| body.linvel().norm() < IDLE_SPEED)
+}
+
+fn primary_position(creature: &dyn Creature, rigid_body_set: &RigidBodySet) -> Option<Vector2<f32>> {
+    let body = rigid_body_set.get(*creature.get_rigid_body_handles().first()?)?;
+    Some(*body.translation())
+}
+
+/// Distance from `point` to the nearest of the creature's segments.
+fn distance_to(creature: &dyn Creature, point: Vector2<f32>, rigid_body_set: &RigidBodySet) -> Option<f32> {
+    creature
+        .get_rigid_body_handles()
+        .iter()
+        .filter_map(|&handle| rigid_body_set.get(handle))
+        .map(|body| (body.translation() - point).norm())
+        .min_by(f32::total_cmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Simulation, TIMESTEP};
+
+    #[test]
+    fn cleaners_relieve_idle_hosts() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        let snake = sim.spawn_snake(Vector2::zeros());
+        let head = *sim.rigid_body_set[sim.creatures[0].get_rigid_body_handles()[0]].translation();
+        let plankton = sim.spawn_plankton(head + Vector2::new(0.0, 0.25));
+
+        let find = |sim: &Simulation, id| sim.creatures.iter().position(|c| c.id() == id).unwrap();
+        let (s, p) = (find(&sim, snake), find(&sim, plankton));
+        sim.creatures[s].attributes_mut().parasite_load = 20.0;
+        sim.creatures[s].attributes_mut().energy = 50.0;
+        sim.creatures[p].attributes_mut().satiety = 10.0;
+
+        update(&mut sim.creatures, &sim.rigid_body_set, TIMESTEP);
+
+        let host = sim.creatures[s].attributes();
+        assert!(host.parasite_load < 20.0);
+        assert!(host.energy > 50.0);
+        assert!(sim.creatures[p].attributes().satiety > 10.0);
+        // Cleaners don't carry parasites themselves
+        assert_eq!(sim.creatures[p].attributes().parasite_load, 0.0);
+    }
+
+    #[test]
+    fn parasites_build_up_away_from_cleaners() {
+        let mut sim = Simulation::new(20.0, 16.0);
+        sim.spawn_snake(Vector2::zeros());
+        for _ in 0..60 {
+            sim.tick(TIMESTEP);
+        }
+        let load = sim.creatures[0].attributes().parasite_load;
+        assert!((load - PARASITES_PER_SECOND).abs() < 0.05, "{}", load);
+    }
+}
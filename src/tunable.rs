@@ -0,0 +1,71 @@
+/// A named number a creature exposes for live tuning, shown as a slider in the inspector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tunable {
+    pub name: &'static str,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Registers one field of a species as tunable. A species lists its params in a `const`
+/// (see `param!`) and hands them to `list` and `set` from its `Creature` impl, so a new
+/// tunable is one line.
+pub struct Param<T> {
+    pub name: &'static str,
+    pub min: f32,
+    pub max: f32,
+    pub get: fn(&T) -> f32,
+    pub set: fn(&mut T, f32),
+}
+
+/// `param!("Forward drag", 0.0, 20.0, forward_drag)` makes a `Param` reading and writing
+/// that field, which may be a path like `gait.amplitude`.
+macro_rules! param {
+    ($name:literal, $min:expr, $max:expr, $($field:ident).+) => {
+        $crate::tunable::Param { name: $name, min: $min, max: $max, get: |creature| creature.$($field).+, set: |creature, value| creature.$($field).+ = value }
+    };
+}
+pub(crate) use param;
+
+/// The current value of each param of `creature`.
+pub fn list<T>(params: &[Param<T>], creature: &T) -> Vec<Tunable> {
+    params.iter().map(|param| Tunable { name: param.name, value: (param.get)(creature), min: param.min, max: param.max }).collect()
+}
+
+/// Sets the param called `name`, clamped to its range. Returns false if there's none.
+pub fn set<T>(params: &[Param<T>], creature: &mut T, name: &str, value: f32) -> bool {
+    let Some(param) = params.iter().find(|param| param.name == name) else { return false };
+    (param.set)(creature, value.clamp(param.min, param.max));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::behavior::Behavior;
+    use crate::creatures::plankton::Plankton;
+    use crate::creatures::snake::Snake;
+    use crate::creature::Creature;
+
+    #[test]
+    fn every_species_exposes_its_parameters_by_name() {
+        let species: [Box<dyn Creature>; 2] = [Box::new(Snake::new(0.1, 3, 0.3)), Box::new(Plankton::new(0.1))];
+        for mut creature in species {
+            let tunables = creature.tunables();
+            assert!(!tunables.is_empty(), "{}", creature.type_name());
+            for tunable in &tunables {
+                assert!(tunable.min <= tunable.value && tunable.value <= tunable.max, "{:?}", tunable);
+                assert!(creature.set_tunable(tunable.name, tunable.max + 1.0));
+            }
+            // Set to the top of the range, not past it
+            assert!(creature.tunables().iter().all(|tunable| tunable.value == tunable.max));
+            assert!(!creature.set_tunable("No such thing", 1.0));
+        }
+
+        let mut snake = Snake::new(0.1, 3, 0.3);
+        snake.set_tunable("Wiggle amplitude", 1.5);
+        assert_eq!(snake.gait().unwrap().amplitude, 1.5);
+        let mut plankton = Plankton::new(0.1);
+        plankton.set_tunable("Cohesion", 0.4);
+        assert!(matches!(plankton.behavior(), Some(Behavior::Plankton(weights)) if weights.cohesion == 0.4));
+    }
+}
@@ -0,0 +1,105 @@
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// The wall or piece of scenery nearest some point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallContact {
+    /// Closest point on the wall.
+    pub point: Vector2<f32>,
+    /// Unit vector from the wall out into the water, toward the point it was found from.
+    pub normal: Vector2<f32>,
+    pub distance: f32,
+}
+
+impl WallContact {
+    /// The contact seen from `from`, given the closest point on the wall to it. `None` if
+    /// they're the same point, i.e. `from` is in the wall.
+    pub fn between(from: Vector2<f32>, point: Vector2<f32>) -> Option<Self> {
+        let offset = from - point;
+        let distance = offset.norm();
+        let normal = offset.try_normalize(1e-6)?;
+        Some(Self { point, normal, distance })
+    }
+}
+
+/// Which side a wall-follower keeps the wall on. Holding to one side is what gets it round
+/// corners: whichever wall it meets next, it turns the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WallSide {
+    Left,
+    Right,
+}
+
+impl WallSide {
+    /// The side that has it carrying on along `wall` the way closest to `heading`.
+    pub fn for_heading(wall: &WallContact, heading: Vector2<f32>) -> Self {
+        if WallSide::Left.along(wall).dot(&heading) >= 0.0 { WallSide::Left } else { WallSide::Right }
+    }
+
+    /// Direction along `wall` with the wall on this side.
+    fn along(self, wall: &WallContact) -> Vector2<f32> {
+        match self {
+            WallSide::Left => Vector2::new(-wall.normal.y, wall.normal.x),
+            WallSide::Right => Vector2::new(wall.normal.y, -wall.normal.x),
+        }
+    }
+}
+
+/// Swimming along a wall at a set distance from it, for creatures that keep to the edges
+/// (thigmotaxis) or need a way out of a corner: following one wall into a corner turns
+/// them along the next.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WallFollowing {
+    /// How far from the wall to keep, in meters.
+    pub distance: f32,
+    /// How close a wall has to be, in meters, before it's worth following.
+    pub range: f32,
+}
+
+impl WallFollowing {
+    /// Unit direction to swim in: along the wall with it on `side`, bent away from it when
+    /// too close and toward it when too far.
+    pub fn steering(&self, wall: &WallContact, side: WallSide) -> Vector2<f32> {
+        let offset = ((self.distance - wall.distance) / self.distance).clamp(-1.0, 1.0);
+        (side.along(wall) + wall.normal * offset).normalize()
+    }
+
+    /// Whether `wall` is in the way of going in `direction`: it's in range, and `direction`
+    /// leads into it. Then it's time to follow the wall instead.
+    pub fn blocks(&self, wall: &WallContact, direction: Vector2<f32>) -> bool {
+        wall.distance <= self.range && direction.dot(&wall.normal) < 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_a_wall_at_its_distance_and_turns_at_corners() {
+        let follow = WallFollowing { distance: 0.5, range: 1.5 };
+        let left_wall = |x: f32, y: f32| WallContact::between(Vector2::new(x, y), Vector2::new(-10.0, y)).unwrap();
+        let up = Vector2::new(0.0, 1.0);
+
+        // At the right distance it goes straight along, whichever way it was heading
+        let side = WallSide::for_heading(&left_wall(-9.5, 0.0), up);
+        assert_eq!(side, WallSide::Left);
+        assert!((follow.steering(&left_wall(-9.5, 0.0), side) - up).norm() < 1e-6);
+        let down = WallSide::for_heading(&left_wall(-9.5, 0.0), Vector2::new(0.3, -1.0));
+        assert!((follow.steering(&left_wall(-9.5, 0.0), down) + up).norm() < 1e-6);
+        // Too close edges out, too far edges in
+        assert!(follow.steering(&left_wall(-9.9, 0.0), side).x > 0.0);
+        assert!(follow.steering(&left_wall(-8.0, 0.0), side).x < 0.0);
+
+        // Up into the top-left corner, the ceiling takes over and, keeping it on the same
+        // side, it turns right and out
+        let ceiling = WallContact::between(Vector2::new(-9.5, 7.6), Vector2::new(-9.5, 8.0)).unwrap();
+        assert!(follow.steering(&ceiling, side).x > 0.9);
+
+        // Only a wall in range, and only going toward it, gets in the way
+        assert!(follow.blocks(&left_wall(-9.0, 0.0), Vector2::new(-2.0, 1.0)));
+        assert!(!follow.blocks(&left_wall(-9.0, 0.0), Vector2::new(2.0, 1.0)));
+        assert!(!follow.blocks(&left_wall(-5.0, 0.0), Vector2::new(-6.0, 0.0)));
+        assert!(WallContact::between(Vector2::new(-10.0, 3.0), Vector2::new(-10.0, 3.0)).is_none());
+    }
+}
@@ -0,0 +1,13 @@
+// Runs the simulation off the main thread. Started by `SimulationRunner` in wasm builds.
+import init, { worker_main } from './pkg/softies.js';
+
+// The page may post before the wasm module has loaded, so hold on to early messages.
+const pending = [];
+self.onmessage = (event) => pending.push(event);
+
+await init();
+worker_main();
+
+for (const event of pending) {
+    self.onmessage(event);
+}